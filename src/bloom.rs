@@ -0,0 +1,105 @@
+use std::collections::hash_map::RandomState;
+use std::f64::consts::LN_2;
+use std::hash::{BuildHasher, Hasher};
+use siphasher::sip::SipHasher13;
+use crate::cms::{row_keys, MIN_DIMENSION};
+
+/// A Bloom filter answering whether an item may have been inserted
+/// before, in a fixed number of bits regardless of how many items are
+/// inserted. Items are hashed through their `ToString` representation
+/// with the same keyed hashers `CountMinSketch` uses.
+///
+/// `contains` never returns a false negative, every inserted item is
+/// always reported. It can return false positives, reporting an item
+/// that was never inserted, with a probability that grows as the
+/// filter fills up. `with_rate` sizes a filter to keep that
+/// probability near a target for a given number of items.
+///
+/// ```
+/// use decentrust::bloom::BloomFilter;
+///
+/// let mut filter = BloomFilter::with_rate(1000, 0.01);
+/// filter.insert(&"node1");
+///
+/// assert!(filter.contains(&"node1"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: usize,
+    num_hashes: usize,
+    seed: u64,
+}
+
+impl BloomFilter {
+    /// Creates an empty filter with `num_bits` bits and `num_hashes`
+    /// hash functions, and a random hash seed. Both are clamped to a
+    /// minimum of 1.
+    pub fn new(num_bits: usize, num_hashes: usize) -> Self {
+        let num_bits = num_bits.max(MIN_DIMENSION);
+        let num_hashes = num_hashes.max(MIN_DIMENSION);
+
+        BloomFilter {
+            bits: vec![0; num_bits.div_ceil(64)],
+            num_bits,
+            num_hashes,
+            seed: RandomState::new().build_hasher().finish(),
+        }
+    }
+
+    /// Creates an empty filter sized so that, once `expected_items`
+    /// items are inserted, the false positive probability is about
+    /// `false_positive_rate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `false_positive_rate` is not within `(0, 1)`, e.g. a
+    /// rate of 0 would call for an unbounded number of bits.
+    pub fn with_rate(expected_items: usize, false_positive_rate: f64) -> Self {
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "false positive rate must be between 0 and 1 exclusive, got {}",
+            false_positive_rate
+        );
+        let items = expected_items.max(1) as f64;
+        let num_bits = (-items * false_positive_rate.ln() / (LN_2 * LN_2)).ceil();
+        let num_hashes = (num_bits / items * LN_2).round();
+
+        BloomFilter::new(num_bits as usize, num_hashes as usize)
+    }
+
+    /// Returns the bit index for a given item and hash function
+    fn bit_index<H: ToString>(&self, item: &H, index: usize) -> usize {
+        let (k0, k1) = row_keys(self.seed, index);
+        let mut hasher = SipHasher13::new_with_keys(k0, k1);
+        hasher.write(item.to_string().as_bytes());
+        hasher.finish() as usize % self.num_bits
+    }
+
+    /// Records an item as seen
+    pub fn insert<H: ToString>(&mut self, item: &H) {
+        (0..self.num_hashes).for_each(|i| {
+            let bit = self.bit_index(item, i);
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        });
+    }
+
+    /// Returns `false` if the item was certainly never inserted, and
+    /// `true` if it probably was
+    pub fn contains<H: ToString>(&self, item: &H) -> bool {
+        (0..self.num_hashes).all(|i| {
+            let bit = self.bit_index(item, i);
+            self.bits[bit / 64] & (1 << (bit % 64)) != 0
+        })
+    }
+
+    /// Returns the number of bits in the filter
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
+
+    /// Returns the number of hash functions applied to each item
+    pub fn num_hashes(&self) -> usize {
+        self.num_hashes
+    }
+}
@@ -0,0 +1,40 @@
+use std::time::Instant;
+
+/// A source of the current time, used to age trust by when it was last
+/// updated. `SystemClock` reads the real monotonic clock, tests can
+/// supply their own implementation to control how much time passes.
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use std::time::{Duration, Instant};
+/// use decentrust::clock::Clock;
+///
+/// #[derive(Clone)]
+/// struct ManualClock(Arc<Mutex<Instant>>);
+///
+/// impl Clock for ManualClock {
+///     fn now(&self) -> Instant {
+///         *self.0.lock().unwrap()
+///     }
+/// }
+///
+/// let start = Instant::now();
+/// let clock = ManualClock(Arc::new(Mutex::new(start)));
+/// *clock.0.lock().unwrap() += Duration::from_secs(60);
+///
+/// assert_eq!(clock.now() - start, Duration::from_secs(60));
+/// ```
+pub trait Clock: Send + Sync {
+    /// Returns the current instant
+    fn now(&self) -> Instant;
+}
+
+/// The default clock, backed by `Instant::now`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
@@ -1,13 +1,56 @@
 #![allow(unused)]
-use std::fmt::Debug;
-use std::hash::{BuildHasher, Hash, Hasher};
+use core::fmt::Debug;
+use core::hash::{BuildHasher, Hash, Hasher};
+#[cfg(feature = "std")]
 use std::collections::hash_map::RandomState;
-use std::ops::{AddAssign, SubAssign, Add, DivAssign};
+use core::ops::{AddAssign, SubAssign, Add, Div, DivAssign, Mul};
 use siphasher::sip::SipHasher13;
-use std::num::Wrapping;
-use std::f64::consts::E;
-use num_traits::Bounded;
-use std::default::Default;
+use core::num::Wrapping;
+use num_traits::{Bounded, ToPrimitive};
+use core::default::Default;
+use alloc::vec;
+use alloc::vec::Vec;
+use alloc::string::{String, ToString};
+use alloc::format;
+use crate::error::{ShapeError, CmsParamError, UnderflowError, MergeError, DecodeError};
+use crate::codec::{ByteCodec, FORMAT_VERSION, decode_version, expect_end};
+use crate::util::{saturating_add, saturating_sub, scaled_share, is_invalid};
+
+/// Increment of the splitmix64 sequence (the golden ratio in 64 bits)
+const SPLITMIX_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The splitmix64 finalizer, used to derive well mixed, independent 
+/// row seeds from a single master seed
+fn splitmix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Derives the two `SipHasher13` keys for a given row from the master 
+/// seed, taking the row's and the next element of the splitmix64 
+/// sequence starting at `seed`
+pub(crate) fn row_keys(seed: u64, row: usize) -> (u64, u64) {
+    let state = seed.wrapping_add(SPLITMIX_GAMMA.wrapping_mul(2 * row as u64 + 1));
+    (splitmix64(state), splitmix64(state.wrapping_add(SPLITMIX_GAMMA)))
+}
+
+/// The smallest width and depth a `CountMinSketch` can have, smaller 
+/// requested dimensions are clamped to it
+pub const MIN_DIMENSION: usize = 1;
+
+/// The width of a `CountMinSketch` created through `Default`. With 
+/// `DEFAULT_DEPTH` rows this keeps the matrix at 1280 cells, which is 
+/// cheap enough for small deployments, but the expected overestimation 
+/// of each estimate grows with the total value recorded divided by the 
+/// width. Deployments tracking more than a few hundred peers should size 
+/// their sketch with `new_from_bounds` instead.
+pub const DEFAULT_WIDTH: usize = 256;
+
+/// The depth of a `CountMinSketch` created through `Default`. Each 
+/// extra row lowers the probability that an estimate exceeds its error 
+/// bound, at the cost of one more hash and cell update per operation.
+pub const DEFAULT_DEPTH: usize = 5;
 
 /// CountMinSketch is a probabilistic data structure for estimating 
 /// values, typically frequencies in a data stream. In this crate 
@@ -16,8 +59,13 @@ use std::default::Default;
 /// as other use cases for probabilistic data structures where small 
 /// overestimations within a given error bound and with a given 
 /// proability is acceptable, but underestimations are never acceptable
+///
+/// Items are hashed with a `SipHasher13` per row, each keyed by a 
+/// distinct seed derived from `seed` with a splitmix64 step, so every 
+/// row is an independent hash function (as the Count-Min error 
+/// guarantee requires) and two sketches with the same dimensions and 
+/// seed place every item in the same cells, even across processes.
 /// ```
-/// use std::collections::hash_map::RandomState;
 /// use std::ops::{AddAssign, SubAssign, DivAssign, Add};
 /// use std::hash::Hash;
 /// use std::fmt::Debug;
@@ -33,10 +81,10 @@ use std::default::Default;
 ///     + Hash
 ///     + Debug
 /// {
-///     pub width: usize,
-///     pub depth: usize,
-///     pub matrix: Vec<Vec<T>>,
-///     hash_builder: RandomState,
+///     width: usize,
+///     depth: usize,
+///     matrix: Vec<Vec<T>>,
+///     seed: u64,
 ///     max: T,
 ///     min: T,
 /// }
@@ -52,10 +100,10 @@ where
     + Hash
     + Debug
 {
-    pub width: usize,
-    pub depth: usize,
-    pub matrix: Vec<Vec<T>>,
-    hash_builder: RandomState,
+    width: usize,
+    depth: usize,
+    matrix: Vec<Vec<T>>,
+    seed: u64,
     max: T,
     min: T 
 }
@@ -74,7 +122,9 @@ where
     + Debug
 {
     /// Creates a new CountMinSketch struct with a width,
-    /// depth, min value and max value
+    /// depth, min value and max value, and a random hash seed.
+    /// The width and depth are clamped to a minimum of 1, so a 
+    /// sketch always has at least one cell to hash into.
     ///
     /// ```
     /// use decentrust::cms::CountMinSketch;
@@ -85,24 +135,110 @@ where
     ///
     /// println!("{:?}", cms);
     /// ```
+    #[cfg(feature = "std")]
     pub fn new(width: usize, depth: usize, min: T, max: T) -> Self {
+        let seed = RandomState::new().build_hasher().finish();
+
+        CountMinSketch::new_with_seed(width, depth, min, max, seed)
+    }
+
+    /// Creates a new CountMinSketch like `new`, but with a given hash 
+    /// seed instead of a random one, so that sketches created apart, 
+    /// e.g. on different nodes, hash every item into the same cells and 
+    /// can be merged. This is also the constructor to use without the 
+    /// `std` feature, which `new` and `new_from_bounds` require.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let a = CountMinSketch::<u64>::new_with_seed(100, 3, 0, 1000, 42);
+    /// let b = CountMinSketch::<u64>::new_with_seed(100, 3, 0, 1000, 42);
+    ///
+    /// assert!(a.shares_hasher(&b));
+    /// ```
+    pub fn new_with_seed(width: usize, depth: usize, min: T, max: T, seed: u64) -> Self {
+        let width = width.max(MIN_DIMENSION);
+        let depth = depth.max(MIN_DIMENSION);
         let matrix = vec![vec![T::default(); width]; depth];
-        let hash_builder = RandomState::new();
 
         CountMinSketch {
             width,
             depth,
             matrix,
-            hash_builder,
+            seed,
             max,
             min,
         }
     }
 
+    /// Creates a CountMinSketch from an existing matrix, e.g. one 
+    /// produced by another process, along with the bounds and the hash 
+    /// seed it was built with. The width and depth are derived from the 
+    /// matrix, which must have at least one row and column and have 
+    /// rows of equal length.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    /// use decentrust::error::ShapeError;
+    ///
+    /// let mut cms = CountMinSketch::<u64>::new(100, 3, 0, 1000);
+    /// cms.increment(&"node1", 10);
+    ///
+    /// let loaded = CountMinSketch::from_matrix(
+    ///     cms.matrix().clone(), 0, 1000, cms.seed()
+    /// ).unwrap();
+    ///
+    /// assert_eq!(loaded.estimate(&"node1"), 10);
+    ///
+    /// let jagged = CountMinSketch::<u64>::from_matrix(
+    ///     vec![vec![0; 3], vec![0; 2]], 0, 1000, 0
+    /// );
+    ///
+    /// assert_eq!(
+    ///     jagged, 
+    ///     Err(ShapeError::Jagged { row: 1, expected: 3, found: 2 })
+    /// );
+    /// ```
+    pub fn from_matrix(
+        matrix: Vec<Vec<T>>, 
+        min: T, 
+        max: T, 
+        seed: u64
+    ) -> Result<Self, ShapeError> {
+        let depth = matrix.len();
+        let width = matrix.first().map(|row| row.len()).unwrap_or(0);
+
+        if width == 0 {
+            return Err(ShapeError::Empty);
+        }
+
+        if let Some((row, found)) = matrix.iter()
+            .map(|row| row.len())
+            .enumerate()
+            .find(|(_, len)| *len != width) 
+        {
+            return Err(ShapeError::Jagged { row, expected: width, found });
+        }
+
+        Ok(CountMinSketch {
+            width,
+            depth,
+            matrix,
+            seed,
+            max,
+            min,
+        })
+    }
+
     /// Creates a new CountMinSketch from desired bounds and 
     /// probability of overestimation, and the maximum number 
     /// of expected entries.
     ///
+    /// # Panics
+    ///
+    /// Panics if the parameters are invalid, see `try_new_from_bounds` 
+    /// for the checked version.
+    ///
     /// ```
     /// use decentrust::cms::CountMinSketch;
     /// use ordered_float::OrderedFloat;
@@ -117,6 +253,7 @@ where
     ///
     /// println!("{:?}", cms);
     /// ```
+    #[cfg(feature = "std")]
     pub fn new_from_bounds(
         error_bound: f64, 
         probability: f64, 
@@ -124,32 +261,66 @@ where
         min: T,
         max: T 
     ) -> Self {
+        match CountMinSketch::try_new_from_bounds(
+            error_bound, probability, max_entries, min, max
+        ) {
+            Ok(cms) => cms,
+            Err(e) => panic!("invalid CountMinSketch parameters: {}", e)
+        }
+    }
 
+    /// Creates a new CountMinSketch from desired bounds and 
+    /// probability of overestimation, and the maximum number 
+    /// of expected entries, checking that `0 < probability < 1` and 
+    /// that `error_bound` and `max_entries` are finite and positive.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    /// use decentrust::error::CmsParamError;
+    ///
+    /// let cms = CountMinSketch::<u64>::try_new_from_bounds(
+    ///     50f64, 0.001, 10000f64, 0, 1000
+    /// );
+    /// assert!(cms.is_ok());
+    ///
+    /// let cms = CountMinSketch::<u64>::try_new_from_bounds(
+    ///     50f64, 1.0, 10000f64, 0, 1000
+    /// );
+    /// assert_eq!(cms, Err(CmsParamError::InvalidProbability(1.0)));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn try_new_from_bounds(
+        error_bound: f64, 
+        probability: f64, 
+        max_entries: f64,
+        min: T,
+        max: T 
+    ) -> Result<Self, CmsParamError> {
         let (width, depth) = {
-            CountMinSketch::<T>::calculate_width_and_depth(
-                error_bound, probability, max_entries
-            ) 
+            CountMinSketch::<T>::sized_dimensions(error_bound, probability, max_entries)?
         };
 
-        CountMinSketch::new(width, depth, min, max)
+        Ok(CountMinSketch::new(width, depth, min, max))
     }
 
     /// Takes a reference to an item implementing the `Hash` trait
     /// and a index representing the hash function. It creates a new
-    /// hasher using the hash_builder, hashes the item, and returns
-    /// the hashed value modulo the width of the sketch matric.
+    /// hasher keyed by the row seed for the index, hashes the item, and 
+    /// returns the hashed value modulo the width of the sketch matric.
+    /// Keying each row (rather than offsetting one hash by the index) 
+    /// places each item in unrelated columns across rows, so items 
+    /// that collide in one row rarely collide in the others.
     fn hash_pair<H: Hash + ToString>(&self, item: &H, index: usize) -> usize {
-        let mut hasher = self.hash_builder.build_hasher();
-        let wrapping_index = Wrapping(index as u64);
+        let (k0, k1) = row_keys(self.seed, index);
+        let mut hasher = SipHasher13::new_with_keys(k0, k1);
         hasher.write(item.to_string().as_bytes());
-        let wrapping_hash = Wrapping(hasher.finish());
-        (wrapping_hash + wrapping_index).0 as usize % self.width
+        hasher.finish() as usize % self.width
     }
 
     /// Takes a reference to an item that implements `Hash` and
     /// returns a vector hashed values for each hash function
     /// (one value for each row in the sketch matrix)
-    fn hash_functions<H: Hash + ToString>(&self, item: &H) -> Vec<usize> {
+    pub(crate) fn hash_functions<H: Hash + ToString>(&self, item: &H) -> Vec<usize> {
         (0..self.depth).map(|i| self.hash_pair(item, i)).collect()
     }
 
@@ -157,7 +328,8 @@ where
     /// a value of type `T` to be added. It calculates the hashes
     /// for the item using `hash_functions` method, and updates
     /// the sketch matrix adding the given value at respective
-    /// positions
+    /// positions. Cells saturate at `T::max_value()` and 
+    /// `T::min_value()` instead of overflowing.
     ///
     /// # Examples
     ///
@@ -188,7 +360,8 @@ where
         let hashes = self.hash_functions(item);
         (0..self.depth).into_iter()
             .for_each(|i| {
-                self.matrix[i][hashes[i]] += value;
+                let cell = &mut self.matrix[i][hashes[i]];
+                *cell = saturating_add(*cell, value);
             }
         )
     }
@@ -215,17 +388,48 @@ where
         let hashes = self.hash_functions(item);
         (0..self.depth).into_iter()
             .for_each(|i| {
-                let mut val = self.matrix[i][hashes[i]].clone();
+                let val = self.matrix[i][hashes[i]].clone();
                 if value > val {
                     self.matrix[i][hashes[i]] = T::default();
                 } else {
-                    val -= value;
-                    self.matrix[i][hashes[i]] = val; 
+                    self.matrix[i][hashes[i]] = saturating_sub(val, value); 
                 }
             }
         )
     }
 
+    /// Decrements the value associated with the given item like 
+    /// `decrement`, but returns an `UnderflowError` instead of flooring 
+    /// when `value` exceeds the item's current estimate. The sketch is 
+    /// left untouched on error.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let mut cms = CountMinSketch::<u32>::new(100, 4, 0, 1_000);
+    /// cms.increment(&"node1", 10);
+    ///
+    /// let err = cms.try_decrement(&"node1", 15).unwrap_err();
+    /// assert_eq!((err.attempted, err.available), (15, 10));
+    /// assert_eq!(cms.estimate(&"node1"), 10);
+    ///
+    /// assert!(cms.try_decrement(&"node1", 4).is_ok());
+    /// assert_eq!(cms.estimate(&"node1"), 6);
+    /// ```
+    pub fn try_decrement<H: Hash + ToString>(
+        &mut self, 
+        item: &H, 
+        value: T
+    ) -> Result<(), UnderflowError<T>> {
+        let available = self.estimate(item);
+        if value > available {
+            return Err(UnderflowError { attempted: value, available });
+        }
+
+        self.decrement(item, value);
+        Ok(())
+    }
+
     /// Takes a reference to an item implementing `Hash` and
     /// returns an estimate of the value for that item. It calculates
     /// the hash values for the item using hash_functions and returns 
@@ -250,7 +454,7 @@ where
         let hashes = self.hash_functions(item);
         let mut min_estimate = self.matrix[0][hashes[0]];
         (1..self.depth).into_iter().for_each(|i| {
-            min_estimate = std::cmp::min(
+            min_estimate = core::cmp::min(
                 min_estimate, 
                 self.matrix[i][hashes[i]]
             ); 
@@ -259,8 +463,501 @@ where
         min_estimate
     }
 
+    /// Returns the value of the cell an item hashes to in each row, in 
+    /// row order, so one value per hash function. `estimate` is the 
+    /// smallest of them, and the spread between them shows how much 
+    /// other items collide with this one.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let mut cms = CountMinSketch::<u64>::new(100, 4, 0, u64::MAX);
+    /// cms.increment(&"node1", 10);
+    ///
+    /// assert_eq!(cms.row_estimates(&"node1"), vec![10, 10, 10, 10]);
+    /// ```
+    pub fn row_estimates<H: Hash + ToString>(&self, item: &H) -> Vec<T> {
+        self.hash_functions(item)
+            .into_iter()
+            .enumerate()
+            .map(|(row, col)| self.matrix[row][col])
+            .collect()
+    }
+
+    /// Returns a confidence score in `[0, 1]` for the estimate of an 
+    /// item, based on the spread of the cells its hashes land in: the 
+    /// ratio of the smallest to the largest of those cells. Every row 
+    /// holds the item's true value plus whatever collided with it, so 
+    /// equal cells suggest few collisions (confidence close to 1) while 
+    /// a wide spread means some rows are inflated by other items. An 
+    /// item whose cells are all empty has a confidence of 1.
+    ///
+    /// This is a heuristic, in a heavily saturated sketch every cell 
+    /// is inflated by a similar amount and the spread narrows again.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let mut cms = CountMinSketch::<u64>::new(100, 4, 0, 1000);
+    /// cms.increment(&"node1", 10);
+    ///
+    /// assert_eq!(cms.estimate_confidence(&"node1"), 1.0);
+    /// ```
+    pub fn estimate_confidence<H: Hash + ToString>(&self, item: &H) -> f64 
+    where
+        T: ToPrimitive
+    {
+        let cells: Vec<f64> = self.row_estimates(item)
+            .into_iter()
+            .filter_map(|cell| cell.to_f64())
+            .collect();
+
+        let min_cell = cells.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max_cell = cells.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+        if cells.is_empty() || max_cell <= 0.0 {
+            return 1.0;
+        }
+
+        (min_cell.max(0.0) / max_cell).clamp(0.0, 1.0)
+    }
+
+    /// Returns the difference between the largest and the smallest of 
+    /// the cells an item hashes to. Without collisions every row holds 
+    /// the same value and the spread is zero, so a large spread means 
+    /// other items share some of the item's cells. Only the rows where 
+    /// an item collides are inflated, the estimate itself may still be 
+    /// exact if any row is collision free.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let mut cms = CountMinSketch::<u64>::new(1000, 4, 0, u64::MAX);
+    /// cms.increment(&"node1", 10);
+    ///
+    /// assert_eq!(cms.cell_spread(&"node1"), 0);
+    /// ```
+    pub fn cell_spread<H: Hash + ToString>(&self, item: &H) -> T {
+        let cells = self.row_estimates(item).into_iter();
+
+        let (min_cell, max_cell) = cells.fold(
+            (T::max_value(), T::min_value()), 
+            |(lo, hi), cell| (core::cmp::min(lo, cell), core::cmp::max(hi, cell))
+        );
+
+        let mut spread = max_cell;
+        spread -= min_cell;
+        spread
+    }
+
+    /// Yields the `(row, column)` coordinates and value of every cell 
+    /// whose value is strictly greater than `threshold`, row by row. 
+    /// Useful to spot hot or saturated cells when analyzing a sketch.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let mut cms = CountMinSketch::<u64>::new(100, 3, 0, u64::MAX);
+    /// cms.increment(&"node1", 50);
+    /// cms.increment(&"node2", 5);
+    ///
+    /// let hot: Vec<((usize, usize), u64)> = cms.cells_above(10).collect();
+    ///
+    /// assert_eq!(hot.len(), 3);
+    /// assert!(hot.iter().all(|(_, value)| *value >= 50));
+    /// ```
+    pub fn cells_above(&self, threshold: T) -> impl Iterator<Item = ((usize, usize), T)> + '_ {
+        self.matrix.iter()
+            .enumerate()
+            .flat_map(|(row, cells)| {
+                cells.iter().enumerate().map(move |(col, value)| ((row, col), *value))
+            })
+            .filter(move |(_, value)| *value > threshold)
+    }
+
+    /// Returns the column index and value of the largest cell in each 
+    /// row, in row order, taking the leftmost column on ties. A key 
+    /// that dominates the sketch hashes into the hot cell of every row, 
+    /// so checking which retained keys hash into the hot cells is a 
+    /// cheap way to find heavy hitters.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let matrix = vec![vec![1u64, 7, 3], vec![4, 4, 2]];
+    /// let cms = CountMinSketch::from_matrix(matrix, 0, u64::MAX, 0).unwrap();
+    ///
+    /// assert_eq!(cms.hot_cells(), vec![(1, 7), (0, 4)]);
+    /// ```
+    pub fn hot_cells(&self) -> Vec<(usize, T)> {
+        self.matrix.iter()
+            .map(|cells| {
+                cells.iter().enumerate().fold((0, cells[0]), |hottest, (col, value)| {
+                    if *value > hottest.1 { (col, *value) } else { hottest }
+                })
+            })
+            .collect()
+    }
+
+    /// Returns whether any cell is NaN or infinite, see 
+    /// `HonestPeer::has_invalid_values`. Integer cells are never invalid.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut cms: CountMinSketch<OrderedFloat<f64>> = CountMinSketch::new(
+    ///     16, 4, f64::MIN.into(), f64::MAX.into()
+    /// );
+    /// cms.increment(&"node1", 1f64.into());
+    /// assert!(!cms.has_invalid_values());
+    ///
+    /// cms.increment(&"node2", f64::NAN.into());
+    /// assert!(cms.has_invalid_values());
+    /// ```
+    pub fn has_invalid_values(&self) -> bool 
+    where
+        T: ToPrimitive
+    {
+        self.matrix.iter().flatten().any(is_invalid)
+    }
+
+    /// Returns a copy of the sketch matrix as a single contiguous 
+    /// buffer in row-major order, so that the cell at `(row, col)` is 
+    /// at index `row * width + col`. Handy for vectorized analysis or 
+    /// handing the sketch to code expecting a flat buffer, see 
+    /// `from_flat` for the inverse.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let matrix = vec![vec![1u64, 2, 3], vec![4, 5, 6]];
+    /// let cms = CountMinSketch::from_matrix(matrix, 0, u64::MAX, 0).unwrap();
+    ///
+    /// assert_eq!(cms.flatten(), vec![1, 2, 3, 4, 5, 6]);
+    /// ```
+    pub fn flatten(&self) -> Vec<T> {
+        self.matrix.iter().flatten().copied().collect()
+    }
+
+    /// Creates a CountMinSketch from a row-major buffer of cells, as 
+    /// produced by `flatten`, split into rows of `width` cells. Fails 
+    /// like `from_matrix` if `width` is zero, the buffer is empty, or 
+    /// its length is not a multiple of `width`.
+    pub fn from_flat(
+        cells: Vec<T>, 
+        width: usize, 
+        min: T, 
+        max: T, 
+        seed: u64
+    ) -> Result<Self, ShapeError> {
+        if width == 0 {
+            return Err(ShapeError::Empty);
+        }
+
+        let matrix = cells.chunks(width).map(|row| row.to_vec()).collect();
+        Self::from_matrix(matrix, min, max, seed)
+    }
+
+    /// Returns a sketch of half the width, e.g. to send over a 
+    /// constrained link, by folding the right half of every row onto 
+    /// the left half: `folded[j] = cell[j] + cell[j + width / 2]`. 
+    /// Items are hashed into column `hash % width`, and for an even 
+    /// width `(hash % width) % (width / 2)` is `hash % (width / 2)`, so 
+    /// each item lands on the folded cell holding its old cell. 
+    /// Estimates from the folded sketch are therefore still upper 
+    /// bounds on the true counts, at least as large as the original 
+    /// estimates. Sums saturate at the bounds of `T`.
+    ///
+    /// Returns `ShapeError::OddWidth` if the width is odd.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    /// use decentrust::error::ShapeError;
+    ///
+    /// let mut cms = CountMinSketch::<u64>::new(64, 4, 0, u64::MAX);
+    /// cms.increment(&"node1", 10);
+    /// cms.increment(&"node2", 5);
+    ///
+    /// let folded = cms.fold_width().unwrap();
+    /// assert_eq!(folded.width(), 32);
+    /// assert!(folded.estimate(&"node1") >= cms.estimate(&"node1"));
+    ///
+    /// let odd = CountMinSketch::<u64>::new(33, 4, 0, u64::MAX);
+    /// assert_eq!(odd.fold_width().unwrap_err(), ShapeError::OddWidth(33));
+    /// ```
+    pub fn fold_width(&self) -> Result<CountMinSketch<T>, ShapeError> {
+        if self.width % 2 != 0 {
+            return Err(ShapeError::OddWidth(self.width));
+        }
+
+        let half = self.width / 2;
+        let matrix = self.matrix.iter()
+            .map(|row| {
+                (0..half).map(|j| saturating_add(row[j], row[j + half])).collect()
+            })
+            .collect();
+
+        CountMinSketch::from_matrix(matrix, self.min, self.max, self.seed)
+    }
+
+    /// Encodes the sketch in a compact binary format: the 
+    /// `codec::FORMAT_VERSION` tag, the width, depth, seed and bounds, 
+    /// then every cell in row-major order. See `from_bytes`.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let mut cms = CountMinSketch::<u64>::new(100, 3, 0, u64::MAX);
+    /// cms.increment(&"node1", 10);
+    ///
+    /// let decoded = CountMinSketch::<u64>::from_bytes(&cms.to_bytes()).unwrap();
+    ///
+    /// assert_eq!(decoded, cms);
+    /// assert_eq!(decoded.estimate(&"node1"), 10);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> 
+    where
+        T: ByteCodec
+    {
+        let mut out = vec![FORMAT_VERSION];
+        self.width.encode(&mut out);
+        self.depth.encode(&mut out);
+        self.seed.encode(&mut out);
+        self.min.encode(&mut out);
+        self.max.encode(&mut out);
+        self.matrix.iter().flatten().for_each(|cell| cell.encode(&mut out));
+        out
+    }
+
+    /// Decodes a sketch encoded by `to_bytes`, failing if the version 
+    /// is unknown, the input is truncated or has trailing bytes, or the 
+    /// encoded dimensions are empty
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> 
+    where
+        T: ByteCodec
+    {
+        let mut input = bytes;
+        decode_version(&mut input)?;
+        let width = usize::decode(&mut input)?;
+        let depth = usize::decode(&mut input)?;
+        let seed = u64::decode(&mut input)?;
+        let min = T::decode(&mut input)?;
+        let max = T::decode(&mut input)?;
+
+        let len = width.checked_mul(depth).ok_or(DecodeError::UnexpectedEnd)?;
+        let cells = (0..len)
+            .map(|_| T::decode(&mut input))
+            .collect::<Result<Vec<T>, DecodeError>>()?;
+        expect_end(input)?;
+
+        Ok(Self::from_flat(cells, width, min, max, seed)?)
+    }
+
+    /// Streams the estimate for each of the given items, in order, 
+    /// without collecting them. Equivalent to calling `estimate` for 
+    /// every item.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let mut cms = CountMinSketch::<u64>::default();
+    /// let keys = vec!["node1", "node2", "node3"];
+    /// cms.increment(&"node1", 10);
+    /// cms.increment(&"node3", 30);
+    ///
+    /// let estimates: Vec<u64> = cms.estimate_all(keys.iter()).collect();
+    /// assert_eq!(estimates, vec![10, 0, 30]);
+    /// ```
+    pub fn estimate_all<'a, H: Hash + ToString + 'a>(
+        &'a self, 
+        items: impl Iterator<Item = &'a H> + 'a
+    ) -> impl Iterator<Item = T> + 'a {
+        items.map(move |item| self.estimate(item))
+    }
+
+    /// Returns `true` if `other` hashes items into the same columns 
+    /// as this instance, i.e. both sketches have the same dimensions 
+    /// and the same hash seed. Sketches that are 
+    /// derived from one another, such as a raw sketch and its normalized 
+    /// counterpart, must share a hasher, otherwise estimates are read 
+    /// from the wrong cells.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let cms = CountMinSketch::<u64>::default();
+    /// let cloned = cms.clone();
+    /// let independent = CountMinSketch::<u64>::default();
+    ///
+    /// assert!(cms.shares_hasher(&cloned));
+    /// assert!(!cms.shares_hasher(&independent));
+    /// ```
+    pub fn shares_hasher(&self, other: &CountMinSketch<T>) -> bool {
+        self.width == other.width 
+            && self.depth == other.depth 
+            && self.seed == other.seed
+    }
+
+    /// Checks that `other` can be merged into this sketch, i.e. that it 
+    /// has the same dimensions and hash seed, see `shares_hasher`
+    pub fn check_mergeable(&self, other: &CountMinSketch<T>) -> Result<(), MergeError> {
+        if (self.width, self.depth) != (other.width, other.depth) {
+            return Err(MergeError::DimensionMismatch { 
+                expected: (self.width, self.depth), 
+                found: (other.width, other.depth) 
+            });
+        }
+
+        if self.seed != other.seed {
+            return Err(MergeError::SeedMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Adds every cell of `other` into this sketch, so estimates cover 
+    /// the values recorded in both. Both sketches must share a hasher, 
+    /// e.g. by being cloned from the same sketch, otherwise the same 
+    /// item lands in different cells and a `MergeError` is returned 
+    /// without modifying this sketch.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let mut a = CountMinSketch::<u64>::new(100, 4, 0, u64::MAX);
+    /// let mut b = a.clone();
+    /// a.increment(&"node1", 3);
+    /// b.increment(&"node1", 4);
+    ///
+    /// a.merge(&b).unwrap();
+    /// assert_eq!(a.estimate(&"node1"), 7);
+    ///
+    /// let independent = CountMinSketch::<u64>::new(100, 4, 0, u64::MAX);
+    /// assert!(a.merge(&independent).is_err());
+    /// ```
+    pub fn merge(&mut self, other: &CountMinSketch<T>) -> Result<(), MergeError> {
+        self.check_mergeable(other)?;
+        self.matrix.iter_mut()
+            .zip(other.matrix.iter())
+            .for_each(|(row, other_row)| {
+                row.iter_mut().zip(other_row.iter()).for_each(|(cell, other_cell)| {
+                    *cell = saturating_add(*cell, *other_cell);
+                });
+            });
+
+        Ok(())
+    }
+
+    /// Builds a new, empty sketch with the given dimensions, sharing 
+    /// this instance's hash seed and bounds. Use this to grow a sketch 
+    /// whose estimates have degraded under load.
+    ///
+    /// The per-item values cannot be recovered from the sketch matrix 
+    /// alone, as each cell mixes every item that hashed into it, so 
+    /// the returned sketch holds no values. To carry values over, the 
+    /// caller must re-insert them from a known key set, see 
+    /// `rehash_from_keys`.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let cms = CountMinSketch::<u64>::new(100, 3, 0, 1000);
+    /// let resized = cms.rehash_into(400, 5);
+    ///
+    /// assert_eq!(resized.get_width(), 400);
+    /// assert_eq!(resized.get_depth(), 5);
+    /// ```
+    pub fn rehash_into(&self, new_width: usize, new_depth: usize) -> CountMinSketch<T> {
+        let new_width = new_width.max(MIN_DIMENSION);
+        let new_depth = new_depth.max(MIN_DIMENSION);
+        CountMinSketch {
+            width: new_width,
+            depth: new_depth,
+            matrix: vec![vec![T::default(); new_width]; new_depth],
+            seed: self.seed,
+            max: self.max,
+            min: self.min,
+        }
+    }
+
+    /// Builds a new sketch with the given dimensions, like `rehash_into`, 
+    /// and re-inserts each `(key, estimate)` pair from a known key set. 
+    /// Estimates for the provided keys are preserved within the error 
+    /// bound of the new dimensions.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let mut cms = CountMinSketch::<u64>::new(100, 3, 0, 1000);
+    /// let keys = vec!["node1", "node2"];
+    /// cms.increment(&"node1", 10);
+    /// cms.increment(&"node2", 20);
+    ///
+    /// let resized = cms.rehash_from_keys(
+    ///     400, 
+    ///     5, 
+    ///     keys.iter().map(|k| (k, cms.estimate(k)))
+    /// );
+    ///
+    /// assert!(resized.estimate(&"node1") >= 10);
+    /// assert!(resized.estimate(&"node2") >= 20);
+    /// ```
+    pub fn rehash_from_keys<'a, H: Hash + ToString + 'a>(
+        &self, 
+        new_width: usize, 
+        new_depth: usize,
+        entries: impl IntoIterator<Item = (&'a H, T)>
+    ) -> CountMinSketch<T> {
+        let mut resized = self.rehash_into(new_width, new_depth);
+        entries.into_iter().for_each(|(key, estimate)| {
+            resized.increment(key, estimate);
+        });
+
+        resized
+    }
+
+    /// Checks the sizing parameters of `try_new_from_bounds`
+    #[cfg(feature = "std")]
+    fn check_sizing(
+        error_bound: f64, 
+        probability: f64, 
+        max_entries: f64
+    ) -> Result<(), CmsParamError> {
+        if !(probability > 0.0 && probability < 1.0) {
+            return Err(CmsParamError::InvalidProbability(probability));
+        }
+
+        if !(error_bound.is_finite() && error_bound > 0.0) {
+            return Err(CmsParamError::InvalidErrorBound(error_bound));
+        }
+
+        if !(max_entries.is_finite() && max_entries > 0.0) {
+            return Err(CmsParamError::InvalidMaxEntries(max_entries));
+        }
+
+        Ok(())
+    }
+
+    /// Checks the sizing parameters of `try_new_from_bounds` and returns 
+    /// the width and depth they call for, without allocating a sketch
+    #[cfg(feature = "std")]
+    pub(crate) fn sized_dimensions(
+        error_bound: f64, 
+        probability: f64, 
+        max_entries: f64
+    ) -> Result<(usize, usize), CmsParamError> {
+        CountMinSketch::<T>::check_sizing(error_bound, probability, max_entries)?;
+
+        Ok(CountMinSketch::<T>::calculate_width_and_depth(error_bound, probability, max_entries))
+    }
+
     /// Helper method to calculate width and depth of a CountMinSketch 
-    /// internally. Used in the `new_from_bounds` initializer method
+    /// internally. Used in the `new_from_bounds` initializer method.
+    /// Parameters whose ratio overflows (e.g. a huge error bound over 
+    /// a tiny number of entries) yield the minimum dimension of 1 
+    /// rather than 0.
+    #[cfg(feature = "std")]
     fn calculate_width_and_depth(
         error_bound: f64, 
         probability: f64, 
@@ -268,10 +965,55 @@ where
     ) -> (usize, usize) { 
         let epsilon = error_bound / max_entries;
         
-        let width = f64::ceil(std::f64::consts::E / epsilon) as usize;
+        let width = f64::ceil(core::f64::consts::E / epsilon) as usize;
         let depth = f64::ceil(-probability.ln()) as usize;
 
-        (width, depth)
+        (width.max(MIN_DIMENSION), depth.max(MIN_DIMENSION))
+    }
+
+    /// Returns the `(width, depth)` a sketch needs to overestimate by at 
+    /// most `target_error` with probability `confidence`, once 
+    /// `observed_entries` have been counted. This is the sizing 
+    /// `new_from_bounds` uses, with `confidence` in place of its 
+    /// overestimation probability of `1 - confidence`, so that a sketch 
+    /// that has run for a while can be compared with what its real load 
+    /// calls for, e.g. to decide whether to `rehash_from_keys` into a 
+    /// larger one.
+    ///
+    /// The parameters are checked like those of `try_new_from_bounds`, 
+    /// with `CmsParamError::InvalidProbability` carrying `confidence` 
+    /// when it is not within `(0, 1)`.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    /// use decentrust::error::CmsParamError;
+    ///
+    /// let cms = CountMinSketch::<u64>::new(100, 3, 0, u64::MAX);
+    /// let (width, depth) = CountMinSketch::<u64>::recommended_dimensions(5000, 50.0, 0.99).unwrap();
+    ///
+    /// assert!(width > cms.width());
+    /// assert_eq!(depth, 5);
+    ///
+    /// assert_eq!(
+    ///     CountMinSketch::<u64>::recommended_dimensions(5000, 50.0, 1.0), 
+    ///     Err(CmsParamError::InvalidProbability(1.0))
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn recommended_dimensions(
+        observed_entries: usize, 
+        target_error: f64, 
+        confidence: f64
+    ) -> Result<(usize, usize), CmsParamError> {
+        let probability = 1.0 - confidence;
+        let max_entries = observed_entries as f64;
+        CountMinSketch::<T>::check_sizing(target_error, probability, max_entries)
+            .map_err(|err| match err {
+                CmsParamError::InvalidProbability(_) => CmsParamError::InvalidProbability(confidence),
+                err => err,
+            })?;
+
+        Ok(CountMinSketch::<T>::calculate_width_and_depth(target_error, probability, max_entries))
     }
 
     pub fn get_min(&self) -> T {
@@ -282,12 +1024,32 @@ where
         self.max
     }
 
+    /// Same as `width`
     pub fn get_width(&self) -> usize {
-        self.width
+        self.width()
     }
 
+    /// Same as `depth`
     pub fn get_depth(&self) -> usize {
-        self.depth
+        self.depth()
+    }
+
+    /// Returns the seed used to key the hash functions. Together with 
+    /// the matrix and bounds it is enough to rebuild the sketch with 
+    /// `from_matrix`.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Replaces the sketch matrix, used by the honest peer 
+    /// implementations to swap in normalized values. The matrix must 
+    /// have the same dimensions as the sketch.
+    pub(crate) fn set_matrix(&mut self, matrix: Vec<Vec<T>>) {
+        debug_assert!(
+            matrix.len() == self.depth 
+            && matrix.iter().all(|row| row.len() == self.width)
+        );
+        self.matrix = matrix;
     }
 
     /// Loops through the entire matrix and extracts summed value 
@@ -295,6 +1057,13 @@ where
     /// in the matrix and divides each value by the summed value for 
     /// the given row to create a normalized value. Currently this 
     /// should only return a value between 0 or 1, i.e. a float.
+    /// Rows that sum to `T::default()`, e.g. those of an empty sketch, 
+    /// are left at `T::default()` instead of being divided by zero, 
+    /// which would fill them with NaN, or panic for integer `T`.
+    ///
+    /// For integer `T` every ratio floors to 0 (unless a single cell 
+    /// holds the whole row total), so integer sketches should be 
+    /// normalized with `normalize_estimates_scaled` instead.
     /// ```
     /// use decentrust::cms::CountMinSketch;
     /// use ordered_float::OrderedFloat;
@@ -310,18 +1079,17 @@ where
     /// let estimated_score = cms.estimate(&node_id);
     /// assert_eq!(estimated_score, OrderedFloat(10f64));
     ///
-    /// let mut normalized_cms = cms.clone();
-    /// normalized_cms.matrix = cms.normalize_estimates(); 
+    /// let normalized_cms = CountMinSketch::from_matrix(
+    ///     cms.normalize_estimates(), 
+    ///     cms.get_min(), 
+    ///     cms.get_max(), 
+    ///     cms.seed()
+    /// ).unwrap();
     ///
     /// let normalized_estimate = normalized_cms.estimate(&node_id);
     /// println!("{}", normalized_estimate);
     /// 
     /// ```
-    // TODO: add normalization factor to be able to return integers 
-    // both signed and unsigned, by multiplying the float value for 
-    // each normalized value by the factor. Factors should be orders 
-    // of decimal magnitude, i.e. should always be modulo 10 == 0.
-    //
     pub fn normalize_estimates(&self) -> Vec<Vec<T>> {
         let mut total_vec: Vec<T> = vec![T::default(); self.depth]; 
         let mut new_matrix = vec![vec![T::default(); self.width]; self.depth];
@@ -337,6 +1105,10 @@ where
             .enumerate()
             .for_each(|(idx, row)| {
                 let total_trust = total_vec[idx];
+                if total_trust == T::default() {
+                    return;
+                }
+
                 row.iter_mut().enumerate().for_each(|(i, v)| {
                     let mut raw = self.matrix[idx][i];
                     raw /= total_trust;
@@ -347,6 +1119,49 @@ where
         new_matrix
     }
 
+    /// Normalizes the matrix like `normalize_estimates`, but multiplies 
+    /// each value by `factor` before dividing by its row total, so that 
+    /// the normalized values of a row sum to (roughly) `factor` rather 
+    /// than 1. This keeps integer sketches meaningful: with a factor of 
+    /// 1000 a `u64` cell holding a quarter of its row total normalizes 
+    /// to 250 rather than 0. Factors should be orders of decimal 
+    /// magnitude, e.g. 100 or 10_000. Where `value * factor` would 
+    /// overflow `T` the row total is divided by `factor` instead, giving 
+    /// up some precision, see `util::scaled_share`. Rows that sum to `T::default()` are left at 
+    /// `T::default()` rather than divided by zero, which would panic 
+    /// for integer cells.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let mut cms = CountMinSketch::<u64>::new(1000, 3, 0, u64::MAX);
+    /// cms.increment(&"node1", 1);
+    /// cms.increment(&"node2", 3);
+    ///
+    /// let normalized = CountMinSketch::from_matrix(
+    ///     cms.normalize_estimates_scaled(1000), 0, u64::MAX, cms.seed()
+    /// ).unwrap();
+    ///
+    /// assert!(normalized.estimate(&"node1") >= 250);
+    /// ```
+    pub fn normalize_estimates_scaled(&self, factor: T) -> Vec<Vec<T>> 
+    where
+        T: Mul<Output = T> + Div<Output = T>
+    {
+        self.matrix.iter()
+            .map(|row| {
+                let total_trust = row.iter().fold(T::default(), |acc, v| {
+                    acc + *v
+                });
+                if total_trust == T::default() {
+                    return vec![T::default(); row.len()];
+                }
+
+                row.iter().map(|v| scaled_share(*v, total_trust, factor)).collect()
+            })
+            .collect()
+    }
+
     /// Returns the length of all non-default entries in the 
     /// `CountMinSketch` instance to get a probabilistic length 
     /// of the number of items the instance is tracking.
@@ -384,10 +1199,85 @@ where
 
         len
     }
+
+    /// Returns a short, human readable report of how saturated the 
+    /// sketch is: its width and depth, the total mass (sum of every 
+    /// cell), the fraction of non-default cells in each row, and the 
+    /// smallest and largest cell values. Intended for debugging, as an 
+    /// alternative to dumping the whole matrix with `{:?}`.
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let mut cms = CountMinSketch::<u64>::new(100, 3, 0, 1000);
+    /// cms.increment(&"node1", 10);
+    ///
+    /// let report = cms.occupancy_report();
+    /// println!("{}", report);
+    ///
+    /// assert!(report.contains("width: 100"));
+    /// assert!(report.contains("depth: 3"));
+    /// ```
+    pub fn occupancy_report(&self) -> String {
+        let total_mass = self.matrix.iter().flatten().fold(T::default(), |acc, v| {
+            saturating_add(acc, *v)
+        });
+        let min_cell = self.matrix.iter().flatten().min().copied().unwrap_or_default();
+        let max_cell = self.matrix.iter().flatten().max().copied().unwrap_or_default();
+
+        let mut report = format!(
+            "width: {}, depth: {}, total mass: {:?}, min cell: {:?}, max cell: {:?}",
+            self.width, self.depth, total_mass, min_cell, max_cell
+        );
+
+        self.matrix.iter().enumerate().for_each(|(idx, row)| {
+            let occupied = row.iter().filter(|&v| *v != T::default()).count();
+            let fraction = occupied as f64 / self.width.max(1) as f64;
+            report.push_str(&format!("\nrow {}: {:.4} occupied", idx, fraction));
+        });
+
+        report
+    }
+}
+
+impl<T> CountMinSketch<T> 
+where
+    T: AddAssign 
+    + SubAssign 
+    + DivAssign
+    + Add<Output = T> 
+    + Ord 
+    + Hash
+    + Debug
+{
+    /// Returns a reference to the sketch matrix, one row per hash 
+    /// function
+    pub fn matrix(&self) -> &Vec<Vec<T>> {
+        &self.matrix
+    }
+
+    /// Returns the number of columns in each row of the sketch
+    ///
+    /// ```
+    /// use decentrust::cms::CountMinSketch;
+    ///
+    /// let cms = CountMinSketch::<u64>::new(100, 3, 0, u64::MAX);
+    ///
+    /// assert_eq!((cms.width(), cms.depth()), (100, 3));
+    /// ```
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows, i.e. hash functions, in the sketch
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
 }
 
 /// Implements the default trait for count_min_sketch for a 
 /// given T value. 
+#[cfg(feature = "std")]
 impl<T> Default for CountMinSketch<T> 
 where
     T: AddAssign 
@@ -402,7 +1292,43 @@ where
     + Bounded
     
 {
+    /// Creates a `DEFAULT_WIDTH` x `DEFAULT_DEPTH` sketch bounded by the 
+    /// full range of `T`
     fn default() -> Self {
-        Self::new(3000, 10, T::min_value(), T::max_value())
+        Self::new(DEFAULT_WIDTH, DEFAULT_DEPTH, T::min_value(), T::max_value())
     }
 }
+
+/// Two sketches are equal when they have the same dimensions, bounds,
+/// hash seed and cell values, so equal sketches always produce the
+/// same estimates.
+impl<T> PartialEq for CountMinSketch<T> 
+where
+    T: AddAssign 
+    + SubAssign 
+    + DivAssign
+    + Add<Output = T> 
+    + Ord 
+    + Hash
+    + Debug
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width
+            && self.depth == other.depth
+            && self.min == other.min
+            && self.max == other.max
+            && self.seed == other.seed
+            && self.matrix == other.matrix
+    }
+}
+
+impl<T> Eq for CountMinSketch<T> 
+where
+    T: AddAssign 
+    + SubAssign 
+    + DivAssign
+    + Add<Output = T> 
+    + Ord 
+    + Hash
+    + Debug
+{}
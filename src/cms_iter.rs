@@ -1,6 +1,7 @@
-use std::fmt::Debug;
-use std::ops::{AddAssign, SubAssign, Add, DivAssign};
-use std::hash::Hash;
+use core::fmt::Debug;
+use core::ops::{AddAssign, SubAssign, Add, DivAssign};
+use core::hash::Hash;
+use alloc::vec::Vec;
 use num_traits::Bounded;
 
 use crate::cms::CountMinSketch;
@@ -69,14 +70,14 @@ where
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.row >= self.cms.depth {
+        if self.row >= self.cms.depth() {
             return None;
         }
 
-        let element = &self.cms.matrix[self.row][self.col];
+        let element = &self.cms.matrix()[self.row][self.col];
 
         self.col += 1;
-        if self.col >= self.cms.width {
+        if self.col >= self.cms.width() {
             self.col = 0;
             self.row += 1;
         }
@@ -162,7 +163,7 @@ where
 
     fn into_iter(self) -> Self::IntoIter {
         CountMinSketchIntoIter {
-            matrix: self.matrix.clone(),
+            matrix: self.matrix().clone(),
             row: 0,
             col: 0,
         }
@@ -0,0 +1,196 @@
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use ordered_float::OrderedFloat;
+use crate::error::DecodeError;
+use crate::trust::Trust;
+#[cfg(feature = "std")]
+use crate::honest_peer::NormalizationMode;
+
+/// The version tag written as the first byte of every encoding produced
+/// by `to_bytes`. Decoders reject any other version.
+pub const FORMAT_VERSION: u8 = 1;
+
+/// A compact binary encoding for the keys and values stored in trust
+/// stores and sketches, used by their `to_bytes` and `from_bytes`
+/// methods. Numbers are written little-endian at their native width,
+/// strings as a length prefix followed by their UTF-8 bytes.
+///
+/// ```
+/// use decentrust::codec::ByteCodec;
+///
+/// let mut bytes = Vec::new();
+/// 42u32.encode(&mut bytes);
+/// "node1".to_string().encode(&mut bytes);
+///
+/// let mut input = bytes.as_slice();
+/// assert_eq!(u32::decode(&mut input), Ok(42));
+/// assert_eq!(String::decode(&mut input), Ok("node1".to_string()));
+/// assert!(input.is_empty());
+/// ```
+pub trait ByteCodec: Sized {
+    /// Appends the encoding of `self` to `out`
+    fn encode(&self, out: &mut Vec<u8>);
+    /// Decodes a value from the front of `input`, advancing it past the
+    /// bytes consumed
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError>;
+}
+
+macro_rules! impl_byte_codec {
+    ($($t:ty),*) => {
+        $(
+            impl ByteCodec for $t {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+                    let bytes = take(input, core::mem::size_of::<$t>())?;
+                    Ok(<$t>::from_le_bytes(bytes.try_into().expect("sized by take")))
+                }
+            }
+        )*
+    };
+}
+
+impl_byte_codec!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl ByteCodec for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(u64::decode(input)? as usize)
+    }
+}
+
+impl ByteCodec for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        match u8::decode(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            tag => Err(DecodeError::InvalidTag(tag)),
+        }
+    }
+}
+
+impl ByteCodec for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        let len = usize::decode(input)?;
+        let bytes = take(input, len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::InvalidUtf8)
+    }
+}
+
+impl<T: ByteCodec> ByteCodec for OrderedFloat<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(OrderedFloat(T::decode(input)?))
+    }
+}
+
+impl ByteCodec for Trust {
+    fn encode(&self, out: &mut Vec<u8>) {
+        f64::from(*self).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Trust::new(f64::decode(input)?))
+    }
+}
+
+#[cfg(feature = "std")]
+impl ByteCodec for NormalizationMode {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(match self {
+            NormalizationMode::Sum => 0,
+            NormalizationMode::Rank => 1,
+        });
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        match u8::decode(input)? {
+            0 => Ok(NormalizationMode::Sum),
+            1 => Ok(NormalizationMode::Rank),
+            tag => Err(DecodeError::InvalidTag(tag)),
+        }
+    }
+}
+
+/// Maps are written as their length followed by their entries, sorted 
+/// by the encoded bytes of their keys so that equal maps always encode 
+/// the same regardless of iteration order
+#[cfg(feature = "std")]
+impl<K, V> ByteCodec for HashMap<K, V> 
+where
+    K: ByteCodec + Eq + Hash,
+    V: ByteCodec
+{
+    fn encode(&self, out: &mut Vec<u8>) {
+        let mut entries: Vec<(Vec<u8>, &V)> = self.iter()
+            .map(|(k, v)| {
+                let mut key = Vec::new();
+                k.encode(&mut key);
+                (key, v)
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        entries.len().encode(out);
+        entries.into_iter().for_each(|(key, v)| {
+            out.extend_from_slice(&key);
+            v.encode(out);
+        });
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        let len = usize::decode(input)?;
+        (0..len)
+            .map(|_| Ok((K::decode(input)?, V::decode(input)?)))
+            .collect()
+    }
+}
+
+/// Splits the first `len` bytes off of `input`
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], DecodeError> {
+    if input.len() < len {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+
+    let (head, tail) = input.split_at(len);
+    *input = tail;
+    Ok(head)
+}
+
+/// Reads the version tag, failing unless it is `FORMAT_VERSION`
+pub(crate) fn decode_version(input: &mut &[u8]) -> Result<(), DecodeError> {
+    match u8::decode(input)? {
+        FORMAT_VERSION => Ok(()),
+        version => Err(DecodeError::UnsupportedVersion(version)),
+    }
+}
+
+/// Fails if anything is left of `input` once a value is decoded
+pub(crate) fn expect_end(input: &[u8]) -> Result<(), DecodeError> {
+    if input.is_empty() {
+        Ok(())
+    } else {
+        Err(DecodeError::TrailingBytes(input.len()))
+    }
+}
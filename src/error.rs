@@ -0,0 +1,342 @@
+use core::fmt;
+use alloc::format;
+use alloc::string::String;
+
+/// Returned by `CountMinSketch::from_matrix` when the provided matrix 
+/// cannot back a sketch, and by `CountMinSketch::fold_width` when the 
+/// sketch cannot be folded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShapeError {
+    /// The matrix has no rows, or its first row has no columns
+    Empty,
+    /// A row's length differs from the length of the first row
+    Jagged { row: usize, expected: usize, found: usize },
+    /// Only a sketch of even width can be folded in half
+    OddWidth(usize),
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::Empty => {
+                write!(f, "matrix must have at least one row and one column")
+            },
+            ShapeError::Jagged { row, expected, found } => {
+                write!(
+                    f, 
+                    "matrix row {} has {} columns, expected {}", 
+                    row, found, expected
+                )
+            },
+            ShapeError::OddWidth(width) => {
+                write!(f, "cannot fold a sketch of odd width {}", width)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ShapeError {}
+
+/// Returned by `CountMinSketch::try_new_from_bounds` and 
+/// `CountMinSketch::recommended_dimensions` when a sizing parameter is 
+/// out of range, and by `LightHonestPeerBuilder::build` when one is 
+/// missing. Each `Invalid*` variant carries the rejected value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CmsParamError {
+    /// The overestimation probability must be within `(0, 1)`
+    InvalidProbability(f64),
+    /// The error bound must be finite and greater than 0
+    InvalidErrorBound(f64),
+    /// The maximum number of entries must be finite and greater than 0
+    InvalidMaxEntries(f64),
+    /// Some but not all of the sizing parameters were set, carrying the 
+    /// name of the first one missing
+    MissingParameter(&'static str),
+}
+
+impl fmt::Display for CmsParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CmsParamError::InvalidProbability(p) => {
+                write!(f, "probability must be between 0 and 1 exclusive, got {}", p)
+            },
+            CmsParamError::InvalidErrorBound(e) => {
+                write!(f, "error bound must be finite and positive, got {}", e)
+            },
+            CmsParamError::InvalidMaxEntries(n) => {
+                write!(f, "max entries must be finite and positive, got {}", n)
+            },
+            CmsParamError::MissingParameter(name) => {
+                write!(f, "sizing parameter `{}` is not set", name)
+            }
+        }
+    }
+}
+
+impl core::error::Error for CmsParamError {}
+
+/// Returned by the `try_update_*` methods of a trust store in strict 
+/// mode when the updated peer was never initialized
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownPeerError<K> {
+    /// The peer the update was for
+    pub key: K,
+}
+
+impl<K: fmt::Debug> fmt::Display for UnknownPeerError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "peer {:?} must be initialized before it is updated", self.key)
+    }
+}
+
+impl<K: fmt::Debug> core::error::Error for UnknownPeerError<K> {}
+
+/// Returned when merging sketches, or trust stores built on them, whose 
+/// cells do not line up
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeError {
+    /// The sketches have different `(width, depth)` dimensions
+    DimensionMismatch { expected: (usize, usize), found: (usize, usize) },
+    /// The sketches have the same dimensions but different hash seeds
+    SeedMismatch,
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::DimensionMismatch { expected, found } => {
+                write!(
+                    f, 
+                    "cannot merge a {}x{} sketch into a {}x{} sketch", 
+                    found.0, found.1, expected.0, expected.1
+                )
+            },
+            MergeError::SeedMismatch => {
+                write!(f, "cannot merge sketches with different hash seeds")
+            }
+        }
+    }
+}
+
+impl core::error::Error for MergeError {}
+
+/// Returned by the `from_bytes` constructors when the input is not a 
+/// valid encoding
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The version tag is not one this version of the crate can read
+    UnsupportedVersion(u8),
+    /// The input ended in the middle of a value
+    UnexpectedEnd,
+    /// A string is not valid UTF-8
+    InvalidUtf8,
+    /// An enum or flag has a tag with no matching variant
+    InvalidTag(u8),
+    /// The decoded sketch matrix is not a valid shape
+    Shape(ShapeError),
+    /// Bytes are left over after the value was decoded
+    TrailingBytes(usize),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported encoding version {}", version)
+            },
+            DecodeError::UnexpectedEnd => write!(f, "input ended unexpectedly"),
+            DecodeError::InvalidUtf8 => write!(f, "string is not valid utf-8"),
+            DecodeError::InvalidTag(tag) => write!(f, "invalid tag {}", tag),
+            DecodeError::Shape(err) => write!(f, "invalid sketch shape: {}", err),
+            DecodeError::TrailingBytes(n) => {
+                write!(f, "{} bytes left over after decoding", n)
+            }
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+impl From<ShapeError> for DecodeError {
+    fn from(err: ShapeError) -> Self {
+        DecodeError::Shape(err)
+    }
+}
+
+/// Returned by the `try_decrement` family when a decrement would take 
+/// a value below zero, i.e. when the flooring `decrement` would have 
+/// silently discarded part of the delta. Nothing is modified when this 
+/// is returned.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnderflowError<T> {
+    /// The amount the caller tried to decrement by
+    pub attempted: T,
+    /// The amount that was available to decrement
+    pub available: T,
+}
+
+impl<T: fmt::Debug> fmt::Display for UnderflowError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f, 
+            "attempted to decrement by {:?} but only {:?} is available", 
+            self.attempted, self.available
+        )
+    }
+}
+
+impl<T: fmt::Debug> core::error::Error for UnderflowError<T> {}
+
+/// Returned by `PreciseHonestPeer::init_from_normalized_local` when the 
+/// provided values are not a distribution, i.e. some value is outside 
+/// `[0, 1]` or they do not sum to 1. Nothing is modified when this is 
+/// returned.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistributionError<T> {
+    /// The sum of the provided values
+    pub total: T,
+}
+
+impl<T: fmt::Debug> fmt::Display for DistributionError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected a distribution summing to 1, values sum to {:?}", self.total)
+    }
+}
+
+impl<T: fmt::Debug> core::error::Error for DistributionError<T> {}
+
+/// A single error type covering every fallible operation in the crate, 
+/// for callers that would rather propagate one type with `?` than 
+/// handle each method's own error. Every specific error converts into 
+/// it with `From`. Errors that are generic over a key or value type 
+/// keep that key or value as its `Debug` representation.
+///
+/// Fallible methods keep returning their own error type rather than 
+/// this enum. Those types carry the typed key or value, e.g. 
+/// `UnderflowError::available` as a `T` a caller can retry with, which 
+/// this enum can only hold as a string, and each names exactly the 
+/// failures its method can produce. Callers wanting one type lose 
+/// nothing by converting with `?`.
+///
+/// ```
+/// use decentrust::cms::CountMinSketch;
+/// use decentrust::error::DecentrustError;
+///
+/// fn build() -> Result<CountMinSketch<u32>, DecentrustError> {
+///     let cms = CountMinSketch::try_new_from_bounds(50.0, 1.5, 1000.0, 0, 100)?;
+///     Ok(cms)
+/// }
+///
+/// assert!(matches!(build(), Err(DecentrustError::InvalidParameter(_))));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecentrustError {
+    /// Two sketches with different `(width, depth)` dimensions were 
+    /// combined
+    DimensionMismatch { expected: (usize, usize), found: (usize, usize) },
+    /// Two sketches with different hash seeds were combined
+    SeedMismatch,
+    /// A matrix cannot back a sketch
+    Shape(ShapeError),
+    /// A sizing parameter is out of range
+    InvalidParameter(CmsParamError),
+    /// A decrement would have taken a value below zero
+    Underflow { attempted: String, available: String },
+    /// An input is not a valid encoding
+    Decode(DecodeError),
+    /// A peer was updated in strict mode before it was initialized
+    Uninitialized { key: String },
+    /// Values expected to form a distribution do not
+    InvalidDistribution { total: String },
+}
+
+impl fmt::Display for DecentrustError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecentrustError::DimensionMismatch { expected, found } => {
+                MergeError::DimensionMismatch { 
+                    expected: *expected, 
+                    found: *found 
+                }.fmt(f)
+            },
+            DecentrustError::SeedMismatch => MergeError::SeedMismatch.fmt(f),
+            DecentrustError::Shape(err) => err.fmt(f),
+            DecentrustError::InvalidParameter(err) => err.fmt(f),
+            DecentrustError::Underflow { attempted, available } => {
+                write!(
+                    f, 
+                    "attempted to decrement by {} but only {} is available", 
+                    attempted, available
+                )
+            },
+            DecentrustError::Decode(err) => err.fmt(f),
+            DecentrustError::Uninitialized { key } => {
+                write!(f, "peer {} must be initialized before it is updated", key)
+            },
+            DecentrustError::InvalidDistribution { total } => {
+                write!(f, "expected a distribution summing to 1, values sum to {}", total)
+            }
+        }
+    }
+}
+
+impl core::error::Error for DecentrustError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            DecentrustError::Shape(err) => Some(err),
+            DecentrustError::InvalidParameter(err) => Some(err),
+            DecentrustError::Decode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<MergeError> for DecentrustError {
+    fn from(err: MergeError) -> Self {
+        match err {
+            MergeError::DimensionMismatch { expected, found } => {
+                DecentrustError::DimensionMismatch { expected, found }
+            },
+            MergeError::SeedMismatch => DecentrustError::SeedMismatch,
+        }
+    }
+}
+
+impl From<ShapeError> for DecentrustError {
+    fn from(err: ShapeError) -> Self {
+        DecentrustError::Shape(err)
+    }
+}
+
+impl From<CmsParamError> for DecentrustError {
+    fn from(err: CmsParamError) -> Self {
+        DecentrustError::InvalidParameter(err)
+    }
+}
+
+impl<T: fmt::Debug> From<UnderflowError<T>> for DecentrustError {
+    fn from(err: UnderflowError<T>) -> Self {
+        DecentrustError::Underflow { 
+            attempted: format!("{:?}", err.attempted), 
+            available: format!("{:?}", err.available) 
+        }
+    }
+}
+
+impl From<DecodeError> for DecentrustError {
+    fn from(err: DecodeError) -> Self {
+        DecentrustError::Decode(err)
+    }
+}
+
+impl<K: fmt::Debug> From<UnknownPeerError<K>> for DecentrustError {
+    fn from(err: UnknownPeerError<K>) -> Self {
+        DecentrustError::Uninitialized { key: format!("{:?}", err.key) }
+    }
+}
+
+impl<T: fmt::Debug> From<DistributionError<T>> for DecentrustError {
+    fn from(err: DistributionError<T>) -> Self {
+        DecentrustError::InvalidDistribution { total: format!("{:?}", err.total) }
+    }
+}
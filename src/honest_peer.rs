@@ -8,17 +8,178 @@ use std::ops::{
     Mul
 };
 
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::hash::Hash;
 
-use num_traits::Bounded;
+use buckets::bucketize::BucketizeSingle;
+use num_traits::{Bounded, One, ToPrimitive};
+#[cfg(feature = "rand")]
+use rand::Rng;
 use serde::{Serialize, Deserialize};
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+use crate::util::{trust_approx_eq, is_invalid, saturating_add};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Update {
     Increment,
     Decrement
 }
 
+/// A single trust operation, one variant per mutating `HonestPeer` 
+/// method, with the arguments it was called with. Recording the ops 
+/// applied to an instance and replaying them in order with `apply_op` 
+/// rebuilds the same state on a fresh instance, e.g. to replicate a 
+/// node's trust from a serialized log.
+///
+/// ```
+/// use decentrust::honest_peer::{HonestPeer, TrustOp, Update};
+/// use decentrust::precise::PreciseHonestPeer;
+///
+/// let log: Vec<TrustOp<String, u64>> = vec![
+///     TrustOp::InitLocal { key: "node1".to_string(), value: 5 },
+///     TrustOp::UpdateLocal { key: "node1".to_string(), delta: 2, update: Update::Increment },
+/// ];
+///
+/// let json = serde_json::to_string(&log).unwrap();
+/// let replayed: Vec<TrustOp<String, u64>> = serde_json::from_str(&json).unwrap();
+///
+/// let mut hp: PreciseHonestPeer<String, u64> = PreciseHonestPeer::new();
+/// replayed.into_iter().for_each(|op| hp.apply_op(op));
+///
+/// assert_eq!(hp.get_raw_local(&"node1".to_string()), Some(7));
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TrustOp<K, V> {
+    /// See `HonestPeer::init_local`
+    InitLocal { key: K, value: V },
+    /// See `HonestPeer::update_local`
+    UpdateLocal { key: K, delta: V, update: Update },
+    /// See `HonestPeer::reset_local`
+    ResetLocal { key: K, value: V },
+    /// See `HonestPeer::init_global`
+    InitGlobal { sender: K, key: K, value: V },
+    /// See `HonestPeer::update_global`
+    UpdateGlobal { sender: K, key: K, delta: V, update: Update },
+    /// See `HonestPeer::update_global_multi`
+    UpdateGlobalMulti { reports: Vec<(K, V)>, key: K, update: Update },
+    /// See `HonestPeer::reset_global`
+    ResetGlobal { key: K, value: V },
+}
+
+/// How raw trust values are turned into normalized trust values
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NormalizationMode {
+    /// Each value is divided by the sum of all values, so normalized 
+    /// values sum to 1. A single huge score crushes every other one.
+    #[default]
+    Sum,
+    /// Each value is replaced by its percentile rank, the fraction of 
+    /// peers whose value is less than or equal to it. The most trusted 
+    /// peer ranks 1 and outliers only affect the order, not the scale.
+    Rank,
+}
+
+/// A coarse trust level, for sharing how much a peer is trusted 
+/// without revealing its exact score, see `HonestPeer::local_tier`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TrustTier {
+    /// Below the first threshold
+    Low,
+    /// At or above the first threshold but below the last one
+    Medium,
+    /// At or above the last threshold
+    High,
+}
+
+impl TrustTier {
+    /// Maps a value into a tier given ascending `thresholds`, each the 
+    /// lowest value of the next tier up. A value equal to a threshold 
+    /// reaches it. With a single threshold only `Low` and `High` are 
+    /// possible, with two they split the values into all three tiers, 
+    /// and with more every band between the first and the last one is 
+    /// `Medium`. Without thresholds every value is `Low`.
+    ///
+    /// ```
+    /// use decentrust::honest_peer::TrustTier;
+    ///
+    /// let thresholds = [0.2, 0.6];
+    ///
+    /// assert_eq!(TrustTier::from_thresholds(0.1, &thresholds), TrustTier::Low);
+    /// assert_eq!(TrustTier::from_thresholds(0.2, &thresholds), TrustTier::Medium);
+    /// assert_eq!(TrustTier::from_thresholds(0.9, &thresholds), TrustTier::High);
+    /// ```
+    pub fn from_thresholds<V: PartialOrd>(value: V, thresholds: &[V]) -> TrustTier {
+        let reached = thresholds.iter().filter(|threshold| value >= **threshold).count();
+        match reached {
+            0 => TrustTier::Low,
+            n if n == thresholds.len() => TrustTier::High,
+            _ => TrustTier::Medium,
+        }
+    }
+}
+
+/// A snapshot of the local trust diagnostics of an `HonestPeer` 
+/// implementation, see `HonestPeer::summary`. Its `Display` output is 
+/// a single line meant for logging once per epoch.
+///
+/// ```
+/// use decentrust::honest_peer::HonestPeer;
+/// use decentrust::precise::PreciseHonestPeer;
+/// use ordered_float::OrderedFloat;
+///
+/// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+/// hp.init_local(&"node1", 3f64.into());
+/// hp.init_local(&"node2", 1f64.into());
+///
+/// let summary = hp.summary();
+/// assert_eq!(summary.peer_count, 2);
+/// assert_eq!(summary.top_peer, Some(("node1", OrderedFloat::from(0.75))));
+/// assert_eq!(
+///     summary.to_string(), 
+///     "peers: 2, total trust: 4, top peer: node1 (0.75), entropy: 0.5623"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrustSummary<K, V> {
+    /// The number of known local peers
+    pub peer_count: usize,
+    /// The sum of the raw local trust of every known peer
+    pub total_trust: V,
+    /// The peer with the highest normalized local trust and its 
+    /// normalized value, see `HonestPeer::most_trusted_local`
+    pub top_peer: Option<(K, V)>,
+    /// See `HonestPeer::local_trust_entropy`
+    pub entropy: f64,
+    /// The number of local keys per sketch column, for sketch backed 
+    /// implementations, see `LightHonestPeer::load_factor`
+    pub load_factor: Option<f64>,
+    /// The number of local key pairs sharing a sketch cell, for sketch 
+    /// backed implementations, see 
+    /// `LightHonestPeer::estimated_collision_count`
+    pub estimated_collisions: Option<usize>,
+}
+
+impl<K: fmt::Display, V: fmt::Display> fmt::Display for TrustSummary<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "peers: {}, total trust: {}, ", self.peer_count, self.total_trust)?;
+        match &self.top_peer {
+            Some((key, value)) => write!(f, "top peer: {} ({}), ", key, value)?,
+            None => write!(f, "top peer: none, ")?,
+        }
+        write!(f, "entropy: {:.4}", self.entropy)?;
+        if let Some(load_factor) = self.load_factor {
+            write!(f, ", load factor: {:.4}", load_factor)?;
+        }
+        if let Some(collisions) = self.estimated_collisions {
+            write!(f, ", estimated collisions: {}", collisions)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// A trait to implement a shared interface between a 
 /// precise and proabilistic data structures to track P2P node 
 pub trait HonestPeer {
@@ -34,7 +195,8 @@ pub trait HonestPeer {
         + PartialOrd
         + Copy 
         + Default 
-        + Bounded; 
+        + Bounded
+        + One; 
 
     fn init_local(&mut self, key: &Self::Key, init_value: Self::Value);
     fn update_local(&mut self, key: &Self::Key, trust_delta: Self::Value, update: Update);
@@ -42,6 +204,11 @@ pub trait HonestPeer {
     fn get_normalized_local(&self, key: &Self::Key) -> Option<Self::Value>;
     fn init_global(&mut self, sender: &Self::Key, key: &Self::Key, init_value: Self::Value);
     fn update_global(&mut self, sender: &Self::Key, key: &Self::Key, trust_delta: Self::Value, update: Update);
+    fn update_global_multi(&mut self, reports: &[(Self::Key, Self::Value)], key: &Self::Key, update: Update);
+    fn init_local_batch(&mut self, peers: impl IntoIterator<Item = (Self::Key, Self::Value)>);
+    fn init_global_batch(&mut self, sender: &Self::Key, peers: impl IntoIterator<Item = (Self::Key, Self::Value)>);
+    fn reset_local(&mut self, key: &Self::Key, value: Self::Value);
+    fn reset_global(&mut self, key: &Self::Key, value: Self::Value);
     fn get_raw_global(&self, key: &Self::Key) -> Option<Self::Value>;
     fn get_normalized_global(&self, key: &Self::Key) -> Option<Self::Value>;
     fn get_raw_local_map(&self) -> Self::Map;
@@ -54,4 +221,832 @@ pub trait HonestPeer {
     fn local_normalized_len(&self) -> usize;
     fn global_raw_len(&self) -> usize;
     fn global_normalized_len(&self) -> usize;
+    fn local_keys(&self) -> Vec<Self::Key>;
+    fn global_keys(&self) -> Vec<Self::Key>;
+    fn value_bounds(&self) -> (Self::Value, Self::Value);
+    fn is_exact(&self) -> bool;
+    fn normalization_target(&self) -> Option<Self::Value>;
+
+    /// Returns the normalized local trust for each of the given keys, 
+    /// in the same order, with `None` for unknown peers
+    fn get_normalized_local_many(&self, keys: &[Self::Key]) -> Vec<Option<Self::Value>> {
+        keys.iter().map(|k| self.get_normalized_local(k)).collect()
+    }
+
+    /// Returns the normalized global trust for each of the given keys, 
+    /// in the same order, with `None` for unknown peers
+    fn get_normalized_global_many(&self, keys: &[Self::Key]) -> Vec<Option<Self::Value>> {
+        keys.iter().map(|k| self.get_normalized_global(k)).collect()
+    }
+
+    /// Returns `key`'s raw local trust normalized against the sum of 
+    /// the raw local trust of the peers in `subset` only, rather than 
+    /// every known peer. Duplicate and unknown keys in `subset` are 
+    /// ignored, and `key` itself does not have to be a member. Returns 
+    /// `None` if `key` is unknown or the subset sums to zero.
+    ///
+    /// ```
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node1", 1f64.into());
+    /// hp.init_local(&"node2", 3f64.into());
+    /// hp.init_local(&"node3", 4f64.into());
+    ///
+    /// let within = hp.get_normalized_local_within(&"node1", &["node1", "node2"]);
+    /// assert_eq!(within, Some(OrderedFloat::from(0.25)));
+    /// ```
+    fn get_normalized_local_within(&self, key: &Self::Key, subset: &[Self::Key]) -> Option<Self::Value> {
+        let raw = self.get_raw_local(key)?;
+        let members: HashSet<&Self::Key> = subset.iter().collect();
+        let total = members.into_iter()
+            .filter_map(|k| self.get_raw_local(k))
+            .fold(Self::Value::default(), |acc, v| acc + v);
+
+        if total == Self::Value::default() {
+            return None;
+        }
+
+        Some(raw / total)
+    }
+
+    /// Sets a peer's raw local trust to an exponential moving average of 
+    /// the observations made of it, `alpha * observation + (1 - alpha) * 
+    /// old`, with `alpha` expected within `[0, 1]`, and normalizes the 
+    /// local trust map. Unknown peers start from `Value::default()`. The 
+    /// new value is stored with `reset_local`, so on sketch-based stores 
+    /// colliding peers keep their share of the shared cells.
+    ///
+    /// ```
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node1", 2f64.into());
+    /// hp.update_local_ema(&"node1", 4f64.into(), 0.25.into());
+    ///
+    /// assert_eq!(hp.get_raw_local(&"node1"), Some(OrderedFloat::from(2.5)));
+    /// ```
+    fn update_local_ema(&mut self, key: &Self::Key, observation: Self::Value, alpha: Self::Value) {
+        let old = self.get_raw_local(key).unwrap_or_default();
+        let one = Self::Value::one();
+        self.reset_local(key, alpha * observation + (one - alpha) * old);
+    }
+
+    /// Blends a peer's normalized local and global trust into a single 
+    /// score, `alpha * local + (1 - alpha) * global`, with `alpha` 
+    /// expected within `[0, 1]`. A side the peer is unknown to counts 
+    /// as zero, and `None` is returned if it is unknown to both.
+    ///
+    /// ```
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node1", 1f64.into());
+    /// hp.init_local(&"node2", 1f64.into());
+    /// hp.init_global(&"node1", &"node2", 1f64.into());
+    ///
+    /// // local trust of 0.5 and global trust of 1.0
+    /// let combined = hp.combined_trust(&"node2", 0.5.into());
+    /// assert_eq!(combined, Some(OrderedFloat::from(0.75)));
+    /// assert_eq!(hp.combined_trust(&"node3", 0.5.into()), None);
+    /// ```
+    fn combined_trust(&self, key: &Self::Key, alpha: Self::Value) -> Option<Self::Value> {
+        let local = self.get_normalized_local(key);
+        let global = self.get_normalized_global(key);
+        if local.is_none() && global.is_none() {
+            return None;
+        }
+
+        let one = Self::Value::one();
+        Some(
+            alpha * local.unwrap_or_default() 
+            + (one - alpha) * global.unwrap_or_default()
+        )
+    }
+
+    /// Returns the peer with the highest normalized local trust and 
+    /// its normalized value, or `None` if no peers are known. If 
+    /// several peers share the highest value the smallest key is 
+    /// returned.
+    fn most_trusted_local(&self) -> Option<(Self::Key, Self::Value)> 
+    where
+        Self::Key: Ord
+    {
+        self.top_k_local(1).into_iter().next()
+    }
+
+    /// Returns the peer with the lowest normalized local trust and 
+    /// its normalized value, or `None` if no peers are known. If 
+    /// several peers share the lowest value the smallest key is 
+    /// returned.
+    fn least_trusted_local(&self) -> Option<(Self::Key, Self::Value)> 
+    where
+        Self::Key: Ord
+    {
+        self.local_keys().into_iter()
+            .filter_map(|k| self.get_normalized_local(&k).map(|v| (k, v)))
+            .min_by(ascending_trust)
+    }
+
+    /// Returns the peer with the highest normalized global trust and 
+    /// its normalized value, or `None` if no peers are known. If 
+    /// several peers share the highest value the smallest key is 
+    /// returned.
+    fn most_trusted_global(&self) -> Option<(Self::Key, Self::Value)> 
+    where
+        Self::Key: Ord
+    {
+        self.top_k_global(1).into_iter().next()
+    }
+
+    /// Returns the peer with the lowest normalized global trust and 
+    /// its normalized value, or `None` if no peers are known. If 
+    /// several peers share the lowest value the smallest key is 
+    /// returned.
+    fn least_trusted_global(&self) -> Option<(Self::Key, Self::Value)> 
+    where
+        Self::Key: Ord
+    {
+        self.global_keys().into_iter()
+            .filter_map(|k| self.get_normalized_global(&k).map(|v| (k, v)))
+            .min_by(ascending_trust)
+    }
+
+    /// Returns every known peer with its normalized local trust, from 
+    /// most to least trusted, for ranked peer selection. Peers with 
+    /// equal trust are ordered by key, so the result is the same across 
+    /// runs. Sketch backed implementations rank the estimates of the 
+    /// retained local keys.
+    ///
+    /// ```
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node2", 1f64.into());
+    /// hp.init_local(&"node3", 2f64.into());
+    /// hp.init_local(&"node1", 1f64.into());
+    ///
+    /// let ranked: Vec<&str> = hp.normalized_local_sorted().into_iter().map(|(k, _)| k).collect();
+    /// assert_eq!(ranked, vec!["node3", "node1", "node2"]);
+    /// ```
+    fn normalized_local_sorted(&self) -> Vec<(Self::Key, Self::Value)> 
+    where
+        Self::Key: Ord
+    {
+        let mut ranked: Vec<(Self::Key, Self::Value)> = self.local_keys().into_iter()
+            .filter_map(|key| self.get_normalized_local(&key).map(|v| (key, v)))
+            .collect();
+        ranked.sort_by(descending_trust);
+        ranked
+    }
+
+    /// Returns up to `k` peers with the highest normalized local trust 
+    /// and their normalized values, from most to least trusted. Peers 
+    /// with equal trust are ordered by key, so the result is the same 
+    /// across runs.
+    fn top_k_local(&self, k: usize) -> Vec<(Self::Key, Self::Value)> 
+    where
+        Self::Key: Ord
+    {
+        let mut ranked = self.normalized_local_sorted();
+        ranked.truncate(k);
+        ranked
+    }
+
+    /// Returns up to `k` peers with the highest normalized global trust 
+    /// and their normalized values, from most to least trusted. Peers 
+    /// with equal trust are ordered by key, so the result is the same 
+    /// across runs.
+    fn top_k_global(&self, k: usize) -> Vec<(Self::Key, Self::Value)> 
+    where
+        Self::Key: Ord
+    {
+        let mut ranked: Vec<(Self::Key, Self::Value)> = self.global_keys().into_iter()
+            .filter_map(|key| self.get_normalized_global(&key).map(|v| (key, v)))
+            .collect();
+        ranked.sort_by(descending_trust);
+        ranked.truncate(k);
+        ranked
+    }
+
+    /// Returns the Shannon entropy, in nats, of the normalized local 
+    /// trust distribution over the known peers. Weights are rescaled by 
+    /// their sum as in `sample_local_weighted`, and peers without 
+    /// positive trust are skipped. Low entropy means trust is 
+    /// concentrated in few peers, the maximum of `ln(n)` is reached 
+    /// when `n` peers are trusted equally. Returns `0.0` if no peer has 
+    /// a positive normalized trust.
+    ///
+    /// ```
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node1", 1f64.into());
+    /// hp.init_local(&"node2", 1f64.into());
+    ///
+    /// assert!((hp.local_trust_entropy() - 2f64.ln()).abs() < 1e-9);
+    /// assert!((hp.effective_peer_count() - 2.0).abs() < 1e-9);
+    /// ```
+    fn local_trust_entropy(&self) -> f64 
+    where
+        Self::Value: ToPrimitive
+    {
+        let weights: Vec<f64> = self.local_keys().into_iter()
+            .filter_map(|key| self.get_normalized_local(&key)?.to_f64())
+            .filter(|weight| *weight > 0.0)
+            .collect();
+        let total: f64 = weights.iter().sum();
+
+        weights.into_iter()
+            .map(|weight| {
+                let p = weight / total;
+                -p * p.ln()
+            })
+            // summing from 0.0 rather than with `sum`, which starts at -0.0
+            .fold(0.0, |acc, h| acc + h)
+            .max(0.0)
+    }
+
+    /// Returns the effective number of trusted peers, `exp` of 
+    /// `local_trust_entropy`. `n` equally trusted peers give `n`, and 
+    /// the closer it is to 1 the more trust is held by a single peer. 
+    /// Returns `0.0` if no peer has a positive normalized trust.
+    fn effective_peer_count(&self) -> f64 
+    where
+        Self::Value: ToPrimitive
+    {
+        let any_trusted = self.local_keys().into_iter()
+            .filter_map(|key| self.get_normalized_local(&key)?.to_f64())
+            .any(|weight| weight > 0.0);
+
+        if any_trusted {
+            self.local_trust_entropy().exp()
+        } else {
+            0.0
+        }
+    }
+
+    /// Picks a known peer at random with probability proportional to 
+    /// its normalized local trust, e.g. to spread requests across peers 
+    /// according to how much they are trusted. Weights are rescaled by 
+    /// their sum, so estimates that do not add up to exactly 1 (as with 
+    /// `LightHonestPeer`) are still sampled proportionally. Returns 
+    /// `None` if no peer has a positive normalized trust.
+    ///
+    /// Requires the `rand` feature.
+    #[cfg(feature = "rand")]
+    fn sample_local_weighted(&self, rng: &mut impl Rng) -> Option<Self::Key> 
+    where
+        Self::Value: ToPrimitive
+    {
+        let weighted: Vec<(Self::Key, f64)> = self.local_keys().into_iter()
+            .filter_map(|key| {
+                let weight = self.get_normalized_local(&key)?.to_f64()?;
+                (weight > 0.0 && weight.is_finite()).then_some((key, weight))
+            })
+            .collect();
+
+        let total: f64 = weighted.iter().map(|(_, weight)| weight).sum();
+        let mut target = rng.gen::<f64>() * total;
+        let last = weighted.last().map(|(key, _)| key.clone());
+
+        for (key, weight) in weighted {
+            if target < weight {
+                return Some(key);
+            }
+            target -= weight;
+        }
+
+        // only reached through floating point rounding in the running 
+        // subtraction, or if there are no candidates at all
+        last
+    }
+
+    /// Returns whether the normalized local trust of a given peer is 
+    /// within `epsilon` of `expected`, or `None` if the peer is unknown. 
+    /// See `util::trust_approx_eq`.
+    fn get_normalized_local_approx(
+        &self, 
+        key: &Self::Key, 
+        expected: Self::Value, 
+        epsilon: Self::Value
+    ) -> Option<bool> {
+        self.get_normalized_local(key)
+            .map(|trust| trust_approx_eq(trust, expected, epsilon))
+    }
+
+    /// Returns whether the normalized global trust of a given peer is 
+    /// within `epsilon` of `expected`, or `None` if the peer is unknown. 
+    /// See `util::trust_approx_eq`.
+    fn get_normalized_global_approx(
+        &self, 
+        key: &Self::Key, 
+        expected: Self::Value, 
+        epsilon: Self::Value
+    ) -> Option<bool> {
+        self.get_normalized_global(key)
+            .map(|trust| trust_approx_eq(trust, expected, epsilon))
+    }
+
+    /// Returns whether the normalized local trust of the known peers is 
+    /// a valid distribution, i.e. every value is within `[0, 1]` and 
+    /// they sum to 1 within `epsilon`, or within `[0, target]` summing 
+    /// to `target` once a normalization target is set. Sketch-based 
+    /// stores check the estimates of their retained keys, whose 
+    /// collisions can push the sum above 1, so they need a looser 
+    /// `epsilon`, as do integer values, which truncate. Holds trivially 
+    /// when no peers are known, and is not expected to hold under 
+    /// `NormalizationMode::Rank`.
+    ///
+    /// ```
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node1", 1f64.into());
+    /// hp.init_local(&"node2", 2f64.into());
+    ///
+    /// assert!(hp.validate_normalized_local(1e-9.into()));
+    /// ```
+    fn validate_normalized_local(&self, epsilon: Self::Value) -> bool {
+        let values = self.local_keys().into_iter()
+            .map(|key| self.get_normalized_local(&key));
+        is_distribution(values, self.normalization_target(), epsilon)
+    }
+
+    /// Returns whether the normalized global trust of the known peers is 
+    /// a valid distribution, see `validate_normalized_local`
+    fn validate_normalized_global(&self, epsilon: Self::Value) -> bool {
+        let values = self.global_keys().into_iter()
+            .map(|key| self.get_normalized_global(&key));
+        is_distribution(values, self.normalization_target(), epsilon)
+    }
+
+    /// Applies a recorded trust operation by calling the method it 
+    /// stands for, see `TrustOp`
+    fn apply_op(&mut self, op: TrustOp<Self::Key, Self::Value>) {
+        match op {
+            TrustOp::InitLocal { key, value } => self.init_local(&key, value),
+            TrustOp::UpdateLocal { key, delta, update } => {
+                self.update_local(&key, delta, update)
+            },
+            TrustOp::ResetLocal { key, value } => self.reset_local(&key, value),
+            TrustOp::InitGlobal { sender, key, value } => {
+                self.init_global(&sender, &key, value)
+            },
+            TrustOp::UpdateGlobal { sender, key, delta, update } => {
+                self.update_global(&sender, &key, delta, update)
+            },
+            TrustOp::UpdateGlobalMulti { reports, key, update } => {
+                self.update_global_multi(&reports, &key, update)
+            },
+            TrustOp::ResetGlobal { key, value } => self.reset_global(&key, value),
+        }
+    }
+
+    /// Bucketizes the raw local trust of the given peers, yielding 
+    /// `(key, bucket)` pairs in the order the keys are given. This has 
+    /// the same shape for every backend, so it can be used generically 
+    /// where the inherent `bucketize_local` methods differ. It is not 
+    /// named `bucketize_local` itself because method call syntax on a 
+    /// concrete store picks the inherent method over a trait method of 
+    /// the same name, so the trait method would only be reachable 
+    /// through generics or `HonestPeer::bucketize_local(..)`. Keys without 
+    /// a raw local value are skipped, so an exact backend only yields 
+    /// known peers while an estimating one yields every key. Raw values 
+    /// are passed to the bucketizer as they are, so whether a boundary 
+    /// is inclusive or exclusive is decided by the bucketizer alone and 
+    /// equal values get equal buckets on every backend.
+    ///
+    /// ```
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use buckets::bucketizers::fw::FixedWidthBucketizer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// fn buckets<H: HonestPeer<Key = &'static str, Value = OrderedFloat<f64>>>(
+    ///     hp: &H
+    /// ) -> Vec<(&'static str, usize)> {
+    ///     let bucketizer = FixedWidthBucketizer::new(5f64.into(), 0f64.into());
+    ///     hp.bucketize_local_keys(["node1", "node2", "node3"].into_iter(), bucketizer).collect()
+    /// }
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node1", 3f64.into());
+    /// hp.init_local(&"node3", 12f64.into());
+    ///
+    /// assert_eq!(buckets(&hp), vec![("node1", 0), ("node3", 2)]);
+    /// ```
+    fn bucketize_local_keys<'a, B>(
+        &'a self, 
+        keys: impl Iterator<Item = Self::Key> + 'a, 
+        bucketizer: B
+    ) -> impl Iterator<Item = (Self::Key, usize)> + 'a
+    where 
+        B: BucketizeSingle<Self::Value> + 'a
+    {
+        keys.filter_map(move |key| {
+            let value = self.get_raw_local(&key)?;
+            Some((key, bucketizer.bucketize(&value)))
+        })
+    }
+
+    /// Returns the tier of a peer's normalized local trust given 
+    /// ascending `thresholds`, see `TrustTier::from_thresholds`, or 
+    /// `None` if the peer is unknown
+    ///
+    /// ```
+    /// use decentrust::honest_peer::{HonestPeer, TrustTier};
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node1", 1f64.into());
+    /// hp.init_local(&"node2", 3f64.into());
+    ///
+    /// let thresholds = [OrderedFloat::from(0.3), OrderedFloat::from(0.7)];
+    /// assert_eq!(hp.local_tier(&"node1", &thresholds), Some(TrustTier::Low));
+    /// assert_eq!(hp.local_tier(&"node2", &thresholds), Some(TrustTier::High));
+    /// ```
+    fn local_tier(&self, key: &Self::Key, thresholds: &[Self::Value]) -> Option<TrustTier> {
+        self.get_normalized_local(key)
+            .map(|value| TrustTier::from_thresholds(value, thresholds))
+    }
+
+    /// Returns the tier of a peer's normalized global trust given 
+    /// ascending `thresholds`, see `local_tier`
+    fn global_tier(&self, key: &Self::Key, thresholds: &[Self::Value]) -> Option<TrustTier> {
+        self.get_normalized_global(key)
+            .map(|value| TrustTier::from_thresholds(value, thresholds))
+    }
+
+    /// Returns whether any raw or normalized trust value, local or 
+    /// global, is NaN or infinite. A single bad input poisons every 
+    /// normalized value through the shared total, so this is meant for 
+    /// tests and health checks to catch corruption early. Values are 
+    /// checked through their `f64` conversion, so integer values are 
+    /// never invalid.
+    ///
+    /// ```
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node1", 1f64.into());
+    /// assert!(!hp.has_invalid_values());
+    ///
+    /// hp.init_from_normalized_local(&[("node1", 1f64.into())], f64::INFINITY.into()).unwrap();
+    /// assert!(hp.has_invalid_values());
+    /// ```
+    fn has_invalid_values(&self) -> bool 
+    where
+        Self::Value: ToPrimitive
+    {
+        let local = self.local_keys().into_iter()
+            .flat_map(|key| [self.get_raw_local(&key), self.get_normalized_local(&key)]);
+        let global = self.global_keys().into_iter()
+            .flat_map(|key| [self.get_raw_global(&key), self.get_normalized_global(&key)]);
+
+        local.chain(global).flatten().any(|value| is_invalid(&value))
+    }
+
+    /// Returns the peer count, total raw trust, most trusted peer and 
+    /// entropy of local trust in a single `TrustSummary`. Sketch backed 
+    /// implementations also report their load factor and collisions.
+    fn summary(&self) -> TrustSummary<Self::Key, Self::Value> 
+    where
+        Self::Key: Ord,
+        Self::Value: ToPrimitive
+    {
+        local_summary(self)
+    }
+}
+
+/// An object-safe counterpart of `HonestPeer` with concrete key and 
+/// value types, so different backends can be stored behind a single 
+/// `Box<dyn DynHonestPeer<K, V>>`. Maps are returned as `(key, value)` 
+/// pairs over the known keys rather than as `HonestPeer::Map`. It is 
+/// implemented for every `HonestPeer`, so it never needs to be 
+/// implemented directly.
+///
+/// ```
+/// use decentrust::honest_peer::DynHonestPeer;
+/// use decentrust::precise::PreciseHonestPeer;
+/// use decentrust::probabilistic::LightHonestPeer;
+/// use decentrust::trust::Trust;
+///
+/// let mut backends: Vec<Box<dyn DynHonestPeer<String, Trust>>> = vec![
+///     Box::new(PreciseHonestPeer::new()),
+///     Box::new(LightHonestPeer::new()),
+/// ];
+///
+/// backends.iter_mut().for_each(|hp| hp.init_local(&"node1".to_string(), 2.0.into()));
+///
+/// assert!(backends.iter().all(|hp| {
+///     hp.get_raw_local(&"node1".to_string()) == Some(Trust::from(2.0))
+/// }));
+/// ```
+pub trait DynHonestPeer<K, V> {
+    fn init_local(&mut self, key: &K, init_value: V);
+    fn update_local(&mut self, key: &K, trust_delta: V, update: Update);
+    fn get_raw_local(&self, key: &K) -> Option<V>;
+    fn get_normalized_local(&self, key: &K) -> Option<V>;
+    fn init_global(&mut self, sender: &K, key: &K, init_value: V);
+    fn update_global(&mut self, sender: &K, key: &K, trust_delta: V, update: Update);
+    fn update_global_multi(&mut self, reports: &[(K, V)], key: &K, update: Update);
+    fn get_raw_global(&self, key: &K) -> Option<V>;
+    fn get_normalized_global(&self, key: &K) -> Option<V>;
+    fn reset_local(&mut self, key: &K, value: V);
+    fn reset_global(&mut self, key: &K, value: V);
+    fn get_raw_local_map(&self) -> Vec<(K, V)>;
+    fn get_normalized_local_map(&self) -> Vec<(K, V)>;
+    fn get_raw_global_map(&self) -> Vec<(K, V)>;
+    fn get_normalized_global_map(&self) -> Vec<(K, V)>;
+    fn normalize_local(&mut self);
+    fn normalize_global(&mut self);
+    fn local_raw_len(&self) -> usize;
+    fn global_raw_len(&self) -> usize;
+    fn local_keys(&self) -> Vec<K>;
+    fn global_keys(&self) -> Vec<K>;
+    fn value_bounds(&self) -> (V, V);
+    fn is_exact(&self) -> bool;
+}
+
+impl<H, K, V> DynHonestPeer<K, V> for H 
+where
+    H: HonestPeer<Key = K, Value = V>
+{
+    fn init_local(&mut self, key: &K, init_value: V) {
+        HonestPeer::init_local(self, key, init_value)
+    }
+
+    fn update_local(&mut self, key: &K, trust_delta: V, update: Update) {
+        HonestPeer::update_local(self, key, trust_delta, update)
+    }
+
+    fn get_raw_local(&self, key: &K) -> Option<V> {
+        HonestPeer::get_raw_local(self, key)
+    }
+
+    fn get_normalized_local(&self, key: &K) -> Option<V> {
+        HonestPeer::get_normalized_local(self, key)
+    }
+
+    fn init_global(&mut self, sender: &K, key: &K, init_value: V) {
+        HonestPeer::init_global(self, sender, key, init_value)
+    }
+
+    fn update_global(&mut self, sender: &K, key: &K, trust_delta: V, update: Update) {
+        HonestPeer::update_global(self, sender, key, trust_delta, update)
+    }
+
+    fn update_global_multi(&mut self, reports: &[(K, V)], key: &K, update: Update) {
+        HonestPeer::update_global_multi(self, reports, key, update)
+    }
+
+    fn get_raw_global(&self, key: &K) -> Option<V> {
+        HonestPeer::get_raw_global(self, key)
+    }
+
+    fn get_normalized_global(&self, key: &K) -> Option<V> {
+        HonestPeer::get_normalized_global(self, key)
+    }
+
+    fn reset_local(&mut self, key: &K, value: V) {
+        HonestPeer::reset_local(self, key, value)
+    }
+
+    fn reset_global(&mut self, key: &K, value: V) {
+        HonestPeer::reset_global(self, key, value)
+    }
+
+    fn get_raw_local_map(&self) -> Vec<(K, V)> {
+        HonestPeer::local_keys(self).into_iter()
+            .filter_map(|k| HonestPeer::get_raw_local(self, &k).map(|v| (k, v)))
+            .collect()
+    }
+
+    fn get_normalized_local_map(&self) -> Vec<(K, V)> {
+        HonestPeer::local_keys(self).into_iter()
+            .filter_map(|k| HonestPeer::get_normalized_local(self, &k).map(|v| (k, v)))
+            .collect()
+    }
+
+    fn get_raw_global_map(&self) -> Vec<(K, V)> {
+        HonestPeer::global_keys(self).into_iter()
+            .filter_map(|k| HonestPeer::get_raw_global(self, &k).map(|v| (k, v)))
+            .collect()
+    }
+
+    fn get_normalized_global_map(&self) -> Vec<(K, V)> {
+        HonestPeer::global_keys(self).into_iter()
+            .filter_map(|k| HonestPeer::get_normalized_global(self, &k).map(|v| (k, v)))
+            .collect()
+    }
+
+    fn normalize_local(&mut self) {
+        HonestPeer::normalize_local(self)
+    }
+
+    fn normalize_global(&mut self) {
+        HonestPeer::normalize_global(self)
+    }
+
+    fn local_raw_len(&self) -> usize {
+        HonestPeer::local_raw_len(self)
+    }
+
+    fn global_raw_len(&self) -> usize {
+        HonestPeer::global_raw_len(self)
+    }
+
+    fn local_keys(&self) -> Vec<K> {
+        HonestPeer::local_keys(self)
+    }
+
+    fn global_keys(&self) -> Vec<K> {
+        HonestPeer::global_keys(self)
+    }
+
+    fn value_bounds(&self) -> (V, V) {
+        HonestPeer::value_bounds(self)
+    }
+
+    fn is_exact(&self) -> bool {
+        HonestPeer::is_exact(self)
+    }
+}
+
+/// Returns the change in normalized local trust for every peer whose 
+/// normalized value differs between two snapshots, i.e. `after - before`. 
+/// A peer known to only one side is treated as having a normalized 
+/// trust of `V::default()` on the other, so added peers show up with 
+/// their new value and removed peers with its negation. Decreases are 
+/// only representable if the value type is signed.
+///
+/// ```
+/// use decentrust::honest_peer::{diff_normalized_local, HonestPeer, Update};
+/// use decentrust::precise::PreciseHonestPeer;
+/// use ordered_float::OrderedFloat;
+///
+/// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+/// hp.init_local(&"node1", 1f64.into());
+/// hp.init_local(&"node2", 1f64.into());
+///
+/// let before = hp.clone();
+/// hp.update_local(&"node1", 2f64.into(), Update::Increment);
+///
+/// let diff = diff_normalized_local(&before, &hp);
+/// assert_eq!(diff[&"node1"], OrderedFloat::from(0.25));
+/// assert_eq!(diff[&"node2"], OrderedFloat::from(-0.25));
+/// ```
+pub fn diff_normalized_local<A, B>(before: &A, after: &B) -> HashMap<A::Key, A::Value> 
+where
+    A: HonestPeer,
+    B: HonestPeer<Key = A::Key, Value = A::Value>
+{
+    let keys: HashSet<A::Key> = before.local_keys().into_iter()
+        .chain(after.local_keys())
+        .collect();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let old = before.get_normalized_local(&key).unwrap_or_default();
+            let new = after.get_normalized_local(&key).unwrap_or_default();
+            if old == new {
+                None
+            } else {
+                Some((key, new - old))
+            }
+        })
+        .collect()
+}
+
+/// Returns the median of the scores several senders reported for the 
+/// same peer, with each report weighted by its sender's normalized 
+/// local trust in `weights_from`. Unlike the weighted sum applied by 
+/// `update_global_multi`, a single lying sender cannot drag the result 
+/// arbitrarily far, it takes senders holding half of the total trust 
+/// to move the median. When the weights split evenly the lower of the 
+/// two middle scores is returned. Reports from senders without 
+/// positive trust are ignored, and `V::default()` is returned if no 
+/// report is left.
+///
+/// ```
+/// use decentrust::honest_peer::{weighted_median_report, HonestPeer};
+/// use decentrust::precise::PreciseHonestPeer;
+/// use ordered_float::OrderedFloat;
+///
+/// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+/// hp.init_local(&"node1", 1f64.into());
+/// hp.init_local(&"node2", 1f64.into());
+/// hp.init_local(&"node3", 1f64.into());
+///
+/// let reports = [("node1", 5f64.into()), ("node2", 6f64.into()), ("node3", 1000f64.into())];
+/// assert_eq!(weighted_median_report(&reports, &hp), OrderedFloat::from(6.0));
+/// ```
+pub fn weighted_median_report<H: HonestPeer>(
+    reports: &[(H::Key, H::Value)], 
+    weights_from: &H
+) -> H::Value {
+    let zero = H::Value::default();
+    let mut weighted: Vec<(H::Value, H::Value)> = reports.iter()
+        .filter_map(|(sender, score)| {
+            let weight = weights_from.get_normalized_local(sender)?;
+            (weight > zero).then_some((*score, weight))
+        })
+        .collect();
+    weighted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+    let total = weighted.iter().fold(zero, |acc, (_, weight)| acc + *weight);
+    let mut cumulative = zero;
+    for (score, weight) in weighted {
+        cumulative += weight;
+        if cumulative + cumulative >= total {
+            return score;
+        }
+    }
+
+    zero
+}
+
+/// Returns whether every value is present and within `[0, target]`, 
+/// and the values sum to `target` within `epsilon`, or there are no 
+/// values at all. Without a target the values must sum to 1. NaN fails 
+/// every comparison, so it is never accepted.
+pub(crate) fn is_distribution<V>(
+    values: impl Iterator<Item = Option<V>>, 
+    target: Option<V>, 
+    epsilon: V
+) -> bool 
+where
+    V: Add<Output = V> + Sub<Output = V> + PartialOrd + Copy + Default + One
+{
+    let zero = V::default();
+    let one = target.unwrap_or_else(V::one);
+    let mut total = zero;
+    let mut empty = true;
+
+    for value in values {
+        match value {
+            Some(value) if value >= zero && value <= one => {
+                total = total + value;
+                empty = false;
+            },
+            _ => return false,
+        }
+    }
+
+    empty || trust_approx_eq(total, one, epsilon) || total == one
+}
+
+/// Builds the `TrustSummary` fields every implementation shares, 
+/// leaving the sketch diagnostics unset
+pub(crate) fn local_summary<H>(peer: &H) -> TrustSummary<H::Key, H::Value> 
+where
+    H: HonestPeer + ?Sized,
+    H::Key: Ord,
+    H::Value: ToPrimitive
+{
+    let keys = peer.local_keys();
+    let total_trust = keys.iter()
+        .filter_map(|key| peer.get_raw_local(key))
+        .fold(H::Value::default(), saturating_add);
+
+    TrustSummary {
+        peer_count: keys.len(),
+        total_trust,
+        top_peer: peer.most_trusted_local(),
+        entropy: peer.local_trust_entropy(),
+        load_factor: None,
+        estimated_collisions: None,
+    }
+}
+
+/// Orders `(key, trust)` pairs from lowest to highest trust, breaking 
+/// ties by key. Incomparable values (e.g. NaN) are treated as equal.
+fn ascending_trust<K: Ord, V: PartialOrd>(a: &(K, V), b: &(K, V)) -> Ordering {
+    a.1.partial_cmp(&b.1)
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| a.0.cmp(&b.0))
+}
+
+/// Orders `(key, trust)` pairs from highest to lowest trust, breaking 
+/// ties by key. Incomparable values (e.g. NaN) are treated as equal.
+fn descending_trust<K: Ord, V: PartialOrd>(a: &(K, V), b: &(K, V)) -> Ordering {
+    b.1.partial_cmp(&a.1)
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| a.0.cmp(&b.0))
 }
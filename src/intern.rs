@@ -0,0 +1,84 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::sync::Arc;
+
+/// Shares a single allocation between every copy of a key. Trust stores
+/// clone their keys on every insert and whenever they return keys, which
+/// for `String` keys in a large network means an allocation each time.
+/// Using `Arc<K>` as the key type makes those clones reference count
+/// bumps instead, and interning keys before handing them to the store
+/// makes repeated keys share the allocation made for the first one.
+/// `Arc<K>` borrows as `K`, so maps returned by the stores can still be
+/// looked up with a plain `&K`.
+///
+/// ```
+/// use std::sync::Arc;
+/// use decentrust::intern::KeyInterner;
+/// use decentrust::precise::PreciseHonestPeer;
+/// use decentrust::honest_peer::{HonestPeer, Update};
+/// use ordered_float::OrderedFloat;
+///
+/// let mut interner: KeyInterner<String> = KeyInterner::new();
+/// let mut hp: PreciseHonestPeer<Arc<String>, OrderedFloat<f64>> = PreciseHonestPeer::new();
+///
+/// for _ in 0..3 {
+///     let key = interner.intern(&"node1".to_string());
+///     hp.update_local(&key, 1f64.into(), Update::Increment);
+/// }
+///
+/// assert_eq!(interner.len(), 1);
+/// assert_eq!(hp.get_raw_local_map()[&"node1".to_string()], OrderedFloat::from(3.0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeyInterner<K: Eq + Hash> {
+    keys: HashSet<Arc<K>>,
+}
+
+impl<K: Eq + Hash> Default for KeyInterner<K> {
+    fn default() -> Self {
+        KeyInterner { keys: HashSet::new() }
+    }
+}
+
+impl<K: Eq + Hash> KeyInterner<K> {
+    /// Creates an interner with no keys in it
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the shared copy of `key`, allocating one the first time
+    /// the key is interned
+    pub fn intern(&mut self, key: &K) -> Arc<K>
+    where
+        K: Clone
+    {
+        if let Some(shared) = self.keys.get(key) {
+            return Arc::clone(shared);
+        }
+
+        let shared = Arc::new(key.clone());
+        self.keys.insert(Arc::clone(&shared));
+        shared
+    }
+
+    /// Returns the shared copy of `key` if it was interned before
+    pub fn get(&self, key: &K) -> Option<Arc<K>> {
+        self.keys.get(key).cloned()
+    }
+
+    /// Drops every key that is no longer used outside of the interner,
+    /// e.g. after peers were removed from the stores holding them
+    pub fn purge_unused(&mut self) {
+        self.keys.retain(|shared| Arc::strong_count(shared) > 1);
+    }
+
+    /// Returns the number of distinct keys interned
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if no key was interned
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
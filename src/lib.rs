@@ -1,19 +1,88 @@
-pub mod precise;
-pub mod probabilistic;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod cms;
 pub mod cms_iter;
+pub mod util;
+pub mod error;
+pub mod trust;
+pub mod codec;
+#[cfg(feature = "std")]
+pub mod precise;
+#[cfg(feature = "std")]
+pub mod probabilistic;
+#[cfg(feature = "std")]
 pub mod honest_peer;
+#[cfg(feature = "std")]
+pub mod windowed;
+#[cfg(feature = "std")]
+pub mod weight;
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "std")]
+pub mod bloom;
+#[cfg(feature = "std")]
+pub mod intern;
+#[cfg(feature = "std")]
+pub mod rate;
+#[cfg(feature = "async")]
+pub mod shared;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+
+// Built with `cargo test --no-default-features` to check that the 
+// `CountMinSketch` core does not depend on the standard library
+#[cfg(all(test, not(feature = "std")))]
+mod no_std_tests {
+    use crate::cms::CountMinSketch;
+
+    #[test]
+    fn count_min_sketch_should_work_without_std() {
+        let mut cms = CountMinSketch::<u32>::new_with_seed(64, 4, 0, 10_000, 7);
+        cms.increment(&"node1", 5);
+        cms.increment(&"node1", 3);
+        cms.increment(&"node2", 1);
+
+        assert_eq!(cms.estimate(&"node1"), 8);
+        assert_eq!(cms.estimate(&"node2"), 1);
+
+        let mut other = CountMinSketch::<u32>::new_with_seed(64, 4, 0, 10_000, 7);
+        other.increment(&"node1", 2);
+        cms.merge(&other).unwrap();
+        assert_eq!(cms.estimate(&"node1"), 10);
+
+        let bytes = cms.to_bytes();
+        assert_eq!(CountMinSketch::<u32>::from_bytes(&bytes), Ok(cms));
+    }
+}
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use crate::cms::CountMinSketch;
     use crate::{
         probabilistic::LightHonestPeer,
         precise::PreciseHonestPeer,
-        honest_peer::{HonestPeer, Update},
+        honest_peer::{
+            HonestPeer, Update, NormalizationMode, TrustOp, TrustTier, diff_normalized_local, 
+            weighted_median_report
+        },
+        windowed::WindowedLightHonestPeer,
+        util::trust_approx_eq,
+        error::{
+            ShapeError, CmsParamError, UnderflowError, MergeError, 
+            UnknownPeerError, DecodeError, DistributionError, DecentrustError
+        },
+        trust::Trust,
     };
+    #[cfg(feature = "async")]
+    use crate::shared::SharedHonestPeer;
+    #[cfg(feature = "snapshot")]
+    use crate::snapshot::SnapshotHonestPeer;
     use ordered_float::OrderedFloat;
     use num_traits::Bounded;
+    use buckets::bucketizers::range::RangeBucketizer;
+    use std::collections::HashMap;
 
     #[test]
     fn should_create_precise_honest_peer_instance() {
@@ -41,6 +110,19 @@ mod tests {
         assert_eq!(hp.get_depth(), 10);
     }
 
+    #[test]
+    fn raw_and_normalized_sketches_should_share_hasher_light() {
+        let mut hp: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new();
+
+        assert!(hp.get_raw_local_map().shares_hasher(&hp.get_normalized_local_map()));
+        assert!(hp.get_raw_global_map().shares_hasher(&hp.get_normalized_global_map()));
+
+        hp.init_local(&"node_1", 5.0.into());
+        let trust = hp.get_normalized_local(&"node_1");
+
+        assert_eq!(trust, Some(OrderedFloat::from(1.0)));
+    }
+
     #[test]
     fn should_create_count_min_sketch_instance() {
         let error_bound = 50.0;
@@ -371,7 +453,7 @@ mod tests {
 
         hp.init_local(&"node_1", 5.0.into());
         hp.init_local(&"node_2", 5.0.into());
-        hp.init_global(&"node_2", 2.5.into());
+        hp.init_global(&"node_1", &"node_2", 2.5.into());
         hp.update_global(
             &"node_2", 
             &"node_1", 
@@ -380,8 +462,10 @@ mod tests {
         );
 
 
+        // both senders hold half of the local trust, so node_2 is 
+        // initialized at 1.25 and node_1 is incremented by 5.0
         let expected_node_1_norm = {
-            OrderedFloat::from(5.0) / OrderedFloat::from(7.5)
+            OrderedFloat::from(5.0) / OrderedFloat::from(6.25)
         };
 
         let actual_node_1_norm = hp.get_normalized_global(&"node_1").unwrap();
@@ -395,7 +479,7 @@ mod tests {
 
         hp.init_local(&"node_1", 5.0.into());
         hp.init_local(&"node_2", 5.0.into());
-        hp.init_global(&"node_2", 2.5.into());
+        hp.init_global(&"node_1", &"node_2", 2.5.into());
         hp.update_global(&"node_2", &"node_1", 10.0.into(), Update::Increment);
         hp.update_global(&"node_2", &"node_1", 5.0.into(), Update::Decrement);
 
@@ -489,7 +573,7 @@ mod tests {
 
         hp.init_local(&"node_1", 5.0.into());
         hp.init_local(&"node_2", 5.0.into());
-        hp.init_global(&"node_2", 2.5.into());
+        hp.init_global(&"node_1", &"node_2", 2.5.into());
         hp.update_global(&"node_2", &"node_1", 10.0.into(), Update::Increment);
         hp.update_global(&"node_2", &"node_1", 5.0.into(), Update::Decrement);
 
@@ -524,7 +608,7 @@ mod tests {
 
         hp.init_local(&"node_1", 5.0.into());
         hp.init_local(&"node_2", 5.0.into());
-        hp.init_global(&"node_2", 2.5.into());
+        hp.init_global(&"node_1", &"node_2", 2.5.into());
         hp.update_global(&"node_2", &"node_1", 10.0.into(), Update::Increment);
         hp.update_global(&"node_2", &"node_1", 5.0.into(), Update::Decrement);
 
@@ -576,4 +660,3307 @@ mod tests {
             v >= &OrderedFloat::from(100.0) && v <= &OrderedFloat::from(110.0)
         });
     }
+
+    #[test]
+    fn quantiles_should_match_known_distribution_precise() {
+        let mut hp: PreciseHonestPeer<usize, OrderedFloat<f64>> = PreciseHonestPeer::new();
+
+        assert_eq!(hp.local_quantile(0.5), None);
+        assert_eq!(hp.global_quantile(0.5), None);
+
+        (1..=10).for_each(|i| {
+            hp.init_local(&i, OrderedFloat::from(i as f64));
+        });
+
+        let total = OrderedFloat::from(55.0);
+
+        assert_eq!(hp.local_quantile(0.5), Some(OrderedFloat::from(5.0) / total));
+        assert_eq!(hp.local_quantile(0.9), Some(OrderedFloat::from(9.0) / total));
+        assert_eq!(hp.local_quantile(1.5), None);
+    }
+
+    #[test]
+    fn quantiles_should_match_known_distribution_light() {
+        let mut hp: LightHonestPeer<usize, OrderedFloat<f64>> = {
+            LightHonestPeer::new_from_bounds(
+                1.0, 
+                0.0001, 
+                3000.0, 
+                OrderedFloat::from(0.0), 
+                OrderedFloat::from(f64::max_value()),
+            )
+        };
+
+        assert_eq!(hp.local_quantile(0.5), None);
+        assert_eq!(hp.global_quantile(0.5), None);
+
+        (1..=10).for_each(|i| {
+            hp.init_local(&i, OrderedFloat::from(i as f64));
+        });
+
+        let total = OrderedFloat::from(55.0);
+
+        assert_eq!(hp.local_quantile(0.5), Some(OrderedFloat::from(5.0) / total));
+        assert_eq!(hp.local_quantile(0.9), Some(OrderedFloat::from(9.0) / total));
+
+        // every report comes from one sender, so its weight cancels out
+        (1..=10).for_each(|i| {
+            hp.init_global(&1, &(i + 10), OrderedFloat::from(i as f64));
+        });
+
+        let epsilon = OrderedFloat::from(1e-9);
+        let median = hp.global_quantile(0.5).unwrap();
+        let p90 = hp.global_quantile(0.9).unwrap();
+        assert!(trust_approx_eq(median, OrderedFloat::from(5.0) / total, epsilon));
+        assert!(trust_approx_eq(p90, OrderedFloat::from(9.0) / total, epsilon));
+        assert_eq!(hp.global_quantile(1.5), None);
+    }
+
+    #[test]
+    fn behaviour_outside_the_window_should_not_contribute_windowed() {
+        let windows = 3;
+        let mut hp: WindowedLightHonestPeer<&str, OrderedFloat<f64>> = {
+            WindowedLightHonestPeer::new(windows)
+        };
+
+        hp.update_local(&"node_1", 50.0.into(), Update::Increment);
+        hp.update_local(&"node_2", 10.0.into(), Update::Increment);
+
+        (1..windows).for_each(|_| {
+            hp.advance_window();
+            hp.update_local(&"node_2", 10.0.into(), Update::Increment);
+        });
+
+        assert_eq!(hp.get_raw_local(&"node_1"), Some(OrderedFloat::from(50.0)));
+        assert_eq!(hp.get_raw_local(&"node_2"), Some(OrderedFloat::from(30.0)));
+
+        hp.advance_window();
+
+        assert_eq!(hp.get_raw_local(&"node_1"), Some(OrderedFloat::from(0.0)));
+        assert_eq!(hp.get_raw_local(&"node_2"), Some(OrderedFloat::from(20.0)));
+        assert_eq!(hp.get_normalized_local(&"node_2"), Some(OrderedFloat::from(1.0)));
+    }
+
+    #[test]
+    fn occupancy_report_should_reflect_dimensions_and_occupancy() {
+        let mut cms = CountMinSketch::<OrderedFloat<f64>>::new(
+            50, 
+            4, 
+            OrderedFloat::from(0.0), 
+            OrderedFloat::from(f64::max_value())
+        );
+
+        let report = cms.occupancy_report();
+
+        assert!(report.contains("width: 50"));
+        assert!(report.contains("depth: 4"));
+        assert!(report.contains("row 0: 0.0000 occupied"));
+
+        cms.increment(&"node_1", 5.0.into());
+        cms.increment(&"node_2", 5.0.into());
+        cms.increment(&"node_3", 5.0.into());
+
+        let report = cms.occupancy_report();
+
+        assert!(report.contains("width: 50"));
+        assert!(report.contains("depth: 4"));
+        assert!(!report.contains("row 0: 0.0000 occupied"));
+
+        let mut saturated = CountMinSketch::<u64>::new(50, 4, 0, u64::MAX);
+        saturated.increment(&"node_1", u64::MAX);
+
+        let report = saturated.occupancy_report();
+
+        assert!(report.contains(&format!("total mass: {}", u64::MAX)));
+    }
+
+    #[test]
+    fn rehashed_sketch_should_preserve_estimates_for_known_keys() {
+        let mut cms = CountMinSketch::<OrderedFloat<f64>>::new(
+            10, 
+            3, 
+            OrderedFloat::from(0.0), 
+            OrderedFloat::from(f64::max_value())
+        );
+
+        let keys: Vec<usize> = (0..20).collect();
+        keys.iter().for_each(|k| {
+            cms.increment(k, OrderedFloat::from(1.0));
+        });
+
+        let new_width = 100_000;
+        let resized = cms.rehash_from_keys(
+            new_width, 
+            3, 
+            keys.iter().map(|k| (k, cms.estimate(k)))
+        );
+
+        assert_eq!(resized.get_width(), new_width);
+        assert_eq!(resized.get_depth(), 3);
+
+        let total_mass = keys.iter().fold(0.0, |acc, k| acc + cms.estimate(k).into_inner());
+        let error_bound = std::f64::consts::E / new_width as f64 * total_mass;
+
+        keys.iter().for_each(|k| {
+            let original = cms.estimate(k);
+            let estimate = resized.estimate(k);
+            assert!(estimate >= original);
+            assert!(estimate <= original + OrderedFloat::from(error_bound));
+        });
+    }
+
+    #[test]
+    fn trust_approx_eq_should_respect_epsilon() {
+        let epsilon = OrderedFloat::from(0.01);
+        let a = OrderedFloat::from(0.5);
+
+        assert!(trust_approx_eq(a, OrderedFloat::from(0.509), epsilon));
+        assert!(trust_approx_eq(a, OrderedFloat::from(0.491), epsilon));
+        assert!(!trust_approx_eq(a, OrderedFloat::from(0.511), epsilon));
+        assert!(!trust_approx_eq(a, OrderedFloat::from(0.489), epsilon));
+        assert!(trust_approx_eq(10u64, 12u64, 3u64));
+        assert!(!trust_approx_eq(12u64, 10u64, 2u64));
+    }
+
+    #[test]
+    fn normalized_approx_should_compare_within_epsilon_precise() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let epsilon = OrderedFloat::from(0.001);
+
+        hp.init_local(&"node_1", 1.0.into());
+        hp.init_local(&"node_2", 2.0.into());
+
+        let expected = OrderedFloat::from(1.0 / 3.0);
+
+        assert_eq!(hp.get_normalized_local_approx(&"node_1", expected + OrderedFloat::from(0.0009), epsilon), Some(true));
+        assert_eq!(hp.get_normalized_local_approx(&"node_1", expected + OrderedFloat::from(0.0011), epsilon), Some(false));
+        assert_eq!(hp.get_normalized_local_approx(&"node_3", expected, epsilon), None);
+    }
+
+    #[test]
+    fn should_find_most_and_least_trusted_peers_precise() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+
+        assert_eq!(hp.most_trusted_local(), None);
+        assert_eq!(hp.least_trusted_global(), None);
+
+        hp.init_local(&"node_1", 4.0.into());
+        hp.init_local(&"node_2", 1.0.into());
+        hp.init_local(&"node_3", 3.0.into());
+        hp.init_local(&"node_4", 2.0.into());
+
+        hp.update_global(&"node_1", &"node_2", 30.0.into(), Update::Increment);
+        hp.update_global(&"node_1", &"node_3", 10.0.into(), Update::Increment);
+        hp.update_global(&"node_1", &"node_4", 20.0.into(), Update::Increment);
+        hp.update_global(&"node_1", &"node_1", 5.0.into(), Update::Increment);
+
+        let total = OrderedFloat::from(10.0);
+
+        assert_eq!(hp.most_trusted_local(), Some(("node_1", OrderedFloat::from(4.0) / total)));
+        assert_eq!(hp.least_trusted_local(), Some(("node_2", OrderedFloat::from(1.0) / total)));
+        assert_eq!(hp.most_trusted_global().map(|(k, _)| k), Some("node_2"));
+        assert_eq!(hp.least_trusted_global().map(|(k, _)| k), Some("node_1"));
+    }
+
+    #[test]
+    fn should_expose_value_bounds_through_trait() {
+        fn bounds<H: HonestPeer>(hp: &H) -> (H::Value, H::Value) {
+            hp.value_bounds()
+        }
+
+        let precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let light: LightHonestPeer<&str, OrderedFloat<f64>> = {
+            LightHonestPeer::new_from_bounds(
+                10.0, 
+                0.0001, 
+                3000.0, 
+                OrderedFloat::from(0.0), 
+                OrderedFloat::from(100.0),
+            )
+        };
+
+        assert_eq!(
+            bounds(&precise), 
+            (OrderedFloat::<f64>::min_value(), OrderedFloat::<f64>::max_value())
+        );
+        assert_eq!(bounds(&light), (OrderedFloat::from(0.0), OrderedFloat::from(100.0)));
+    }
+
+    #[test]
+    fn bucket_indices_should_be_within_bucket_count() {
+        let ranges: Vec<(OrderedFloat<f64>, OrderedFloat<f64>)> = vec![
+            (OrderedFloat::from(0.0), OrderedFloat::from(5.0)),
+            (OrderedFloat::from(5.0), OrderedFloat::from(15.0)), 
+            (OrderedFloat::from(15.0), OrderedFloat::<f64>::max_value())
+        ];
+
+        let mut precise: PreciseHonestPeer<usize, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<usize, OrderedFloat<f64>> = LightHonestPeer::new();
+
+        assert_eq!(precise.bucket_count(), 0);
+        assert_eq!(light.max_bucket_seen(), None);
+
+        (0..20).for_each(|i| {
+            precise.init_local(&i, OrderedFloat::from(i as f64));
+            light.init_local(&i, OrderedFloat::from(i as f64));
+        });
+
+        let precise_buckets: Vec<(usize, usize)> = {
+            precise.bucketize_local(RangeBucketizer::new(ranges.clone())).collect()
+        };
+        let light_buckets: Vec<(usize, usize)> = {
+            light.bucketize_local(0..20, RangeBucketizer::new(ranges.clone())).collect()
+        };
+
+        assert_eq!(precise.bucket_count(), 3);
+        assert!(light.bucket_count() >= 1);
+        assert!(precise_buckets.iter().all(|(_, b)| *b < precise.bucket_count()));
+        assert!(light_buckets.iter().all(|(_, b)| *b < light.bucket_count()));
+    }
+
+    #[test]
+    fn histograms_should_count_peers_per_bucket() {
+        let ranges: Vec<(OrderedFloat<f64>, OrderedFloat<f64>)> = vec![
+            (OrderedFloat::from(0.0), OrderedFloat::from(5.0)),
+            (OrderedFloat::from(5.0), OrderedFloat::from(15.0)), 
+            (OrderedFloat::from(15.0), OrderedFloat::<f64>::max_value())
+        ];
+        let scores = [1.0, 2.0, 3.0, 7.0, 9.0, 20.0];
+
+        let mut precise: PreciseHonestPeer<usize, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<usize, OrderedFloat<f64>> = {
+            LightHonestPeer::new_from_bounds(
+                0.1, 
+                0.01, 
+                3000.0, 
+                OrderedFloat::<f64>::min_value(), 
+                OrderedFloat::<f64>::max_value()
+            )
+        };
+
+        scores.iter().enumerate().for_each(|(i, score)| {
+            precise.init_local(&i, OrderedFloat::from(*score));
+            light.init_local(&i, OrderedFloat::from(*score));
+        });
+
+        let expected: HashMap<usize, usize> = vec![(0, 3), (1, 2), (2, 1)].into_iter().collect();
+
+        assert_eq!(precise.local_histogram(RangeBucketizer::new(ranges.clone())), expected);
+        assert_eq!(
+            light.local_histogram(0..scores.len(), RangeBucketizer::new(ranges.clone())), 
+            expected
+        );
+    }
+
+    #[test]
+    fn cloned_sketch_should_equal_original_until_mutated() {
+        let mut cms = CountMinSketch::<OrderedFloat<f64>>::new(
+            100, 4, OrderedFloat::from(0.0), OrderedFloat::from(1000.0)
+        );
+        cms.increment(&"node1", OrderedFloat::from(3.0));
+
+        let mut cloned = cms.clone();
+        assert_eq!(cloned, cms);
+
+        cloned.increment(&"node2", OrderedFloat::from(1.0));
+        assert_ne!(cloned, cms);
+
+        let resized = cms.rehash_into(200, 4);
+        assert_ne!(resized, cms);
+    }
+
+    #[test]
+    fn sketch_should_load_from_rectangular_matrix_only() {
+        let mut cms = CountMinSketch::<u64>::new(50, 4, 0, 1000);
+        cms.increment(&"node1", 7);
+        cms.increment(&"node2", 3);
+
+        let loaded = CountMinSketch::from_matrix(
+            cms.matrix().clone(), 
+            cms.get_min(), 
+            cms.get_max(), 
+            cms.seed()
+        ).unwrap();
+
+        assert_eq!(loaded, cms);
+        assert_eq!(loaded.get_width(), 50);
+        assert_eq!(loaded.get_depth(), 4);
+        assert_eq!(loaded.estimate(&"node1"), cms.estimate(&"node1"));
+
+        let jagged = CountMinSketch::<u64>::from_matrix(
+            vec![vec![0; 4], vec![0; 4], vec![0; 5]], 0, 1000, 0
+        );
+        assert_eq!(jagged, Err(ShapeError::Jagged { row: 2, expected: 4, found: 5 }));
+
+        let empty = CountMinSketch::<u64>::from_matrix(vec![], 0, 1000, 0);
+        assert_eq!(empty, Err(ShapeError::Empty));
+    }
+
+    #[test]
+    fn extreme_bounds_should_never_yield_zero_dimensions() {
+        let params = vec![
+            (f64::MAX, 0.01, 1e-300),
+            (1e12, 0.999, 1.0),
+            (1.0, 0.999999, 1e-3),
+        ];
+
+        params.into_iter().for_each(|(error_bound, probability, max_entries)| {
+            let mut cms = CountMinSketch::<u64>::new_from_bounds(
+                error_bound, probability, max_entries, 0, 1000
+            );
+
+            assert!(cms.get_width() >= 1);
+            assert!(cms.get_depth() >= 1);
+
+            cms.increment(&"node1", 5);
+            assert_eq!(cms.estimate(&"node1"), 5);
+        });
+
+        let cms = CountMinSketch::<u64>::new(0, 0, 0, 1000);
+        assert_eq!((cms.get_width(), cms.get_depth()), (1, 1));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_updates_should_be_consistent_shared() {
+        let hp: SharedHonestPeer<PreciseHonestPeer<usize, OrderedFloat<f64>>> = {
+            SharedHonestPeer::new(PreciseHonestPeer::new())
+        };
+
+        for k in 0..4usize {
+            hp.init_local(&k, OrderedFloat::from(1.0)).await;
+        }
+
+        let tasks: Vec<_> = (0..100usize).map(|i| {
+            let handle = hp.clone();
+            tokio::spawn(async move {
+                handle.update_local(&(i % 4), OrderedFloat::from(1.0), Update::Increment).await;
+            })
+        }).collect();
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        for k in 0..4usize {
+            assert_eq!(hp.get_raw_local(&k).await, Some(OrderedFloat::from(26.0)));
+            assert_eq!(hp.get_normalized_local(&k).await, Some(OrderedFloat::from(0.25)));
+        }
+        assert_eq!(hp.read().await.local_raw_len(), 4);
+    }
+
+    #[test]
+    fn bulk_normalized_lookup_should_match_individual_lookups() {
+        let mut precise: PreciseHonestPeer<String, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<String, OrderedFloat<f64>> = LightHonestPeer::new();
+
+        vec![("node1", 2.0), ("node2", 6.0)].into_iter().for_each(|(k, v)| {
+            precise.init_local(&k.to_string(), OrderedFloat::from(v));
+            light.init_local(&k.to_string(), OrderedFloat::from(v));
+            precise.init_global(&"node1".to_string(), &k.to_string(), OrderedFloat::from(v));
+            light.init_global(&"node1".to_string(), &k.to_string(), OrderedFloat::from(v));
+        });
+
+        let keys: Vec<String> = vec!["node2", "unknown", "node1"].into_iter()
+            .map(|k| k.to_string())
+            .collect();
+
+        let individual_local: Vec<Option<OrderedFloat<f64>>> = {
+            keys.iter().map(|k| precise.get_normalized_local(k)).collect()
+        };
+        let individual_global: Vec<Option<OrderedFloat<f64>>> = {
+            keys.iter().map(|k| precise.get_normalized_global(k)).collect()
+        };
+
+        assert_eq!(precise.get_normalized_local_many(&keys), individual_local);
+        assert_eq!(precise.get_normalized_global_many(&keys), individual_global);
+        assert_eq!(individual_local[1], None);
+
+        let light_local: Vec<Option<OrderedFloat<f64>>> = {
+            keys.iter().map(|k| light.get_normalized_local(k)).collect()
+        };
+        assert_eq!(light.get_normalized_local_many(&keys), light_local);
+    }
+
+    #[test]
+    fn local_trust_matrix_rows_should_be_normalized_per_owner_precise() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let peers = ["node1", "node2", "node3"];
+        let rows = [
+            [0.0, 1.0, 3.0],
+            [10.0, 0.0, 10.0],
+            [2.0, 6.0, 0.0],
+        ];
+
+        peers.iter().zip(rows.iter()).for_each(|(owner, row)| {
+            let vector: HashMap<&str, OrderedFloat<f64>> = peers.iter()
+                .cloned()
+                .zip(row.iter().map(|v| OrderedFloat::from(*v)))
+                .collect();
+            hp.set_local_vector(*owner, vector);
+        });
+
+        let matrix = hp.local_trust_matrix();
+        let expected = [
+            [0.0, 0.25, 0.75],
+            [0.5, 0.0, 0.5],
+            [0.25, 0.75, 0.0],
+        ];
+
+        assert_eq!(matrix.len(), 3);
+        peers.iter().zip(expected.iter()).for_each(|(owner, row)| {
+            peers.iter().zip(row.iter()).for_each(|(peer, v)| {
+                assert_eq!(matrix[owner][peer], OrderedFloat::from(*v));
+            });
+            let row_total = matrix[owner].values().fold(0.0, |acc, v| acc + v.0);
+            assert!(trust_approx_eq(row_total, 1.0, 1e-9));
+        });
+
+        assert_eq!(hp.raw_local_trust_matrix()["node2"]["node1"], OrderedFloat::from(10.0));
+    }
+
+    #[test]
+    fn invalid_bounds_should_be_rejected() {
+        let cases = vec![
+            ((1.0, 0.0, 100.0), CmsParamError::InvalidProbability(0.0)),
+            ((1.0, 1.0, 100.0), CmsParamError::InvalidProbability(1.0)),
+            ((1.0, 1.5, 100.0), CmsParamError::InvalidProbability(1.5)),
+            ((0.0, 0.01, 100.0), CmsParamError::InvalidErrorBound(0.0)),
+            ((-1.0, 0.01, 100.0), CmsParamError::InvalidErrorBound(-1.0)),
+            ((f64::INFINITY, 0.01, 100.0), CmsParamError::InvalidErrorBound(f64::INFINITY)),
+            ((1.0, 0.01, 0.0), CmsParamError::InvalidMaxEntries(0.0)),
+            ((1.0, 0.01, -5.0), CmsParamError::InvalidMaxEntries(-5.0)),
+        ];
+
+        cases.into_iter().for_each(|((error_bound, probability, max_entries), expected)| {
+            let cms = CountMinSketch::<u64>::try_new_from_bounds(
+                error_bound, probability, max_entries, 0, 1000
+            );
+            assert_eq!(cms, Err(expected));
+        });
+
+        let nan = CountMinSketch::<u64>::try_new_from_bounds(1.0, f64::NAN, 100.0, 0, 1000);
+        assert!(matches!(nan, Err(CmsParamError::InvalidProbability(_))));
+
+        let valid = CountMinSketch::<u64>::try_new_from_bounds(1.0, 0.01, 100.0, 0, 1000);
+        assert!(valid.is_ok());
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_from_bounds_should_panic_on_invalid_probability() {
+        CountMinSketch::<u64>::new_from_bounds(1.0, 1.0, 100.0, 0, 1000);
+    }
+
+    #[test]
+    #[should_panic(expected = "false positive rate must be between 0 and 1 exclusive")]
+    fn bloom_filter_should_reject_a_zero_false_positive_rate() {
+        crate::bloom::BloomFilter::with_rate(1000, 0.0);
+    }
+
+    #[test]
+    fn should_increment_node_reputation_with_trust_values() {
+        let mut precise: PreciseHonestPeer<&str, Trust> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<&str, Trust> = {
+            LightHonestPeer::new_from_bounds(
+                10.0, 
+                0.0001, 
+                3000.0, 
+                Trust::from(0.0), 
+                Trust::max_value(),
+            )
+        };
+
+        precise.update_local(&"node_1", 5.0.into(), Update::Increment);
+        light.update_local(&"node_1", 5.0.into(), Update::Increment);
+
+        assert_eq!(precise.get_raw_local(&"node_1"), Some(Trust::from(5.0)));
+        assert_eq!(light.get_raw_local(&"node_1"), Some(Trust::from(5.0)));
+
+        precise.update_local(&"node_1", 2.0.into(), Update::Decrement);
+        light.update_local(&"node_1", 2.0.into(), Update::Decrement);
+
+        assert_eq!(precise.get_raw_local(&"node_1"), Some(Trust::from(3.0)));
+        assert_eq!(light.get_raw_local(&"node_1"), Some(Trust::from(3.0)));
+    }
+
+    #[test]
+    fn increments_should_be_normalized_with_trust_values() {
+        let mut precise: PreciseHonestPeer<&str, Trust> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<&str, Trust> = {
+            LightHonestPeer::new_from_bounds(
+                10.0, 
+                0.0001, 
+                3000.0, 
+                Trust::from(0.0), 
+                Trust::max_value(),
+            )
+        };
+
+        precise.init_local(&"node_1", 5.0.into());
+        precise.init_local(&"node_2", 5.0.into());
+        precise.update_local(&"node_1", 10.0.into(), Update::Increment);
+        light.init_local(&"node_1", 5.0.into());
+        light.init_local(&"node_2", 5.0.into());
+        light.update_local(&"node_1", 10.0.into(), Update::Increment);
+
+        let expected_node_1_norm = Trust::from(15.0) / Trust::from(20.0);
+
+        assert_eq!(precise.get_normalized_local(&"node_1"), Some(expected_node_1_norm));
+        assert_eq!(light.get_normalized_local(&"node_1"), Some(expected_node_1_norm));
+    }
+
+    #[test]
+    fn trust_values_should_order_and_hash_consistently() {
+        use std::collections::HashSet;
+
+        let nan = Trust::from(f64::NAN);
+        let other_nan = Trust::from(0.0) / Trust::from(0.0);
+
+        assert_eq!(nan, other_nan);
+        assert!(nan > Trust::from(f64::INFINITY));
+        assert_eq!(Trust::from(-0.0), Trust::from(0.0));
+        assert!(Trust::from(-1.0) < Trust::from(0.0));
+
+        let set: HashSet<Trust> = vec![
+            nan, other_nan, Trust::from(0.0), Trust::from(-0.0)
+        ].into_iter().collect();
+
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn multi_sender_update_should_match_separate_updates() {
+        let senders = [("node_1", 5.0), ("node_2", 3.0), ("node_3", 2.0)];
+        let reports: Vec<(&str, OrderedFloat<f64>)> = vec![
+            ("node_1", OrderedFloat::from(4.0)),
+            ("node_2", OrderedFloat::from(10.0)),
+            ("node_3", OrderedFloat::from(1.0)),
+            ("unknown", OrderedFloat::from(100.0)),
+        ];
+
+        let mut separate: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut multi: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light_separate: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new();
+        let mut light_multi: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new();
+
+        senders.iter().for_each(|(k, v)| {
+            separate.init_local(k, OrderedFloat::from(*v));
+            multi.init_local(k, OrderedFloat::from(*v));
+            light_separate.init_local(k, OrderedFloat::from(*v));
+            light_multi.init_local(k, OrderedFloat::from(*v));
+        });
+
+        reports.iter().for_each(|(sender, delta)| {
+            separate.update_global(sender, &"node_4", *delta, Update::Increment);
+            light_separate.update_global(sender, &"node_4", *delta, Update::Increment);
+        });
+        multi.update_global_multi(&reports, &"node_4", Update::Increment);
+        light_multi.update_global_multi(&reports, &"node_4", Update::Increment);
+
+        let expected = separate.get_raw_global(&"node_4").unwrap();
+
+        assert!(trust_approx_eq(multi.get_raw_global(&"node_4").unwrap(), expected, OrderedFloat::from(1e-9)));
+        assert_eq!(multi.get_normalized_global(&"node_4"), separate.get_normalized_global(&"node_4"));
+        assert!(trust_approx_eq(
+            light_multi.get_raw_global(&"node_4").unwrap(), 
+            light_separate.get_raw_global(&"node_4").unwrap(), 
+            OrderedFloat::from(1e-9)
+        ));
+    }
+
+    #[test]
+    fn ties_should_be_broken_by_key_order() {
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<&str, OrderedFloat<f64>> = {
+            LightHonestPeer::new_from_bounds(
+                0.1, 
+                0.01, 
+                3000.0, 
+                OrderedFloat::<f64>::min_value(), 
+                OrderedFloat::<f64>::max_value()
+            )
+        };
+
+        vec![("node_d", 1.0), ("node_b", 3.0), ("node_c", 3.0), ("node_a", 3.0), ("node_e", 1.0)]
+            .into_iter()
+            .for_each(|(k, v)| {
+                precise.init_local(&k, OrderedFloat::from(v));
+                light.init_local(&k, OrderedFloat::from(v));
+            });
+
+        let top: Vec<&str> = precise.top_k_local(4).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(top, vec!["node_a", "node_b", "node_c", "node_d"]);
+
+        let light_top: Vec<&str> = light.top_k_local(10).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(light_top, vec!["node_a", "node_b", "node_c", "node_d", "node_e"]);
+
+        assert_eq!(precise.most_trusted_local().map(|(k, _)| k), Some("node_a"));
+        assert_eq!(precise.least_trusted_local().map(|(k, _)| k), Some("node_d"));
+        assert_eq!(light.most_trusted_local().map(|(k, _)| k), Some("node_a"));
+        assert_eq!(light.least_trusted_local().map(|(k, _)| k), Some("node_d"));
+        assert!(precise.top_k_global(3).is_empty());
+    }
+
+    #[test]
+    fn streamed_estimates_should_match_individual_estimates() {
+        let mut cms = CountMinSketch::<OrderedFloat<f64>>::new(
+            50, 4, OrderedFloat::from(0.0), OrderedFloat::from(1000.0)
+        );
+        let keys: Vec<String> = (0..30).map(|i| format!("node_{}", i)).collect();
+
+        keys.iter().enumerate().filter(|(i, _)| i % 3 != 0).for_each(|(i, k)| {
+            cms.increment(k, OrderedFloat::from(i as f64));
+        });
+
+        let streamed: Vec<OrderedFloat<f64>> = cms.estimate_all(keys.iter()).collect();
+        let individual: Vec<OrderedFloat<f64>> = keys.iter().map(|k| cms.estimate(k)).collect();
+
+        assert_eq!(streamed, individual);
+        assert_eq!(cms.estimate_all(keys.iter().take(0)).count(), 0);
+    }
+
+    #[test]
+    fn observation_counts_should_ignore_delta_size_light() {
+        let mut hp: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new();
+
+        hp.init_local(&"node_1", 1.0.into());
+        hp.update_local(&"node_1", 1000.0.into(), Update::Increment);
+        (0..10).for_each(|_| {
+            hp.update_local(&"node_2", 0.1.into(), Update::Increment);
+        });
+        hp.update_local(&"node_2", 0.5.into(), Update::Decrement);
+
+        assert_eq!(hp.observation_count_local(&"node_1"), 1);
+        assert_eq!(hp.observation_count_local(&"node_2"), 11);
+        assert_eq!(hp.observation_count_local(&"node_3"), 0);
+
+        hp.update_global(&"node_1", &"node_2", 50.0.into(), Update::Increment);
+        hp.update_global_multi(
+            &[("node_1", 1.0.into()), ("node_2", 2.0.into())], 
+            &"node_2", 
+            Update::Increment
+        );
+
+        assert_eq!(hp.observation_count_global(&"node_2"), 3);
+        assert_eq!(hp.observation_count_global(&"node_1"), 0);
+    }
+
+    #[test]
+    fn integer_normalization_should_be_meaningful_when_scaled() {
+        let matrix = vec![vec![1u64, 3, 0, 4]];
+        let sketch = CountMinSketch::from_matrix(matrix, 0, u64::MAX, 0).unwrap();
+
+        assert_eq!(sketch.normalize_estimates(), vec![vec![0, 0, 0, 0]]);
+        assert_eq!(
+            sketch.normalize_estimates_scaled(1000), 
+            vec![vec![125, 375, 0, 500]]
+        );
+    }
+
+    #[test]
+    fn reset_should_set_raw_trust_to_exact_value() {
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<&str, OrderedFloat<f64>> = {
+            LightHonestPeer::new_from_bounds(
+                0.1, 
+                0.01, 
+                3000.0, 
+                OrderedFloat::<f64>::min_value(), 
+                OrderedFloat::<f64>::max_value()
+            )
+        };
+
+        precise.init_local(&"node_1", 5.0.into());
+        precise.init_local(&"node_2", 5.0.into());
+        precise.update_local(&"node_1", 37.5.into(), Update::Increment);
+        light.init_local(&"node_1", 5.0.into());
+        light.init_local(&"node_2", 5.0.into());
+        light.update_local(&"node_1", 37.5.into(), Update::Increment);
+
+        precise.reset_local(&"node_1", 15.0.into());
+        light.reset_local(&"node_1", 15.0.into());
+
+        assert_eq!(precise.get_raw_local(&"node_1"), Some(OrderedFloat::from(15.0)));
+        assert_eq!(light.get_raw_local(&"node_1"), Some(OrderedFloat::from(15.0)));
+        assert_eq!(precise.get_normalized_local(&"node_1"), Some(OrderedFloat::from(0.75)));
+        assert_eq!(light.get_normalized_local(&"node_1"), Some(OrderedFloat::from(0.75)));
+
+        precise.init_global(&"node_1", &"node_3", 8.0.into());
+        light.init_global(&"node_1", &"node_3", 8.0.into());
+        precise.reset_global(&"node_3", 2.0.into());
+        light.reset_global(&"node_3", 2.0.into());
+
+        assert_eq!(precise.get_raw_global(&"node_3"), Some(OrderedFloat::from(2.0)));
+        assert_eq!(light.get_raw_global(&"node_3"), Some(OrderedFloat::from(2.0)));
+    }
+
+    #[test]
+    fn precise_and_light_should_share_a_dyn_collection() {
+        use crate::honest_peer::DynHonestPeer;
+
+        let mut backends: Vec<Box<dyn DynHonestPeer<String, Trust>>> = vec![
+            Box::new(PreciseHonestPeer::new()),
+            Box::new(LightHonestPeer::new()),
+        ];
+
+        backends.iter_mut().for_each(|hp| {
+            hp.init_local(&"node_1".to_string(), 3.0.into());
+            hp.init_local(&"node_2".to_string(), 1.0.into());
+            hp.update_local(&"node_2".to_string(), 1.0.into(), Update::Decrement);
+            hp.init_global(&"node_1".to_string(), &"node_2".to_string(), 4.0.into());
+        });
+
+        backends.iter().for_each(|hp| {
+            assert_eq!(hp.get_raw_local(&"node_2".to_string()), Some(Trust::from(0.0)));
+            assert_eq!(hp.get_normalized_local(&"node_1".to_string()), Some(Trust::from(1.0)));
+            assert_eq!(hp.get_raw_global(&"node_2".to_string()), Some(Trust::from(4.0)));
+
+            let mut raw_map = hp.get_raw_local_map();
+            raw_map.sort();
+            assert_eq!(raw_map, vec![
+                ("node_1".to_string(), Trust::from(3.0)), 
+                ("node_2".to_string(), Trust::from(0.0))
+            ]);
+        });
+    }
+
+    #[test]
+    fn confidence_should_drop_as_sketch_fills_light() {
+        // a fixed seed, as an unlucky one can leave the targets' cells 
+        // no more spread out after filling than before
+        let mut hp = LightHonestPeer::<String, OrderedFloat<f64>>::builder()
+            .error_bound(100.0)
+            .probability(0.02)
+            .max_entries(3000.0)
+            .seed(7)
+            .build()
+            .unwrap();
+        let targets: Vec<String> = (0..50).map(|i| format!("target_{}", i)).collect();
+        targets.iter().for_each(|k| hp.init_local(k, OrderedFloat::from(1.0)));
+
+        let mean_confidence = |hp: &LightHonestPeer<String, OrderedFloat<f64>>| {
+            targets.iter().map(|k| hp.local_confidence(k)).sum::<f64>() / targets.len() as f64
+        };
+
+        let sparse = mean_confidence(&hp);
+        (0..100).for_each(|i| hp.init_local(&format!("filler_{}", i), OrderedFloat::from(1.0)));
+        let filled = mean_confidence(&hp);
+
+        assert!(hp.get_width() < 150);
+        assert!(sparse > filled, "sparse: {}, filled: {}", sparse, filled);
+        assert!(filled < 0.9);
+    }
+
+    #[test]
+    fn precise_and_light_decrements_should_floor_identically() {
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<&str, OrderedFloat<f64>> = {
+            LightHonestPeer::new_from_bounds(
+                0.1, 
+                0.01, 
+                3000.0, 
+                OrderedFloat::<f64>::min_value(), 
+                OrderedFloat::<f64>::max_value()
+            )
+        };
+
+        let sequence = vec![
+            ("node_1", 5.0, Update::Increment),
+            ("node_1", 8.0, Update::Decrement),
+            ("node_1", 2.0, Update::Increment),
+            ("node_2", 3.0, Update::Decrement),
+            ("node_2", 4.0, Update::Increment),
+            ("node_2", 1.0, Update::Decrement),
+        ];
+
+        sequence.into_iter().for_each(|(k, v, update)| {
+            precise.update_local(&k, OrderedFloat::from(v), update.clone());
+            light.update_local(&k, OrderedFloat::from(v), update);
+
+            assert_eq!(precise.get_raw_local(&k), light.get_raw_local(&k));
+        });
+
+        assert_eq!(precise.get_raw_local(&"node_1"), Some(OrderedFloat::from(2.0)));
+        assert_eq!(precise.get_raw_local(&"node_2"), Some(OrderedFloat::from(3.0)));
+
+        precise.update_global(&"node_1", &"node_3", 1.0.into(), Update::Decrement);
+        light.update_global(&"node_1", &"node_3", 1.0.into(), Update::Decrement);
+
+        assert_eq!(precise.get_raw_global(&"node_3"), Some(OrderedFloat::from(0.0)));
+        assert_eq!(precise.get_raw_global(&"node_3"), light.get_raw_global(&"node_3"));
+    }
+
+    #[test]
+    fn independent_rows_should_reduce_overestimation() {
+        let width = 200;
+        let keys: Vec<String> = (0..1000).map(|i| format!("node_{}", i)).collect();
+
+        let mean_overestimate = |depth: usize| {
+            let mut cms = CountMinSketch::<u64>::new(width, depth, 0, u64::MAX);
+            keys.iter().for_each(|k| cms.increment(k, 1));
+            keys.iter().map(|k| cms.estimate(k) - 1).sum::<u64>() as f64 / keys.len() as f64
+        };
+
+        let single_row = mean_overestimate(1);
+        let five_rows = mean_overestimate(5);
+
+        // a single row overestimates by about keys / width on average, 
+        // taking the minimum over independent rows should roughly halve that
+        assert!(single_row > 3.0, "single row: {}", single_row);
+        assert!(five_rows < 0.75 * single_row, "single: {}, five: {}", single_row, five_rows);
+    }
+
+    #[test]
+    fn keys_colliding_in_one_row_should_rarely_collide_in_every_row() {
+        let mut cms = CountMinSketch::<u64>::new_with_seed(50, 4, 0, u64::MAX, 7);
+        cms.increment(&"heavy", 1000);
+
+        // with rows hashed alike, about one key in 50 would share every 
+        // cell of the heavy key, rather than one in 50^4
+        let shadowed = (0..2000)
+            .filter(|i| cms.estimate(&format!("node_{}", i)) >= 1000)
+            .count();
+        assert_eq!(shadowed, 0);
+    }
+
+    #[test]
+    fn batch_init_should_match_individual_inits() {
+        let peers: Vec<(usize, OrderedFloat<f64>)> = (0..1000)
+            .map(|i| (i, OrderedFloat::from((i % 17 + 1) as f64)))
+            .collect();
+
+        let mut batched: PreciseHonestPeer<usize, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut individual: PreciseHonestPeer<usize, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<usize, OrderedFloat<f64>> = LightHonestPeer::new();
+
+        batched.init_local_batch(peers.clone());
+        light.init_local_batch(peers.clone());
+        peers.iter().for_each(|(k, v)| individual.init_local(k, *v));
+
+        let epsilon = OrderedFloat::from(1e-12);
+
+        assert_eq!(batched.local_raw_len(), 1000);
+        peers.iter().for_each(|(k, _)| {
+            assert!(trust_approx_eq(
+                batched.get_normalized_local(k).unwrap(), 
+                individual.get_normalized_local(k).unwrap(), 
+                epsilon
+            ));
+        });
+
+        let total = peers.iter().fold(OrderedFloat::from(0.0), |acc, (_, v)| acc + *v);
+        assert_eq!(light.local_keys().len(), 1000);
+        peers.iter().for_each(|(k, _)| {
+            let expected = light.get_raw_local(k).unwrap() / total;
+            let normalized = light.get_normalized_local(k).unwrap();
+            assert!(trust_approx_eq(normalized, expected, epsilon));
+        });
+
+        batched.init_global_batch(&0, peers.clone());
+        peers.iter().for_each(|(k, v)| individual.init_global(&0, k, *v));
+
+        assert_eq!(batched.global_raw_len(), 1000);
+        peers.iter().for_each(|(k, _)| {
+            assert!(trust_approx_eq(
+                batched.get_normalized_global(k).unwrap(), 
+                individual.get_normalized_global(k).unwrap(), 
+                epsilon
+            ));
+        });
+
+        let unknown_peers = (1000..1010).map(|i| (i, OrderedFloat::from(1.0)));
+        batched.init_global_batch(&5000, unknown_peers.clone());
+        light.init_global_batch(&5000, unknown_peers);
+        assert_eq!(batched.global_raw_len(), 1000);
+        assert!(light.global_keys().is_empty());
+    }
+
+    #[test]
+    fn diff_should_list_exactly_the_changed_keys() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.init_local_batch(vec![
+            ("node_1", OrderedFloat::from(1.0)),
+            ("node_2", OrderedFloat::from(1.0)),
+            ("node_3", OrderedFloat::from(2.0)),
+        ]);
+
+        let before = hp.clone();
+        hp.update_local(&"node_1", 1.0.into(), Update::Increment);
+        hp.update_local(&"node_3", 1.0.into(), Update::Decrement);
+
+        let diff = diff_normalized_local(&before, &hp);
+        let expected: HashMap<&str, OrderedFloat<f64>> = vec![
+            ("node_1", OrderedFloat::from(0.25)),
+            ("node_3", OrderedFloat::from(-0.25)),
+        ].into_iter().collect();
+
+        assert_eq!(diff, expected);
+        assert!(diff_normalized_local(&hp, &hp).is_empty());
+
+        let mut other: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        other.init_local_batch(vec![
+            ("node_1", OrderedFloat::from(2.0)),
+            ("node_2", OrderedFloat::from(1.0)),
+            ("node_4", OrderedFloat::from(1.0)),
+        ]);
+
+        let diff = diff_normalized_local(&before, &other);
+        let expected: HashMap<&str, OrderedFloat<f64>> = vec![
+            ("node_1", OrderedFloat::from(0.25)),
+            ("node_3", OrderedFloat::from(-0.5)),
+            ("node_4", OrderedFloat::from(0.25)),
+        ].into_iter().collect();
+
+        assert_eq!(diff, expected);
+    }
+
+    #[test]
+    fn default_sketch_should_use_documented_dimensions() {
+        use crate::cms::{DEFAULT_WIDTH, DEFAULT_DEPTH};
+
+        let cms: CountMinSketch<OrderedFloat<f64>> = CountMinSketch::default();
+        assert_eq!((cms.width(), cms.depth()), (256, 5));
+        assert_eq!((cms.width(), cms.depth()), (DEFAULT_WIDTH, DEFAULT_DEPTH));
+        assert_eq!(cms.matrix().len(), DEFAULT_DEPTH);
+        assert!(cms.matrix().iter().all(|row| row.len() == DEFAULT_WIDTH));
+    }
+
+    #[test]
+    fn subset_normalization_should_only_count_subset_members() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.init_local_batch(vec![
+            ("node_1", OrderedFloat::from(1.0)),
+            ("node_2", OrderedFloat::from(1.0)),
+            ("node_3", OrderedFloat::from(2.0)),
+            ("node_4", OrderedFloat::from(4.0)),
+        ]);
+
+        let everyone = hp.local_keys();
+        assert_eq!(
+            hp.get_normalized_local_within(&"node_3", &everyone),
+            hp.get_normalized_local(&"node_3")
+        );
+
+        let overlapping = ["node_1", "node_3", "node_3"];
+        assert_eq!(
+            hp.get_normalized_local_within(&"node_3", &overlapping),
+            Some(OrderedFloat::from(2.0 / 3.0))
+        );
+        assert!(
+            hp.get_normalized_local_within(&"node_3", &overlapping) > 
+            hp.get_normalized_local(&"node_3")
+        );
+
+        let disjoint = ["node_1", "node_2"];
+        assert_eq!(
+            hp.get_normalized_local_within(&"node_4", &disjoint),
+            Some(OrderedFloat::from(2.0))
+        );
+        assert_eq!(hp.get_normalized_local_within(&"node_5", &disjoint), None);
+        assert_eq!(hp.get_normalized_local_within(&"node_1", &["node_5"]), None);
+    }
+
+    #[test]
+    fn try_decrement_should_error_only_when_delta_exceeds_estimate() {
+        let mut cms: CountMinSketch<u32> = CountMinSketch::new(64, 4, 0, u32::MAX);
+        cms.increment(&"node_1", 10);
+
+        assert_eq!(
+            cms.try_decrement(&"node_1", 11),
+            Err(UnderflowError { attempted: 11, available: 10 })
+        );
+        assert_eq!(cms.estimate(&"node_1"), 10);
+
+        assert_eq!(cms.try_decrement(&"node_1", 10), Ok(()));
+        assert_eq!(cms.estimate(&"node_1"), 0);
+        assert!(cms.try_decrement(&"node_1", 1).is_err());
+
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.init_local(&"node_1", 4.0.into());
+
+        assert_eq!(
+            hp.try_decrement_local(&"node_1", 5.0.into()),
+            Err(UnderflowError { attempted: 5.0.into(), available: 4.0.into() })
+        );
+        assert_eq!(hp.get_raw_local(&"node_1"), Some(OrderedFloat::from(4.0)));
+        assert!(hp.try_decrement_local(&"node_1", 4.0.into()).is_ok());
+        assert_eq!(hp.get_raw_local(&"node_1"), Some(OrderedFloat::from(0.0)));
+        assert!(hp.try_decrement_local(&"node_2", 1.0.into()).is_err());
+        assert_eq!(hp.get_raw_local(&"node_2"), None);
+    }
+
+    #[test]
+    fn custom_weight_strategy_should_cap_global_updates() {
+        use crate::weight::WeightStrategy;
+
+        struct Capped(OrderedFloat<f64>);
+
+        impl WeightStrategy<OrderedFloat<f64>> for Capped {
+            fn weight(
+                &self, 
+                delta: OrderedFloat<f64>, 
+                sender_trust: OrderedFloat<f64>
+            ) -> OrderedFloat<f64> {
+                std::cmp::min(delta * sender_trust, self.0)
+            }
+        }
+
+        let cap = OrderedFloat::from(1.0);
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new().with_weight_strategy(Capped(cap))
+        };
+        let mut light: LightHonestPeer<&str, OrderedFloat<f64>> = {
+            LightHonestPeer::new().with_weight_strategy(Capped(cap))
+        };
+        let mut uncapped: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+
+        fn report<H>(hp: &mut H) 
+        where
+            H: HonestPeer<Key = &'static str, Value = OrderedFloat<f64>>
+        {
+            hp.init_local(&"node_1", 3.0.into());
+            hp.init_local(&"node_2", 1.0.into());
+            hp.init_global(&"node_1", &"node_3", 2.0.into());
+            hp.update_global(&"node_1", &"node_3", 4.0.into(), Update::Increment);
+            hp.update_global(&"node_2", &"node_4", 2.0.into(), Update::Increment);
+        }
+
+        report(&mut precise);
+        report(&mut light);
+        report(&mut uncapped);
+
+        assert_eq!(precise.get_raw_global(&"node_3"), Some(OrderedFloat::from(2.0)));
+        assert_eq!(precise.get_raw_global(&"node_4"), Some(OrderedFloat::from(0.5)));
+        assert_eq!(light.get_raw_global(&"node_3"), Some(OrderedFloat::from(2.0)));
+        assert_eq!(light.get_raw_global(&"node_4"), Some(OrderedFloat::from(0.5)));
+
+        assert_eq!(uncapped.get_raw_global(&"node_3"), Some(OrderedFloat::from(4.5)));
+        assert_eq!(uncapped.get_raw_global(&"node_4"), Some(OrderedFloat::from(0.5)));
+
+        let reports = [("node_1", OrderedFloat::from(8.0)), ("node_2", OrderedFloat::from(8.0))];
+        precise.update_global_multi(&reports, &"node_5", Update::Increment);
+        assert_eq!(precise.get_raw_global(&"node_5"), Some(OrderedFloat::from(2.0)));
+    }
+
+    #[test]
+    fn suspect_collisions_should_flag_keys_sharing_cells() {
+        // 2 columns and 17 rows, so each light key shares a cell with 
+        // the heavy key in roughly half of the rows
+        let mut hp: LightHonestPeer<String, u64> = {
+            LightHonestPeer::new_from_bounds(1.4, 1e-7, 1.0, 0, u64::MAX)
+        };
+        hp.init_local(&"heavy".to_string(), 1_000);
+
+        let light_keys: Vec<String> = (0..5).map(|i| format!("light_{}", i)).collect();
+        light_keys.iter().for_each(|key| hp.init_local(key, 1));
+
+        let flagged = hp.suspect_collisions(&light_keys, 10);
+        assert_eq!(flagged, light_keys);
+        assert!(hp.suspect_collisions(&["heavy".to_string()], 10).is_empty());
+
+        let mut roomy: LightHonestPeer<String, u64> = {
+            LightHonestPeer::new_from_bounds(1e-4, 0.02, 1.0, 0, u64::MAX)
+        };
+        roomy.init_local(&"heavy".to_string(), 1_000);
+        light_keys.iter().for_each(|key| roomy.init_local(key, 1));
+
+        assert!(roomy.suspect_collisions(&light_keys, 10).is_empty());
+    }
+
+    #[test]
+    fn shrink_to_fit_should_reclaim_capacity_after_removals() {
+        let mut hp: PreciseHonestPeer<usize, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.init_local_batch((0..5000).map(|i| (i, OrderedFloat::from(1.0))));
+        hp.init_local(&5000, 1.0.into());
+
+        let grown = hp.capacity();
+        assert!(grown >= 5001);
+
+        (0..5000).for_each(|i| {
+            assert_eq!(hp.remove_local(&i), Some(OrderedFloat::from(1.0)));
+        });
+        assert_eq!(hp.remove_local(&0), None);
+
+        let before = hp.capacity();
+        hp.shrink_to_fit();
+
+        assert!(hp.capacity() < before);
+        assert!(hp.capacity() < grown);
+        assert_eq!(hp.local_raw_len(), 1);
+        assert_eq!(hp.get_normalized_local(&5000), Some(OrderedFloat::from(1.0)));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn weighted_sampling_should_follow_normalized_trust() {
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new();
+        let peers = vec![
+            ("node_1", OrderedFloat::from(1.0)),
+            ("node_2", OrderedFloat::from(3.0)),
+            ("node_3", OrderedFloat::from(6.0)),
+        ];
+        precise.init_local_batch(peers.clone());
+        light.init_local_batch(peers);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples = 20_000;
+        let mut precise_counts: HashMap<&str, usize> = HashMap::new();
+        let mut light_counts: HashMap<&str, usize> = HashMap::new();
+        for _ in 0..samples {
+            *precise_counts.entry(precise.sample_local_weighted(&mut rng).unwrap()).or_default() += 1;
+            *light_counts.entry(light.sample_local_weighted(&mut rng).unwrap()).or_default() += 1;
+        }
+
+        for key in ["node_1", "node_2", "node_3"] {
+            let expected = precise.get_normalized_local(&key).unwrap().into_inner();
+            let precise_freq = precise_counts[key] as f64 / samples as f64;
+            let light_freq = light_counts[key] as f64 / samples as f64;
+            assert!((precise_freq - expected).abs() < 0.02);
+            assert!((light_freq - expected).abs() < 0.02);
+        }
+
+        let empty: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        assert_eq!(empty.sample_local_weighted(&mut rng), None);
+    }
+
+    #[test]
+    fn merged_light_peers_should_sum_contributions() {
+        let error_bound = 10.0;
+        let probability = 0.0001;
+        let max_entries = 3000.0;
+        let base: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new_from_bounds(
+            error_bound,
+            probability,
+            max_entries,
+            OrderedFloat::from(0.0),
+            OrderedFloat::from(f64::MAX)
+        );
+
+        let mut a = base.clone();
+        let mut b = base.clone();
+        a.init_local_batch(vec![("node_1", 5.0.into()), ("node_2", 1.0.into())]);
+        b.init_local_batch(vec![("node_1", 2.0.into()), ("node_3", 4.0.into())]);
+        a.init_global(&"node_1", &"node_4", 6.0.into());
+        b.init_global(&"node_3", &"node_4", 4.0.into());
+        a.update_local(&"node_2", 1.0.into(), Update::Increment);
+        b.update_local(&"node_2", 1.0.into(), Update::Increment);
+
+        let mut merged = a.clone();
+        assert_eq!(merged.merge(&b), Ok(()));
+
+        let epsilon = OrderedFloat::from(error_bound);
+        let mut local_keys = merged.local_keys();
+        local_keys.sort();
+        assert_eq!(local_keys, vec!["node_1", "node_2", "node_3"]);
+        for key in ["node_1", "node_2", "node_3"] {
+            let expected = a.get_raw_local(&key).unwrap() + b.get_raw_local(&key).unwrap();
+            let actual = merged.get_raw_local(&key).unwrap();
+            assert!(actual >= expected);
+            assert!(trust_approx_eq(actual, expected, epsilon));
+        }
+
+        let expected = a.get_raw_global(&"node_4").unwrap() + b.get_raw_global(&"node_4").unwrap();
+        assert!(trust_approx_eq(merged.get_raw_global(&"node_4").unwrap(), expected, epsilon));
+        assert_eq!(merged.observation_count_local(&"node_2"), 2);
+
+        let total: OrderedFloat<f64> = ["node_1", "node_2", "node_3"].iter()
+            .map(|key| merged.get_normalized_local(key).unwrap())
+            .fold(OrderedFloat::from(0.0), |acc, v| acc + v);
+        assert!(trust_approx_eq(total, OrderedFloat::from(1.0), OrderedFloat::from(1e-9)));
+
+        let unrelated: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new_from_bounds(
+            error_bound,
+            probability,
+            max_entries,
+            OrderedFloat::from(0.0),
+            OrderedFloat::from(f64::MAX)
+        );
+        assert_eq!(merged.merge(&unrelated), Err(MergeError::SeedMismatch));
+
+        let smaller: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new();
+        assert!(matches!(
+            merged.merge(&smaller), 
+            Err(MergeError::DimensionMismatch { .. })
+        ));
+        assert!(trust_approx_eq(
+            merged.get_raw_local(&"node_1").unwrap(), 
+            OrderedFloat::from(7.0), 
+            epsilon
+        ));
+    }
+
+    #[test]
+    fn light_from_precise_should_estimate_within_error_bound() {
+        let error_bound = 10.0;
+        let mut precise: PreciseHonestPeer<usize, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        precise.init_local_batch((0..200).map(|i| (i, OrderedFloat::from((i % 7 + 1) as f64))));
+        precise.init_global_batch(&0, (200..300).map(|i| (i, OrderedFloat::from((i % 5) as f64))));
+
+        let light = LightHonestPeer::from_precise(&precise, error_bound, 0.0001, 3000.0);
+
+        let mut precise_keys = precise.local_keys();
+        let mut light_keys = light.local_keys();
+        precise_keys.sort();
+        light_keys.sort();
+        assert_eq!(precise_keys, light_keys);
+        assert_eq!(light.global_keys().len(), precise.global_keys().len());
+
+        let epsilon = OrderedFloat::from(error_bound);
+        for (key, value) in precise.get_raw_local_map() {
+            let estimate = light.get_raw_local(&key).unwrap();
+            assert!(estimate >= value);
+            assert!(trust_approx_eq(estimate, value, epsilon));
+        }
+        for (key, value) in precise.get_raw_global_map() {
+            let estimate = light.get_raw_global(&key).unwrap();
+            assert!(estimate >= value);
+            assert!(trust_approx_eq(estimate, value, epsilon));
+        }
+    }
+
+    #[test]
+    fn lazy_normalization_should_defer_until_read() {
+        let mut lazy: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut eager: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        lazy.set_lazy_normalization(true);
+
+        for hp in [&mut lazy, &mut eager] {
+            hp.init_local_batch(vec![("node_1", 1.0.into()), ("node_2", 1.0.into())]);
+            hp.update_local(&"node_1", 2.0.into(), Update::Increment);
+            hp.update_local(&"node_3", 4.0.into(), Update::Increment);
+            hp.update_local(&"node_2", 0.5.into(), Update::Decrement);
+            hp.init_global(&"node_1", &"node_4", 2.0.into());
+            hp.update_global(&"node_3", &"node_4", 1.0.into(), Update::Increment);
+        }
+
+        assert!(lazy.is_normalization_stale());
+        assert!(!eager.is_normalization_stale());
+
+        for key in ["node_1", "node_2", "node_3"] {
+            assert_eq!(lazy.get_normalized_local(&key), eager.get_normalized_local(&key));
+        }
+        assert_eq!(lazy.get_normalized_local_map(), eager.get_normalized_local_map());
+        assert_eq!(lazy.get_normalized_global_map(), eager.get_normalized_global_map());
+        assert_eq!(lazy.local_normalized_len(), 3);
+        assert_eq!(lazy.local_quantile(1.0), eager.local_quantile(1.0));
+
+        // reads do not store the result, only an explicit normalization does
+        assert!(lazy.is_normalization_stale());
+        lazy.normalize_local();
+        lazy.normalize_global();
+        assert!(!lazy.is_normalization_stale());
+
+        lazy.update_local(&"node_1", 1.0.into(), Update::Increment);
+        assert!(lazy.is_normalization_stale());
+        lazy.set_lazy_normalization(false);
+        assert!(!lazy.is_normalization_stale());
+
+        eager.update_local(&"node_1", 1.0.into(), Update::Increment);
+        assert_eq!(lazy.get_normalized_local_map(), eager.get_normalized_local_map());
+    }
+
+    #[test]
+    fn backends_should_report_whether_they_are_exact() {
+        fn exact<H: HonestPeer>(hp: &H) -> bool {
+            hp.is_exact()
+        }
+
+        let precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let light: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new();
+
+        assert!(exact(&precise));
+        assert!(!exact(&light));
+    }
+
+    #[test]
+    fn reconstructed_map_should_hold_every_retained_key() {
+        let mut hp: LightHonestPeer<usize, OrderedFloat<f64>> = LightHonestPeer::new();
+        hp.init_local_batch((0..100).map(|i| (i, OrderedFloat::from(i as f64))));
+        hp.update_local(&100, 5.0.into(), Update::Increment);
+        hp.init_global_batch(&1, (0..10).map(|i| (i, OrderedFloat::from(1.0))));
+
+        let local = hp.reconstruct_local();
+        assert_eq!(local.len(), 101);
+        for key in hp.local_keys() {
+            assert_eq!(local.get(&key).copied(), hp.get_raw_local(&key));
+        }
+
+        let global = hp.reconstruct_global();
+        assert_eq!(global.len(), 10);
+        for key in hp.global_keys() {
+            assert_eq!(global.get(&key).copied(), hp.get_raw_global(&key));
+        }
+    }
+
+    #[test]
+    fn decrements_should_stop_at_configured_floor() {
+        let floor = OrderedFloat::from(2.0);
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new().with_floor(floor)
+        };
+        let mut light: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new();
+        light.set_floor(floor);
+        assert_eq!(light.floor(), floor);
+
+        fn run<H>(hp: &mut H) 
+        where
+            H: HonestPeer<Key = &'static str, Value = OrderedFloat<f64>>
+        {
+            hp.init_local(&"node_1", 10.0.into());
+            hp.init_local(&"node_2", 1.0.into());
+            hp.update_local(&"node_1", 100.0.into(), Update::Decrement);
+            hp.update_local(&"node_2", 100.0.into(), Update::Decrement);
+            hp.init_global(&"node_1", &"node_3", 8.0.into());
+            hp.update_global(&"node_1", &"node_3", 1000.0.into(), Update::Decrement);
+        }
+
+        run(&mut precise);
+        run(&mut light);
+
+        // node_1 stops at the floor, node_2 started below it and is untouched
+        assert_eq!(precise.get_raw_local(&"node_1"), Some(floor));
+        assert_eq!(precise.get_raw_local(&"node_2"), Some(OrderedFloat::from(1.0)));
+        assert_eq!(precise.get_raw_global(&"node_3"), Some(floor));
+        assert_eq!(light.get_raw_local(&"node_1"), Some(floor));
+        assert_eq!(light.get_raw_local(&"node_2"), Some(OrderedFloat::from(1.0)));
+        assert_eq!(light.get_raw_global(&"node_3"), Some(floor));
+
+        precise.update_local(&"node_1", 1.0.into(), Update::Increment);
+        assert!(precise.try_decrement_local(&"node_1", 2.0.into()).is_err());
+        assert!(precise.try_decrement_local(&"node_1", 1.0.into()).is_ok());
+        assert_eq!(precise.get_raw_local(&"node_1"), Some(floor));
+    }
+
+    #[test]
+    fn rank_normalization_should_resist_outliers() {
+        let peers = vec![
+            ("node_1", OrderedFloat::from(1.0)),
+            ("node_2", OrderedFloat::from(2.0)),
+            ("node_3", OrderedFloat::from(3.0)),
+            ("node_4", OrderedFloat::from(10_000.0)),
+        ];
+        let mut by_sum: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut by_rank: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new();
+        by_sum.init_local_batch(peers.clone());
+        by_rank.set_normalization_mode(NormalizationMode::Rank);
+        by_rank.init_local_batch(peers.clone());
+        light.init_local_batch(peers);
+        light.set_normalization_mode(NormalizationMode::Rank);
+
+        let expected = [("node_1", 0.25), ("node_2", 0.5), ("node_3", 0.75), ("node_4", 1.0)];
+        for (key, rank) in expected {
+            assert_eq!(by_rank.get_normalized_local(&key), Some(OrderedFloat::from(rank)));
+            assert_eq!(light.get_normalized_local(&key), Some(OrderedFloat::from(rank)));
+        }
+
+        // without the outlier, the other peers are barely distinguishable by sum
+        let spread = |hp: &PreciseHonestPeer<&str, OrderedFloat<f64>>| {
+            hp.get_normalized_local(&"node_3").unwrap() - hp.get_normalized_local(&"node_1").unwrap()
+        };
+        assert!(spread(&by_sum) < OrderedFloat::from(0.001));
+        assert_eq!(spread(&by_rank), OrderedFloat::from(0.5));
+
+        by_rank.update_local(&"node_1", 5.0.into(), Update::Increment);
+        assert_eq!(by_rank.get_normalized_local(&"node_1"), Some(OrderedFloat::from(0.75)));
+        assert_eq!(by_rank.get_normalized_local(&"node_3"), Some(OrderedFloat::from(0.5)));
+
+        by_sum.rank_normalize_local();
+        assert_eq!(by_sum.get_normalized_local(&"node_2"), Some(OrderedFloat::from(0.5)));
+        by_sum.update_local(&"node_2", 0.0.into(), Update::Increment);
+        assert!(by_sum.get_normalized_local(&"node_2") < Some(OrderedFloat::from(0.001)));
+    }
+
+    #[test]
+    fn cells_above_should_only_yield_cells_over_threshold() {
+        let matrix = vec![
+            vec![1u64, 20, 3, 40],
+            vec![50, 6, 70, 10],
+        ];
+        let cms = CountMinSketch::from_matrix(matrix, 0, u64::MAX, 0).unwrap();
+
+        let hot: Vec<((usize, usize), u64)> = cms.cells_above(10).collect();
+        assert_eq!(hot, vec![((0, 1), 20), ((0, 3), 40), ((1, 0), 50), ((1, 2), 70)]);
+
+        assert_eq!(cms.cells_above(0).count(), 8);
+        assert_eq!(cms.cells_above(70).count(), 0);
+    }
+
+    #[test]
+    fn trust_similarity_should_flag_identical_reporting() {
+        let build = |entries: &[(&'static str, f64)]| {
+            let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+            entries.iter().for_each(|(key, value)| hp.init_local(key, (*value).into()));
+            hp
+        };
+
+        let a = build(&[("node1", 1.0), ("node2", 2.0)]);
+        let identical = build(&[("node1", 1.0), ("node2", 2.0)]);
+        let orthogonal = build(&[("node3", 1.0), ("node4", 5.0)]);
+        let partial = build(&[("node1", 1.0), ("node3", 2.0)]);
+
+        assert!((a.trust_similarity(&identical) - 1.0).abs() < 1e-9);
+        assert_eq!(a.trust_similarity(&orthogonal), 0.0);
+
+        let similarity = a.trust_similarity(&partial);
+        assert!(similarity > 0.0 && similarity < 0.5);
+        assert!((similarity - partial.trust_similarity(&a)).abs() < 1e-9);
+
+        let empty = build(&[]);
+        assert_eq!(a.trust_similarity(&empty), 0.0);
+    }
+
+    #[test]
+    fn decay_by_age_should_decay_stale_peers_more() {
+        use std::sync::{Arc, Mutex};
+        use std::time::{Duration, Instant};
+        use crate::clock::Clock;
+
+        #[derive(Clone)]
+        struct MockClock(Arc<Mutex<Instant>>);
+
+        impl Clock for MockClock {
+            fn now(&self) -> Instant {
+                *self.0.lock().unwrap()
+            }
+        }
+
+        let clock = MockClock(Arc::new(Mutex::new(Instant::now())));
+        let advance = |secs: u64| *clock.0.lock().unwrap() += Duration::from_secs(secs);
+
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new().with_clock(clock.clone())
+        };
+        hp.init_local(&"stale", 8f64.into());
+        advance(20);
+        hp.init_local(&"fresh", 8f64.into());
+        advance(10);
+
+        hp.decay_by_age(Duration::from_secs(10));
+
+        let epsilon = OrderedFloat::from(1e-9);
+        assert!(trust_approx_eq(hp.get_raw_local(&"stale").unwrap(), 1f64.into(), epsilon));
+        assert!(trust_approx_eq(hp.get_raw_local(&"fresh").unwrap(), 4f64.into(), epsilon));
+        assert!(trust_approx_eq(hp.get_normalized_local(&"fresh").unwrap(), 0.8.into(), epsilon));
+
+        // No time has passed since the last decay
+        hp.decay_by_age(Duration::from_secs(10));
+        assert!(trust_approx_eq(hp.get_raw_local(&"fresh").unwrap(), 4f64.into(), epsilon));
+    }
+
+    #[test]
+    fn strict_mode_should_reject_updates_to_unknown_peers() {
+        let mut strict: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new().with_strict(true)
+        };
+        strict.init_local(&"node1", 1f64.into());
+
+        strict.update_local(&"node2", 1f64.into(), Update::Increment);
+        assert_eq!(strict.get_raw_local(&"node2"), None);
+        assert_eq!(
+            strict.try_update_local(&"node2", 1f64.into(), Update::Increment),
+            Err(UnknownPeerError { key: "node2" })
+        );
+        assert_eq!(
+            strict.try_update_global(&"node1", &"node3", 1f64.into(), Update::Increment),
+            Err(UnknownPeerError { key: "node3" })
+        );
+        assert_eq!(strict.get_raw_global(&"node3"), None);
+
+        strict.init_global(&"node1", &"node3", 1f64.into());
+        assert!(strict.try_update_global(&"node1", &"node3", 1f64.into(), Update::Increment).is_ok());
+        assert_eq!(strict.get_raw_global(&"node3"), Some(OrderedFloat::from(2.0)));
+
+        let mut lenient: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        assert!(lenient.try_update_local(&"node2", 1f64.into(), Update::Increment).is_ok());
+        assert_eq!(lenient.get_raw_local(&"node2"), Some(OrderedFloat::from(1.0)));
+
+        let mut light = LightHonestPeer::<&str, OrderedFloat<f64>>::new().with_strict(true);
+        light.init_local(&"node1", 1f64.into());
+        assert!(light.try_update_local(&"node1", 1f64.into(), Update::Increment).is_ok());
+        assert!(light.try_update_local(&"node2", 1f64.into(), Update::Increment).is_err());
+        assert_eq!(light.local_keys(), vec!["node1"]);
+    }
+
+    #[test]
+    fn combined_trust_should_interpolate_between_local_and_global() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.init_local(&"node1", 3f64.into());
+        hp.init_local(&"node2", 1f64.into());
+        hp.init_global(&"node1", &"node2", 1f64.into());
+        hp.init_global(&"node1", &"node3", 1f64.into());
+
+        let local = hp.get_normalized_local(&"node2").unwrap();
+        let global = hp.get_normalized_global(&"node2").unwrap();
+        let epsilon = OrderedFloat::from(1e-9);
+
+        assert_eq!(hp.combined_trust(&"node2", 1f64.into()), Some(local));
+        assert_eq!(hp.combined_trust(&"node2", 0f64.into()), Some(global));
+
+        let blended = hp.combined_trust(&"node2", 0.25.into()).unwrap();
+        assert!(trust_approx_eq(blended, local * 0.25 + global * 0.75, epsilon));
+        assert!(blended > local.min(global) && blended < local.max(global));
+
+        // node3 is only known globally, its local side counts as zero
+        let global_only = hp.combined_trust(&"node3", 0.5.into()).unwrap();
+        assert!(trust_approx_eq(global_only, hp.get_normalized_global(&"node3").unwrap() * 0.5, epsilon));
+        assert_eq!(hp.combined_trust(&"node4", 0.5.into()), None);
+    }
+
+    #[test]
+    fn dimension_getters_should_return_constructed_dimensions() {
+        let cms = CountMinSketch::<u64>::new(37, 4, 0, u64::MAX);
+        assert_eq!((cms.width(), cms.depth()), (37, 4));
+        assert_eq!((cms.get_width(), cms.get_depth()), (cms.width(), cms.depth()));
+
+        let matrix = vec![vec![0u64; 9]; 2];
+        let cms = CountMinSketch::from_matrix(matrix, 0, u64::MAX, 0).unwrap();
+        assert_eq!((cms.width(), cms.depth()), (9, 2));
+        assert_eq!((&cms).into_iter().count(), 18);
+
+        let hp = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+        assert_eq!((hp.get_width(), hp.get_depth()), (256, 5));
+    }
+
+    #[test]
+    fn ema_updates_should_converge_to_repeated_observation() {
+        fn converge<H>(hp: &mut H) -> Vec<OrderedFloat<f64>>
+        where
+            H: HonestPeer<Key = &'static str, Value = OrderedFloat<f64>>
+        {
+            hp.init_local(&"node1", 10f64.into());
+            (0..50).map(|_| {
+                hp.update_local_ema(&"node1", 2f64.into(), 0.2.into());
+                hp.get_raw_local(&"node1").unwrap()
+            }).collect()
+        }
+
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+
+        for history in [converge(&mut precise), converge(&mut light)] {
+            assert!(trust_approx_eq(history[0], 8.4.into(), OrderedFloat::from(1e-9)));
+            assert!(history.windows(2).all(|w| w[1] <= w[0] && w[1] >= OrderedFloat::from(2.0)));
+            assert!(trust_approx_eq(history[49], 2f64.into(), OrderedFloat::from(1e-3)));
+        }
+    }
+
+    #[test]
+    fn pretrusted_peers_should_keep_sybils_from_dominating() {
+        let honest = ["h1", "h2", "h3"];
+        let sybils = ["s1", "s2", "s3", "s4", "s5"];
+        let vector = |peers: &[&'static str], owner: &str| -> HashMap<&'static str, OrderedFloat<f64>> {
+            peers.iter().filter(|p| **p != owner).map(|p| (*p, 1f64.into())).collect()
+        };
+
+        let build = || {
+            let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+            honest.iter().for_each(|h| hp.set_local_vector(h, vector(&honest, h)));
+            sybils.iter().for_each(|s| hp.set_local_vector(s, vector(&sybils, s)));
+
+            // h3 was tricked into giving a sybil a little trust
+            let mut h3 = vector(&honest, "h3");
+            h3.insert("s1", 0.2.into());
+            hp.set_local_vector("h3", h3);
+            hp
+        };
+        let sybil_share = |hp: &PreciseHonestPeer<&str, OrderedFloat<f64>>| {
+            sybils.iter().map(|s| hp.get_normalized_global(s).unwrap_or_default().0).sum::<f64>()
+        };
+
+        let mut undamped = build();
+        undamped.converge_global(1000, 1e-12.into());
+        assert!(sybil_share(&undamped) > 0.5);
+
+        let mut damped = build().with_damping(0.2.into());
+        damped.set_pretrusted(&[("h1", 1f64.into()), ("h2", 1f64.into())]);
+        let iterations = damped.converge_global(1000, 1e-12.into());
+
+        assert!(iterations < 1000);
+        assert!(sybil_share(&damped) < 0.2);
+        let total: f64 = damped.get_normalized_global_map().values().map(|v| v.0).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn unsigned_sketch_updates_should_saturate_instead_of_wrapping() {
+        let mut cms = CountMinSketch::<u64>::new(50, 4, 0, u64::MAX);
+
+        cms.increment(&"node1", u64::MAX - 10);
+        cms.increment(&"node1", 100);
+        assert_eq!(cms.estimate(&"node1"), u64::MAX);
+        cms.increment(&"node1", u64::MAX);
+        assert_eq!(cms.estimate(&"node1"), u64::MAX);
+
+        let mut other = cms.clone();
+        other.merge(&cms).unwrap();
+        assert_eq!(other.estimate(&"node1"), u64::MAX);
+
+        let mut cms = CountMinSketch::<u64>::new(50, 4, 0, u64::MAX);
+        cms.increment(&"node2", 5);
+        cms.decrement(&"node2", 10);
+        assert_eq!(cms.estimate(&"node2"), 0);
+        cms.decrement(&"node2", u64::MAX);
+        assert_eq!(cms.estimate(&"node2"), 0);
+
+        let mut signed = CountMinSketch::<i64>::new(50, 4, i64::MIN, i64::MAX);
+        signed.increment(&"node1", i64::MIN + 1);
+        signed.increment(&"node1", -10);
+        assert_eq!(signed.estimate(&"node1"), i64::MIN);
+
+        let mut signed = CountMinSketch::<i64>::new(50, 4, i64::MIN, i64::MAX);
+        signed.increment(&"node1", i64::MAX - 1);
+        signed.decrement(&"node1", -10);
+        assert_eq!(signed.estimate(&"node1"), i64::MAX);
+    }
+
+    #[test]
+    fn flat_and_nested_sketches_should_give_identical_estimates() {
+        let mut cms = CountMinSketch::<u64>::new(40, 5, 0, u64::MAX);
+        (0..30u64).for_each(|i| cms.increment(&i, i * 7 + 1));
+
+        let flat = cms.flatten();
+        assert_eq!(flat.len(), cms.width() * cms.depth());
+        cms.matrix().iter().enumerate().for_each(|(row, cells)| {
+            cells.iter().enumerate().for_each(|(col, cell)| {
+                assert_eq!(flat[row * cms.width() + col], *cell);
+            })
+        });
+
+        let rebuilt = CountMinSketch::from_flat(flat, cms.width(), 0, u64::MAX, cms.seed()).unwrap();
+        assert_eq!(rebuilt, cms);
+        (0..30u64).for_each(|i| assert_eq!(rebuilt.estimate(&i), cms.estimate(&i)));
+
+        assert_eq!(
+            CountMinSketch::<u64>::from_flat(vec![0; 7], 3, 0, u64::MAX, 0),
+            Err(ShapeError::Jagged { row: 2, expected: 3, found: 1 })
+        );
+        assert_eq!(
+            CountMinSketch::<u64>::from_flat(vec![0; 6], 0, 0, u64::MAX, 0),
+            Err(ShapeError::Empty)
+        );
+    }
+
+    #[test]
+    fn binary_encoding_should_round_trip_byte_identically() {
+        let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new()
+                .with_floor(0.5.into())
+                .with_strict(true)
+                .with_newcomer_default(0.25.into())
+                .with_attribution(true)
+        };
+        (0..20).for_each(|i| hp.init_local(&format!("node{}", i), (i as f64 + 1.0).into()));
+        hp.init_global(&"node3".to_string(), &"node7".to_string(), 2f64.into());
+        hp.set_local_vector("node1".to_string(), HashMap::from([("node2".to_string(), 4f64.into())]));
+        hp.set_pretrusted(&[("node1".to_string(), 1f64.into())]);
+        hp.set_normalization_mode(NormalizationMode::Rank);
+        hp.update_global(&"node4".to_string(), &"node7".to_string(), 3f64.into(), Update::Increment);
+
+        let bytes = hp.to_bytes();
+        let mut decoded = PreciseHonestPeer::<String, OrderedFloat<f64>>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(decoded.get_raw_local_map(), hp.get_raw_local_map());
+        assert_eq!(decoded.get_normalized_local_map(), hp.get_normalized_local_map());
+        assert_eq!(decoded.get_normalized_global_map(), hp.get_normalized_global_map());
+        assert_eq!(decoded.local_trust_matrix(), hp.local_trust_matrix());
+        assert_eq!((decoded.floor(), decoded.is_strict()), (hp.floor(), true));
+        assert_eq!(decoded.newcomer_default(), OrderedFloat::from(0.25));
+
+        // attributed reports can still be revoked after a round trip
+        assert!(hp.sender_influence()["node4"] > OrderedFloat::from(0.0));
+        assert_eq!(decoded.sender_influence(), hp.sender_influence());
+        decoded.revoke_sender(&"node4".to_string());
+        hp.revoke_sender(&"node4".to_string());
+        assert_eq!(decoded.get_raw_global_map(), hp.get_raw_global_map());
+        assert!(!decoded.sender_influence().contains_key("node4"));
+
+        let mut cms = CountMinSketch::<u64>::new(64, 4, 0, u64::MAX);
+        (0..50u64).for_each(|i| cms.increment(&i, i + 1));
+        let bytes = cms.to_bytes();
+        let decoded = CountMinSketch::<u64>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        (0..50u64).for_each(|i| assert_eq!(decoded.estimate(&i), cms.estimate(&i)));
+
+        let mut future = bytes.clone();
+        future[0] = 2;
+        assert_eq!(CountMinSketch::<u64>::from_bytes(&future), Err(DecodeError::UnsupportedVersion(2)));
+        assert_eq!(
+            CountMinSketch::<u64>::from_bytes(&bytes[..bytes.len() - 1]), 
+            Err(DecodeError::UnexpectedEnd)
+        );
+        let mut padded = bytes.clone();
+        padded.push(0);
+        assert_eq!(CountMinSketch::<u64>::from_bytes(&padded), Err(DecodeError::TrailingBytes(1)));
+    }
+
+    #[test]
+    fn normalize_global_should_leave_raw_global_unchanged() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.init_local(&"node1", 1f64.into());
+        hp.init_global(&"node1", &"node2", 3f64.into());
+        hp.init_global(&"node1", &"node3", 5f64.into());
+
+        let raw = hp.get_raw_global_map();
+        hp.normalize_global();
+        hp.normalize_global();
+
+        assert_eq!(hp.get_raw_global_map(), raw);
+        assert_eq!(hp.get_raw_global(&"node2"), Some(OrderedFloat::from(3.0)));
+        assert_eq!(hp.get_normalized_global(&"node3"), Some(OrderedFloat::from(5.0 / 8.0)));
+    }
+
+    #[test]
+    fn seen_filter_should_have_no_false_negatives() {
+        use crate::bloom::BloomFilter;
+
+        let mut hp = LightHonestPeer::<u64, OrderedFloat<f64>>::new()
+            .with_seen_filter(BloomFilter::with_rate(5000, 0.01));
+        (0..2500u64).for_each(|i| hp.init_local(&i, 1f64.into()));
+        (2500..5000u64).for_each(|i| hp.update_local(&i, 1f64.into(), Update::Increment));
+
+        assert!((0..5000u64).all(|i| hp.maybe_seen_local(&i)));
+
+        let false_positives = (5000..15000u64).filter(|i| hp.maybe_seen_local(i)).count();
+        assert!(false_positives < 500);
+
+        let plain = LightHonestPeer::<u64, OrderedFloat<f64>>::new();
+        assert!(!plain.maybe_seen_local(&1));
+    }
+
+    #[test]
+    fn convergence_residuals_should_not_increase_on_a_well_behaved_network() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new().with_damping(0.15.into())
+        };
+        hp.set_local_vector("node1", HashMap::from([("node2", 2f64.into()), ("node3", 1f64.into())]));
+        hp.set_local_vector("node2", HashMap::from([("node3", 1f64.into()), ("node4", 1f64.into())]));
+        hp.set_local_vector("node3", HashMap::from([("node1", 3f64.into()), ("node4", 1f64.into())]));
+        hp.set_local_vector("node4", HashMap::from([("node1", 1f64.into()), ("node2", 1f64.into())]));
+        hp.set_pretrusted(&[("node1", 1f64.into())]);
+
+        let history = hp.converge_global_with_history(500, 1e-12.into());
+
+        assert!(history.len() > 1 && history.len() < 500);
+        assert!(history.windows(2).all(|w| w[1] <= w[0] + OrderedFloat::from(1e-15)));
+        assert!(*history.last().unwrap() < OrderedFloat::from(1e-10));
+        let total: f64 = hp.get_normalized_global_map().values().map(|v| v.0).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn validation_should_reject_invalid_normalized_distributions() {
+        let epsilon = OrderedFloat::from(1e-9);
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        assert!(hp.validate_normalized_local(epsilon));
+
+        ["a", "b", "c", "d", "e"].iter().enumerate()
+            .for_each(|(i, key)| hp.init_local(key, (i as f64 + 1.0).into()));
+        hp.init_global(&"a", &"x", 1f64.into());
+        hp.init_global(&"a", &"y", 3f64.into());
+        assert!(hp.validate_normalized_local(epsilon));
+        assert!(hp.validate_normalized_global(epsilon));
+
+        // a negative raw value pushes the others' share above 1
+        hp.reset_local(&"a", (-20f64).into());
+        assert!(!hp.validate_normalized_local(epsilon));
+
+        // rank normalization does not sum to 1
+        hp.reset_local(&"a", 1f64.into());
+        hp.set_normalization_mode(NormalizationMode::Rank);
+        assert!(!hp.validate_normalized_local(epsilon));
+
+        let mut light = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+        light.init_local(&"a", 1f64.into());
+        light.init_local(&"b", 3f64.into());
+        assert!(light.validate_normalized_local(OrderedFloat::from(0.1)));
+    }
+
+    #[test]
+    fn updates_to_unknown_peers_should_start_from_newcomer_default() {
+        fn first_update<H>(hp: &mut H) -> (OrderedFloat<f64>, OrderedFloat<f64>, OrderedFloat<f64>)
+        where
+            H: HonestPeer<Key = &'static str, Value = OrderedFloat<f64>>
+        {
+            hp.init_local(&"sender", 1f64.into());
+            hp.update_local(&"node1", 2f64.into(), Update::Increment);
+            hp.update_local(&"node1", 2f64.into(), Update::Increment);
+            hp.update_local(&"node2", 1f64.into(), Update::Decrement);
+            hp.update_global(&"sender", &"node3", 2f64.into(), Update::Increment);
+            let weighted_delta = hp.get_normalized_local(&"sender").unwrap() * 2.0;
+            (
+                hp.get_raw_local(&"node1").unwrap(),
+                hp.get_raw_local(&"node2").unwrap(),
+                hp.get_raw_global(&"node3").unwrap() - weighted_delta,
+            )
+        }
+
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new().with_newcomer_default(5f64.into())
+        };
+        let mut light = LightHonestPeer::<&str, OrderedFloat<f64>>::new_from_bounds(
+            1e-4, 0.01, 1.0, OrderedFloat::from(0.0), OrderedFloat::max_value()
+        ).with_newcomer_default(5f64.into());
+        let epsilon = OrderedFloat::from(1e-9);
+        let matches = |(a, b, c): (OrderedFloat<f64>, OrderedFloat<f64>, OrderedFloat<f64>), expected: [f64; 3]| {
+            trust_approx_eq(a, expected[0].into(), epsilon)
+                && trust_approx_eq(b, expected[1].into(), epsilon)
+                && trust_approx_eq(c, expected[2].into(), epsilon)
+        };
+
+        assert!(matches(first_update(&mut precise), [9.0, 4.0, 5.0]));
+        assert!(matches(first_update(&mut light), [9.0, 4.0, 5.0]));
+
+        let mut plain: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        assert!(matches(first_update(&mut plain), [4.0, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn entropy_should_measure_trust_concentration() {
+        let mut uniform: PreciseHonestPeer<usize, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        (0..8).for_each(|i| uniform.init_local(&i, 3f64.into()));
+        assert!((uniform.local_trust_entropy() - 8f64.ln()).abs() < 1e-9);
+        assert!((uniform.effective_peer_count() - 8.0).abs() < 1e-9);
+
+        let mut concentrated: PreciseHonestPeer<usize, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        concentrated.init_local(&0, 97f64.into());
+        (1..4).for_each(|i| concentrated.init_local(&i, 1f64.into()));
+        let expected = -(0.97f64 * 0.97f64.ln() + 3.0 * 0.01 * 0.01f64.ln());
+        assert!((concentrated.local_trust_entropy() - expected).abs() < 1e-9);
+        assert!(concentrated.effective_peer_count() < 1.2);
+
+        let mut single: PreciseHonestPeer<usize, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        assert_eq!(single.effective_peer_count(), 0.0);
+        single.init_local(&0, 1f64.into());
+        assert_eq!(single.local_trust_entropy(), 0.0);
+        assert_eq!(single.effective_peer_count(), 1.0);
+
+        let mut light = LightHonestPeer::<usize, OrderedFloat<f64>>::new_from_bounds(
+            1e-4, 0.01, 1.0, OrderedFloat::from(0.0), OrderedFloat::max_value()
+        );
+        (0..4).for_each(|i| light.init_local(&i, 1f64.into()));
+        assert!((light.effective_peer_count() - 4.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn revoking_a_sender_should_leave_only_remaining_contributions() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new().with_attribution(true)
+        };
+        hp.init_local(&"honest", 1f64.into());
+        hp.init_local(&"malicious", 1f64.into());
+
+        hp.update_global(&"honest", &"node1", 4f64.into(), Update::Increment);
+        hp.update_global(&"honest", &"node2", 2f64.into(), Update::Increment);
+        hp.update_global(&"malicious", &"node1", 10f64.into(), Update::Decrement);
+        hp.update_global(&"malicious", &"node2", 20f64.into(), Update::Increment);
+        hp.update_global(&"malicious", &"node3", 6f64.into(), Update::Increment);
+        hp.update_global_multi(
+            &[("honest", 2f64.into()), ("malicious", 2f64.into())], 
+            &"node2", 
+            Update::Increment
+        );
+
+        hp.revoke_sender(&"malicious");
+
+        let epsilon = OrderedFloat::from(1e-9);
+        assert!(trust_approx_eq(hp.get_raw_global(&"node1").unwrap(), 2f64.into(), epsilon));
+        assert!(trust_approx_eq(hp.get_raw_global(&"node2").unwrap(), 2f64.into(), epsilon));
+        assert!(trust_approx_eq(hp.get_raw_global(&"node3").unwrap(), 0f64.into(), epsilon));
+        assert!(trust_approx_eq(hp.get_normalized_global(&"node1").unwrap(), 0.5.into(), epsilon));
+
+        // revoking twice, or a sender without reports, changes nothing
+        let before = hp.get_raw_global_map();
+        hp.revoke_sender(&"malicious");
+        hp.revoke_sender(&"honest2");
+        assert_eq!(hp.get_raw_global_map(), before);
+
+        // replacing global trust wholesale leaves nothing to revoke
+        hp.update_global(&"malicious", &"node1", 5f64.into(), Update::Increment);
+        hp.set_local_vector("honest", HashMap::from([("node1", 1f64.into())]));
+        hp.propagate_trust(1, 0.5.into());
+        assert!(hp.sender_influence().is_empty());
+        let before = hp.get_raw_global_map();
+        hp.revoke_sender(&"malicious");
+        assert_eq!(hp.get_raw_global_map(), before);
+    }
+
+    #[test]
+    fn every_error_should_convert_into_the_matching_decentrust_variant() {
+        fn classify(err: DecentrustError) -> &'static str {
+            match err {
+                DecentrustError::DimensionMismatch { .. } => "dimension",
+                DecentrustError::SeedMismatch => "seed",
+                DecentrustError::Shape(_) => "shape",
+                DecentrustError::InvalidParameter(_) => "parameter",
+                DecentrustError::Underflow { .. } => "underflow",
+                DecentrustError::Decode(_) => "decode",
+                DecentrustError::Uninitialized { .. } => "uninitialized",
+                DecentrustError::InvalidDistribution { .. } => "distribution",
+            }
+        }
+
+        let small: CountMinSketch<u32> = CountMinSketch::new(8, 2, 0, u32::MAX);
+        let mut large: CountMinSketch<u32> = CountMinSketch::new(16, 2, 0, u32::MAX);
+        let err = large.merge(&small).unwrap_err();
+        assert_eq!(classify(err.into()), "dimension");
+
+        let mut other: CountMinSketch<u32> = CountMinSketch::new(16, 2, 0, u32::MAX);
+        let err = other.merge(&large).unwrap_err();
+        assert_eq!(classify(err.into()), "seed");
+
+        let err = CountMinSketch::<u32>::from_matrix(vec![], 0, 1, 0).unwrap_err();
+        assert_eq!(classify(err.into()), "shape");
+
+        let err = CountMinSketch::<u32>::try_new_from_bounds(
+            50.0, 0.0, 100.0, 0, u32::MAX
+        ).unwrap_err();
+        assert_eq!(classify(err.into()), "parameter");
+
+        let err = large.try_decrement(&"node_1", 1).unwrap_err();
+        let err: DecentrustError = err.into();
+        assert_eq!(
+            err,
+            DecentrustError::Underflow { 
+                attempted: "1".to_string(), 
+                available: "0".to_string() 
+            }
+        );
+        assert_eq!(classify(err), "underflow");
+
+        let err = CountMinSketch::<u32>::from_bytes(&[]).unwrap_err();
+        assert_eq!(classify(err.into()), "decode");
+
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new()
+            .with_strict(true);
+        let err = hp.try_update_local(&"node_1", 1.0.into(), Update::Increment).unwrap_err();
+        let err: DecentrustError = err.into();
+        assert_eq!(err.to_string(), "peer \"node_1\" must be initialized before it is updated");
+        assert_eq!(classify(err), "uninitialized");
+    }
+
+    #[test]
+    fn warm_start_should_restore_the_provided_distribution() {
+        let dist: Vec<(&str, OrderedFloat<f64>)> = vec![
+            ("node_1", 0.5.into()),
+            ("node_2", 0.3.into()),
+            ("node_3", 0.2.into()),
+        ];
+
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.init_local(&"stale", 1.0.into());
+        hp.init_from_normalized_local(&dist, 40.0.into()).unwrap();
+
+        assert_eq!(hp.local_raw_len(), 3);
+        assert_eq!(hp.get_raw_local(&"stale"), None);
+        for (key, normalized) in dist.iter() {
+            assert_eq!(hp.get_normalized_local(key), Some(*normalized));
+            assert!(trust_approx_eq(
+                hp.get_raw_local(key).unwrap(), 
+                *normalized * OrderedFloat::from(40.0), 
+                1e-9.into()
+            ));
+        }
+
+        hp.update_local(&"node_1", 20.0.into(), Update::Increment);
+        assert!(trust_approx_eq(
+            hp.get_normalized_local(&"node_1").unwrap(), 
+            OrderedFloat::from(40.0 / 60.0), 
+            1e-9.into()
+        ));
+
+        let before = hp.get_raw_local_map();
+        let err = hp.init_from_normalized_local(
+            &[("node_4", 0.5.into()), ("node_5", 0.6.into())], 
+            10.0.into()
+        ).unwrap_err();
+        assert!(trust_approx_eq(err.total, OrderedFloat::from(1.1), 1e-9.into()));
+        assert_eq!(hp.get_raw_local_map(), before);
+
+        let err: DecentrustError = DistributionError { total: 2u32 }.into();
+        assert_eq!(err, DecentrustError::InvalidDistribution { total: "2".to_string() });
+    }
+
+    #[test]
+    fn injected_nan_should_be_reported_as_invalid() {
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        precise.init_local(&"node_1", 1.0.into());
+        precise.init_global(&"node_1", &"node_2", 1.0.into());
+        assert!(!precise.has_invalid_values());
+
+        precise.reset_global(&"node_2", f64::NAN.into());
+        assert!(!precise.has_invalid_values());
+
+        precise.init_from_normalized_local(&[("node_1", 1.0.into())], f64::INFINITY.into()).unwrap();
+        assert!(precise.has_invalid_values());
+
+        let mut light: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new_from_bounds(
+            50.0, 0.001, 1000.0, 0.0.into(), f64::MAX.into()
+        );
+        light.init_local(&"node_1", 1.0.into());
+        assert!(!light.has_invalid_values());
+
+        light.update_local(&"node_2", f64::NAN.into(), Update::Increment);
+        assert!(light.has_invalid_values());
+
+        let precise: PreciseHonestPeer<&str, u64> = PreciseHonestPeer::new();
+        assert!(!precise.has_invalid_values());
+    }
+
+    #[test]
+    fn folded_sketch_estimates_should_bound_true_counts() {
+        let mut cms: CountMinSketch<u64> = CountMinSketch::new(32, 4, 0, u64::MAX);
+        let counts: Vec<(String, u64)> = (0..40)
+            .map(|i| (format!("node_{}", i), i as u64 + 1))
+            .collect();
+        counts.iter().for_each(|(key, count)| cms.increment(key, *count));
+
+        let folded = cms.fold_width().unwrap();
+        assert_eq!((folded.width(), folded.depth()), (16, 4));
+        assert_eq!(folded.seed(), cms.seed());
+
+        for (key, count) in counts.iter() {
+            let original = cms.estimate(key);
+            let compacted = folded.estimate(key);
+            assert!(original >= *count);
+            assert!(compacted >= original);
+        }
+
+        let total: u64 = cms.flatten().iter().sum();
+        assert_eq!(folded.flatten().iter().sum::<u64>(), total);
+        assert_eq!(folded.fold_width().unwrap().width(), 8);
+
+        let odd: CountMinSketch<u64> = CountMinSketch::new(5, 4, 0, u64::MAX);
+        assert_eq!(odd.fold_width().unwrap_err(), ShapeError::OddWidth(5));
+    }
+
+    #[test]
+    fn row_estimates_should_have_one_cell_per_row_with_estimate_as_min() {
+        let mut cms: CountMinSketch<u64> = CountMinSketch::new(8, 5, 0, u64::MAX);
+        (0..30).for_each(|i| cms.increment(&format!("node_{}", i), i + 1));
+
+        for i in 0..30 {
+            let key = format!("node_{}", i);
+            let rows = cms.row_estimates(&key);
+
+            assert_eq!(rows.len(), cms.depth());
+            assert_eq!(rows.iter().min().copied(), Some(cms.estimate(&key)));
+            assert_eq!(
+                rows.iter().max().unwrap() - rows.iter().min().unwrap(), 
+                cms.cell_spread(&key)
+            );
+        }
+    }
+
+    #[test]
+    fn trait_bucketize_local_keys_should_work_generically_over_both_backends() {
+        fn bucketize<H>(hp: &H, keys: &[&'static str]) -> Vec<(&'static str, usize)> 
+        where 
+            H: HonestPeer<Key = &'static str, Value = OrderedFloat<f64>>
+        {
+            let bucketizer = RangeBucketizer::new(vec![
+                (OrderedFloat::from(0.0), OrderedFloat::from(5.0)),
+                (OrderedFloat::from(5.0), OrderedFloat::from(15.0)),
+                (OrderedFloat::from(15.0), OrderedFloat::<f64>::max_value()),
+            ]);
+            HonestPeer::bucketize_local_keys(hp, keys.iter().copied(), bucketizer).collect()
+        }
+
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new_from_bounds(
+            50.0, 0.0001, 3000.0, 0.0.into(), f64::MAX.into()
+        );
+        for (key, value) in [("node_1", 2.0), ("node_2", 7.0), ("node_3", 20.0)] {
+            precise.init_local(&key, value.into());
+            light.init_local(&key, value.into());
+        }
+
+        let keys = ["node_3", "node_1", "node_2"];
+        let expected = vec![("node_3", 2), ("node_1", 0), ("node_2", 1)];
+        assert_eq!(bucketize(&precise, &keys), expected);
+        assert_eq!(bucketize(&light, &keys), expected);
+        assert_eq!(precise.max_bucket_seen(), Some(2));
+
+        assert_eq!(bucketize(&precise, &["unknown"]), vec![]);
+        assert_eq!(bucketize(&light, &["unknown"]), vec![("unknown", 0)]);
+    }
+
+    #[test]
+    fn normalization_target_should_scale_normalized_values_to_sum_to_it() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.init_local(&"node_1", 1.0.into());
+        hp.init_local(&"node_2", 3.0.into());
+        hp.init_local(&"node_3", 6.0.into());
+        hp.set_normalization_target(100.0.into());
+
+        let total = hp.local_keys().iter()
+            .map(|key| hp.get_normalized_local(key).unwrap())
+            .fold(OrderedFloat::from(0.0), |acc, v| acc + v);
+        assert!(trust_approx_eq(total, OrderedFloat::from(100.0), 1e-9.into()));
+        assert_eq!(hp.get_normalized_local(&"node_3"), Some(OrderedFloat::from(60.0)));
+
+        hp.update_local(&"node_1", 10.0.into(), Update::Increment);
+        let total = hp.get_normalized_local_map().values()
+            .fold(OrderedFloat::from(0.0), |acc, v| acc + *v);
+        assert!(trust_approx_eq(total, OrderedFloat::from(100.0), 1e-9.into()));
+
+        hp.set_lazy_normalization(true);
+        hp.update_local(&"node_2", 5.0.into(), Update::Increment);
+        assert!(trust_approx_eq(
+            hp.get_normalized_local(&"node_2").unwrap(), 
+            OrderedFloat::from(100.0 * 8.0 / 25.0), 
+            1e-9.into()
+        ));
+
+        let mut light: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new_from_bounds(
+            50.0, 0.001, 1000.0, 0.0.into(), f64::MAX.into()
+        );
+        light.set_normalization_target(100.0.into());
+        light.init_local(&"node_1", 1.0.into());
+        light.init_local(&"node_2", 3.0.into());
+        assert!(light.get_normalized_local(&"node_1").unwrap() >= OrderedFloat::from(25.0));
+        assert!(light.get_normalized_local(&"node_2").unwrap() >= OrderedFloat::from(75.0));
+
+        assert!(hp.validate_normalized_local(1e-9.into()));
+        assert!(light.validate_normalized_local(1e-9.into()));
+        hp.init_local(&"node_4", (-1.0).into());
+        assert!(!hp.validate_normalized_local(1e-9.into()));
+    }
+
+    #[test]
+    fn integer_normalization_target_should_not_overflow_large_values() {
+        let mut precise: PreciseHonestPeer<&str, u64> = PreciseHonestPeer::new();
+        let mut light = LightHonestPeer::<&str, u64>::builder()
+            .normalization_target(10_000)
+            .build()
+            .unwrap();
+        precise.set_normalization_target(10_000);
+
+        // every value times the target overflows, their total does not
+        precise.init_local(&"node_1", 1 << 61);
+        precise.init_local(&"node_2", 3 << 61);
+        light.init_local(&"node_1", 1 << 61);
+        light.init_local(&"node_2", 3 << 61);
+
+        assert_eq!(precise.get_normalized_local(&"node_1"), Some(2_500));
+        assert_eq!(precise.get_normalized_local(&"node_2"), Some(7_500));
+        assert_eq!(light.get_normalized_local(&"node_1"), Some(2_500));
+        assert_eq!(light.get_normalized_local(&"node_2"), Some(7_500));
+        assert!(precise.validate_normalized_local(0));
+        assert!(light.validate_normalized_local(0));
+    }
+
+    #[test]
+    fn decrementing_every_peer_to_zero_should_normalize_to_a_uniform_share() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.init_local(&"node_1", 2.0.into());
+        hp.init_local(&"node_2", 4.0.into());
+        hp.init_local(&"node_3", 6.0.into());
+
+        for key in ["node_1", "node_2", "node_3"] {
+            hp.update_local(&key, 10.0.into(), Update::Decrement);
+        }
+
+        assert_eq!(hp.get_raw_local(&"node_2"), Some(OrderedFloat::from(0.0)));
+        assert!(!hp.has_invalid_values());
+        assert!(hp.validate_normalized_local(1e-9.into()));
+        for key in ["node_1", "node_2", "node_3"] {
+            assert!(trust_approx_eq(
+                hp.get_normalized_local(&key).unwrap(), 
+                OrderedFloat::from(1.0 / 3.0), 
+                1e-9.into()
+            ));
+        }
+
+        hp.set_lazy_normalization(true);
+        hp.init_local(&"node_4", 0.0.into());
+        assert_eq!(hp.get_normalized_local(&"node_4"), Some(OrderedFloat::from(0.25)));
+
+        hp.set_lazy_normalization(false);
+        hp.update_local(&"node_1", 1.0.into(), Update::Increment);
+        assert_eq!(hp.get_normalized_local(&"node_1"), Some(OrderedFloat::from(1.0)));
+        assert_eq!(hp.get_normalized_local(&"node_2"), Some(OrderedFloat::from(0.0)));
+    }
+
+    #[cfg(feature = "snapshot")]
+    #[test]
+    fn snapshot_readers_should_always_observe_a_consistent_version() {
+        let hp: SnapshotHonestPeer<PreciseHonestPeer<&str, OrderedFloat<f64>>> = {
+            SnapshotHonestPeer::new(PreciseHonestPeer::new())
+        };
+        hp.update(|peer| {
+            peer.init_local(&"node_1", 1.0.into());
+            peer.init_local(&"node_2", 1.0.into());
+        });
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                let reader = hp.clone();
+                scope.spawn(move || {
+                    for _ in 0..500 {
+                        let snapshot = reader.snapshot();
+                        assert_eq!(
+                            snapshot.get_raw_local(&"node_1"), 
+                            snapshot.get_raw_local(&"node_2")
+                        );
+                        assert_eq!(
+                            snapshot.get_normalized_local(&"node_1"), 
+                            Some(OrderedFloat::from(0.5))
+                        );
+                    }
+                });
+            }
+
+            let writer = hp.clone();
+            scope.spawn(move || {
+                for _ in 0..100 {
+                    writer.update(|peer| {
+                        peer.update_local(&"node_1", 1.0.into(), Update::Increment);
+                        peer.update_local(&"node_2", 1.0.into(), Update::Increment);
+                    });
+                }
+            });
+        });
+
+        assert_eq!(hp.get_raw_local(&"node_1"), Some(OrderedFloat::from(101.0)));
+        assert_eq!(hp.get_normalized_local(&"node_2"), Some(OrderedFloat::from(0.5)));
+    }
+
+    #[test]
+    fn recommended_dimensions_should_grow_with_tighter_targets() {
+        let recommend = |entries, error, confidence| {
+            CountMinSketch::<u64>::recommended_dimensions(entries, error, confidence).unwrap()
+        };
+        let loose = recommend(10_000, 100.0, 0.9);
+        let tight_error = recommend(10_000, 10.0, 0.9);
+        let tight_confidence = recommend(10_000, 100.0, 0.9999);
+        let more_entries = recommend(100_000, 100.0, 0.9);
+
+        assert!(tight_error.0 > loose.0);
+        assert_eq!(tight_error.1, loose.1);
+        assert!(tight_confidence.1 > loose.1);
+        assert_eq!(tight_confidence.0, loose.0);
+        assert!(more_entries.0 > loose.0);
+
+        let cms = CountMinSketch::<u64>::new_from_bounds(100.0, 0.1, 10_000.0, 0, u64::MAX);
+        assert_eq!((cms.width(), cms.depth()), loose);
+    }
+
+    #[test]
+    fn weighted_median_should_ignore_a_malicious_outlier() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.init_local(&"honest_1", 3.0.into());
+        hp.init_local(&"honest_2", 3.0.into());
+        hp.init_local(&"honest_3", 2.0.into());
+        hp.init_local(&"liar", 2.0.into());
+
+        let reports: Vec<(&str, OrderedFloat<f64>)> = vec![
+            ("honest_1", 10.0.into()),
+            ("honest_2", 12.0.into()),
+            ("honest_3", 11.0.into()),
+            ("liar", 1_000_000.0.into()),
+        ];
+
+        let median = weighted_median_report(&reports, &hp);
+        assert_eq!(median, OrderedFloat::from(11.0));
+
+        let weighted_sum = reports.iter()
+            .fold(OrderedFloat::from(0.0), |acc, (sender, score)| {
+                acc + hp.get_normalized_local(sender).unwrap() * *score
+            });
+        assert!(weighted_sum > OrderedFloat::from(100_000.0));
+
+        let mut trusting_liar = hp.clone();
+        trusting_liar.reset_local(&"liar", 100.0.into());
+        assert_eq!(weighted_median_report(&reports, &trusting_liar), OrderedFloat::from(1_000_000.0));
+
+        let unknown: Vec<(&str, OrderedFloat<f64>)> = vec![("stranger", 5.0.into())];
+        assert_eq!(weighted_median_report(&unknown, &hp), OrderedFloat::from(0.0));
+    }
+
+    #[test]
+    fn replaying_a_recorded_op_log_should_rebuild_the_same_state() {
+        type Op = TrustOp<String, OrderedFloat<f64>>;
+
+        // Calls each method directly and records the op standing for it
+        fn record<H>(hp: &mut H) -> Vec<Op> 
+        where 
+            H: HonestPeer<Key = String, Value = OrderedFloat<f64>>
+        {
+            let node = |i: u32| format!("node_{}", i);
+            let mut log = Vec::new();
+
+            hp.init_local(&node(1), 4.0.into());
+            log.push(Op::InitLocal { key: node(1), value: 4.0.into() });
+            hp.init_local(&node(2), 1.0.into());
+            log.push(Op::InitLocal { key: node(2), value: 1.0.into() });
+            hp.update_local(&node(2), 2.0.into(), Update::Increment);
+            log.push(Op::UpdateLocal { key: node(2), delta: 2.0.into(), update: Update::Increment });
+            hp.init_global(&node(1), &node(3), 5.0.into());
+            log.push(Op::InitGlobal { sender: node(1), key: node(3), value: 5.0.into() });
+            hp.update_global(&node(2), &node(3), 1.0.into(), Update::Decrement);
+            log.push(Op::UpdateGlobal { 
+                sender: node(2), 
+                key: node(3), 
+                delta: 1.0.into(), 
+                update: Update::Decrement 
+            });
+            let reports = vec![(node(1), 2.0.into()), (node(2), 6.0.into())];
+            hp.update_global_multi(&reports, &node(4), Update::Increment);
+            log.push(Op::UpdateGlobalMulti { reports, key: node(4), update: Update::Increment });
+            hp.reset_local(&node(1), 3.0.into());
+            log.push(Op::ResetLocal { key: node(1), value: 3.0.into() });
+            hp.reset_global(&node(4), 7.0.into());
+            log.push(Op::ResetGlobal { key: node(4), value: 7.0.into() });
+
+            log
+        }
+
+        fn assert_same_state<H>(original: &H, replica: &H) 
+        where 
+            H: HonestPeer<Key = String, Value = OrderedFloat<f64>>
+        {
+            for key in (1..=4).map(|i| format!("node_{}", i)) {
+                assert_eq!(replica.get_raw_local(&key), original.get_raw_local(&key));
+                assert_eq!(replica.get_raw_global(&key), original.get_raw_global(&key));
+                assert_eq!(replica.get_normalized_local(&key), original.get_normalized_local(&key));
+                assert_eq!(replica.get_normalized_global(&key), original.get_normalized_global(&key));
+            }
+        }
+
+        let mut original: PreciseHonestPeer<String, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let log = record(&mut original);
+        let mut replica: PreciseHonestPeer<String, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        log.into_iter().for_each(|op| replica.apply_op(op));
+
+        assert_same_state(&original, &replica);
+        assert_eq!(replica.get_raw_local_map(), original.get_raw_local_map());
+        assert_eq!(replica.get_raw_global_map(), original.get_raw_global_map());
+        assert_eq!(replica.get_raw_local(&"node_2".to_string()), Some(OrderedFloat::from(3.0)));
+        assert_eq!(replica.global_raw_len(), 2);
+
+        // the light backend tells resets apart from inits, which add up
+        let seed = 7;
+        let sized = || LightHonestPeer::<String, OrderedFloat<f64>>::builder().seed(seed).build().unwrap();
+        let mut original = sized();
+        let log = record(&mut original);
+        let mut replica = sized();
+        log.into_iter().for_each(|op| replica.apply_op(op));
+
+        assert_same_state(&original, &replica);
+        assert_eq!(replica.get_raw_local(&"node_1".to_string()), Some(OrderedFloat::from(3.0)));
+        assert_eq!(replica.get_raw_global(&"node_4".to_string()), Some(OrderedFloat::from(7.0)));
+    }
+
+    #[test]
+    fn serialized_op_log_should_round_trip() {
+        let ops: Vec<TrustOp<String, u64>> = vec![
+            TrustOp::InitLocal { key: "node_1".to_string(), value: 4 },
+            TrustOp::UpdateGlobal { 
+                sender: "node_1".to_string(), 
+                key: "node_2".to_string(), 
+                delta: 3, 
+                update: Update::Decrement 
+            },
+            TrustOp::UpdateGlobalMulti { 
+                reports: vec![("node_1".to_string(), 2)], 
+                key: "node_3".to_string(), 
+                update: Update::Increment 
+            },
+        ];
+
+        let log = serde_json::to_string(&ops).unwrap();
+        let decoded: Vec<TrustOp<String, u64>> = serde_json::from_str(&log).unwrap();
+        assert_eq!(decoded, ops);
+    }
+
+    #[test]
+    fn interned_keys_should_share_one_allocation_per_distinct_key() {
+        use crate::intern::KeyInterner;
+        use std::sync::Arc;
+
+        let mut interner: KeyInterner<String> = KeyInterner::new();
+        let mut precise: PreciseHonestPeer<Arc<String>, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<Arc<String>, OrderedFloat<f64>> = LightHonestPeer::new();
+
+        let names: Vec<String> = (0..10).map(|i| format!("node_{}", i)).collect();
+        for i in 0..10_000 {
+            let key = interner.intern(&names[i % names.len()]);
+            precise.update_local(&key, 1.0.into(), Update::Increment);
+            light.update_local(&key, 1.0.into(), Update::Increment);
+        }
+
+        assert_eq!(interner.len(), 10);
+        assert_eq!(precise.local_raw_len(), 10);
+        for key in precise.local_keys() {
+            let shared = interner.get(&key).unwrap();
+            assert!(Arc::ptr_eq(&key, &shared));
+            assert_eq!(precise.get_raw_local(&shared), Some(OrderedFloat::from(1000.0)));
+        }
+        assert!(light.local_keys().iter().all(|key| Arc::ptr_eq(key, &interner.get(key).unwrap())));
+
+        let raw = precise.get_raw_local_map();
+        assert_eq!(raw[&"node_3".to_string()], OrderedFloat::from(1000.0));
+
+        drop(raw);
+        precise.remove_local(&interner.get(&names[0]).unwrap());
+        drop(light);
+        interner.purge_unused();
+        assert_eq!(interner.len(), 9);
+        assert!(interner.get(&names[0]).is_none());
+    }
+
+    #[test]
+    fn light_estimate_error_should_stay_non_negative_and_within_bound() {
+        use crate::probabilistic::estimate_error;
+
+        let error_bound = 10.0;
+        let mut precise: PreciseHonestPeer<String, i64> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<String, i64> = LightHonestPeer::new_from_bounds(
+            error_bound, 0.0001, 1000.0, 0, i64::MAX
+        );
+
+        let keys: Vec<String> = (0..200).map(|i| format!("node_{}", i)).collect();
+        for (i, key) in keys.iter().enumerate() {
+            let value = (i % 5) as i64 + 1;
+            precise.update_local(key, value, Update::Increment);
+            light.update_local(key, value, Update::Increment);
+        }
+
+        for key in keys.iter() {
+            let error = estimate_error(&precise, &light, key);
+            assert!(error >= 0);
+            assert!(error as f64 <= error_bound);
+        }
+    }
+
+    #[test]
+    fn normalized_precision_should_limit_fractional_digits() {
+        fn has_at_most_two_decimals(value: OrderedFloat<f64>) -> bool {
+            ((value.0 * 100.0).round() / 100.0 - value.0).abs() < 1e-12
+        }
+
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<&str, OrderedFloat<f64>> = LightHonestPeer::new_from_bounds(
+            50.0, 0.001, 1000.0, 0.0.into(), f64::MAX.into()
+        );
+        for (key, value) in [("node_1", 1.0), ("node_2", 1.0), ("node_3", 1.0), ("node_4", 7.0)] {
+            precise.init_local(&key, value.into());
+            light.init_local(&key, value.into());
+        }
+        precise.set_normalized_precision(2);
+        light.set_normalized_precision(2);
+        assert_eq!(precise.normalized_precision(), Some(2));
+
+        precise.update_local(&"node_4", 2.0.into(), Update::Increment);
+        light.update_local(&"node_4", 2.0.into(), Update::Increment);
+
+        for key in ["node_1", "node_2", "node_3", "node_4"] {
+            assert!(has_at_most_two_decimals(precise.get_normalized_local(&key).unwrap()));
+            assert!(has_at_most_two_decimals(light.get_normalized_local(&key).unwrap()));
+        }
+        assert_eq!(precise.get_normalized_local(&"node_1"), Some(OrderedFloat::from(0.08)));
+        assert_eq!(precise.get_normalized_local(&"node_4"), Some(OrderedFloat::from(0.75)));
+
+        precise.set_lazy_normalization(true);
+        precise.update_local(&"node_1", 1.0.into(), Update::Increment);
+        assert_eq!(precise.get_normalized_local(&"node_1"), Some(OrderedFloat::from(0.15)));
+        assert!(precise.get_normalized_local_map().values().all(|v| has_at_most_two_decimals(*v)));
+
+        // more decimals than an f64 holds leave values unrounded, not NaN
+        precise.set_lazy_normalization(false);
+        precise.set_normalized_precision(400);
+        light.set_normalized_precision(400);
+        assert_eq!(precise.normalized_precision(), Some(400));
+        assert_eq!(precise.get_normalized_local(&"node_1"), Some(OrderedFloat::from(2.0 / 13.0)));
+        assert!(!light.has_invalid_values());
+        assert!(!precise.has_invalid_values());
+    }
+
+    #[test]
+    fn rate_limit_should_drop_reports_beyond_a_senders_rate() {
+        use std::sync::{Arc, Mutex};
+        use std::time::{Duration, Instant};
+        use crate::clock::Clock;
+        use crate::rate::RateLimit;
+
+        #[derive(Clone)]
+        struct MockClock(Arc<Mutex<Instant>>);
+
+        impl Clock for MockClock {
+            fn now(&self) -> Instant {
+                *self.0.lock().unwrap()
+            }
+        }
+
+        let clock = MockClock(Arc::new(Mutex::new(Instant::now())));
+        let advance = |secs: u64| *clock.0.lock().unwrap() += Duration::from_secs(secs);
+
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new()
+                .with_clock(clock.clone())
+                .with_rate_limit(RateLimit::new(2, 1.0))
+        };
+        hp.init_local(&"spammer", 1f64.into());
+        hp.init_local(&"honest", 1f64.into());
+
+        for _ in 0..5 {
+            hp.update_global(&"spammer", &"target", 2f64.into(), Update::Increment);
+        }
+        // Only the first two reports fit in the spammer's burst
+        assert_eq!(hp.get_raw_global(&"target"), Some(OrderedFloat::from(2.0)));
+
+        // Other senders have their own buckets
+        hp.update_global(&"honest", &"target", 2f64.into(), Update::Increment);
+        assert_eq!(hp.get_raw_global(&"target"), Some(OrderedFloat::from(3.0)));
+
+        // A second later the spammer has regained a single token
+        advance(1);
+        let reports = vec![("spammer", OrderedFloat::from(2.0)), ("spammer", 2f64.into())];
+        hp.update_global_multi(&reports, &"target", Update::Increment);
+        assert_eq!(hp.get_raw_global(&"target"), Some(OrderedFloat::from(4.0)));
+
+        // Reports from a sender that is not yet known neither count nor 
+        // take tokens from the bucket it gets once it is
+        for _ in 0..5 {
+            hp.update_global(&"newcomer", &"target", 3f64.into(), Update::Increment);
+            hp.ingest_report(&"newcomer", &HashMap::from([("target", 3f64.into())]));
+        }
+        assert_eq!(hp.get_raw_global(&"target"), Some(OrderedFloat::from(4.0)));
+
+        hp.init_local(&"newcomer", 1f64.into());
+        hp.update_global(&"newcomer", &"target", 3f64.into(), Update::Increment);
+        hp.update_global(&"newcomer", &"target", 3f64.into(), Update::Increment);
+        assert!(trust_approx_eq(
+            hp.get_raw_global(&"target").unwrap(), 
+            OrderedFloat::from(6.0), 
+            1e-9.into()
+        ));
+    }
+
+    #[test]
+    fn key_retention_should_evict_least_recently_updated_keys() {
+        let mut hp: LightHonestPeer<&str, OrderedFloat<f64>> = {
+            LightHonestPeer::new().with_key_retention(2)
+        };
+        hp.init_local(&"node1", 1f64.into());
+        hp.init_local(&"node2", 1f64.into());
+        hp.update_local(&"node1", 1f64.into(), Update::Increment);
+        hp.init_local(&"node3", 1f64.into());
+
+        // node2 was updated least recently
+        let mut keys = hp.local_keys();
+        keys.sort();
+        assert_eq!(keys, vec!["node1", "node3"]);
+
+        // its mass stays in the sketch
+        assert!(hp.get_raw_local(&"node2") >= Some(OrderedFloat::from(1.0)));
+
+        hp.init_global(&"node1", &"node4", 1f64.into());
+        hp.init_global(&"node1", &"node5", 1f64.into());
+        hp.init_global(&"node1", &"node6", 1f64.into());
+        let mut keys = hp.global_keys();
+        keys.sort();
+        assert_eq!(keys, vec!["node5", "node6"]);
+
+        // keys retained before the limit was lowered are evicted right away
+        hp.set_key_retention(1);
+        assert_eq!(hp.local_keys().len(), 1);
+        assert_eq!(hp.global_keys(), vec!["node6"]);
+    }
+
+    #[test]
+    fn light_builder_should_apply_configured_settings() {
+        use crate::error::CmsParamError;
+
+        let builder = LightHonestPeer::<&str, OrderedFloat<f64>>::builder()
+            .error_bound(50.0)
+            .probability(0.0001)
+            .max_entries(3000.0)
+            .bounds(0f64.into(), 1000f64.into())
+            .seed(42)
+            .strict(true)
+            .newcomer_default(2f64.into())
+            .floor(1f64.into());
+        let mut a = builder.clone().build().unwrap();
+        let b = builder.build().unwrap();
+
+        let sized = CountMinSketch::<OrderedFloat<f64>>::new_from_bounds(
+            50.0, 0.0001, 3000.0, 0f64.into(), 1000f64.into()
+        );
+        assert_eq!((a.get_width(), a.get_depth()), (sized.width(), sized.depth()));
+        assert!(a.is_strict());
+        assert_eq!(a.newcomer_default(), OrderedFloat::from(2.0));
+        assert_eq!(a.floor(), OrderedFloat::from(1.0));
+
+        // instances built apart with the same seed can be merged
+        assert!(a.merge(&b).is_ok());
+        let unseeded = LightHonestPeer::<&str, OrderedFloat<f64>>::builder().build().unwrap();
+        assert_eq!((unseeded.get_width(), unseeded.get_depth()), (256, 5));
+        assert!(a.merge(&unseeded).is_err());
+
+        let partial = LightHonestPeer::<&str, OrderedFloat<f64>>::builder()
+            .error_bound(50.0)
+            .max_entries(3000.0)
+            .build();
+        assert!(matches!(partial, Err(CmsParamError::MissingParameter("probability"))));
+    }
+
+    #[test]
+    fn hot_cells_should_point_at_a_dominant_key() {
+        let mut cms = CountMinSketch::<u64>::new(64, 4, 0, u64::MAX);
+        cms.increment(&"heavy", 1000);
+        for i in 0..20 {
+            cms.increment(&format!("node{}", i), 1);
+        }
+
+        let hot = cms.hot_cells();
+        let columns: Vec<usize> = hot.iter().map(|(col, _)| *col).collect();
+
+        assert_eq!(hot.len(), 4);
+        assert_eq!(columns, cms.hash_functions(&"heavy"));
+        assert!(hot.iter().all(|(_, value)| *value >= 1000));
+    }
+
+    #[test]
+    fn exports_of_the_same_state_should_be_identical() {
+        let peers: Vec<(String, OrderedFloat<f64>)> = (0..50)
+            .map(|i| (format!("node{}", i), OrderedFloat::from(i as f64 + 1.0)))
+            .collect();
+
+        let mut a: PreciseHonestPeer<String, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut b: PreciseHonestPeer<String, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        peers.iter().for_each(|(key, value)| a.init_local(key, *value));
+        peers.iter().rev().for_each(|(key, value)| b.init_local(key, *value));
+        peers.iter().for_each(|(key, value)| a.init_global(&peers[0].0, key, *value));
+        peers.iter().rev().for_each(|(key, value)| b.init_global(&peers[0].0, key, *value));
+
+        assert_eq!(a.local_keys_sorted(), b.local_keys_sorted());
+        assert_eq!(a.global_keys_sorted(), b.global_keys_sorted());
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        assert_eq!(a.to_bytes(), a.clone().to_bytes());
+    }
+
+    #[test]
+    fn transfer_should_move_trust_onto_the_new_key() {
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        precise.init_local(&"old", 3f64.into());
+        precise.init_local(&"new", 1f64.into());
+        precise.init_local(&"other", 4f64.into());
+        precise.reset_global(&"old", 6f64.into());
+
+        precise.transfer_local(&"old", &"new");
+        precise.transfer_global(&"old", &"new");
+
+        assert_eq!(precise.get_raw_local(&"new"), Some(OrderedFloat::from(4.0)));
+        assert_eq!(precise.get_normalized_local(&"new"), Some(OrderedFloat::from(0.5)));
+        assert_eq!(precise.get_raw_local(&"old"), None);
+        assert_eq!(precise.get_normalized_local(&"old"), None);
+        assert_eq!(precise.get_raw_global(&"new"), Some(OrderedFloat::from(6.0)));
+        assert_eq!(precise.get_raw_global(&"old"), None);
+
+        let mut light = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+        light.init_local(&"old", 3f64.into());
+        light.init_local(&"new", 1f64.into());
+        light.reset_global(&"old", 6f64.into());
+
+        light.transfer_local(&"old", &"new");
+        light.transfer_global(&"old", &"new");
+
+        assert!(light.get_raw_local(&"new") >= Some(OrderedFloat::from(4.0)));
+        assert!(light.get_raw_global(&"new") >= Some(OrderedFloat::from(6.0)));
+        assert_eq!(light.local_keys(), vec!["new"]);
+        assert_eq!(light.global_keys(), vec!["new"]);
+
+        // an unknown key has nothing to move, whatever its estimate
+        let local_before = light.get_raw_local_map();
+        let global_before = light.get_raw_global_map();
+        light.transfer_local(&"ghost", &"fresh");
+        light.transfer_global(&"ghost", &"fresh");
+        assert_eq!(light.get_raw_local_map(), local_before);
+        assert_eq!(light.get_raw_global_map(), global_before);
+        assert_eq!(light.local_keys(), vec!["new"]);
+        assert_eq!(light.global_keys(), vec!["new"]);
+
+        precise.transfer_local(&"ghost", &"fresh");
+        precise.transfer_global(&"ghost", &"fresh");
+        assert_eq!(precise.get_raw_local(&"fresh"), None);
+        assert_eq!(precise.get_raw_global(&"fresh"), None);
+    }
+
+    #[test]
+    fn sender_influence_should_scale_with_sender_trust() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new().with_attribution(true)
+        };
+        hp.init_local(&"trusted", 3f64.into());
+        hp.init_local(&"untrusted", 1f64.into());
+
+        for key in ["node1", "node2"] {
+            hp.update_global(&"trusted", &key, 4f64.into(), Update::Increment);
+            hp.update_global(&"untrusted", &key, 4f64.into(), Update::Increment);
+        }
+
+        let influence = hp.sender_influence();
+        let epsilon = OrderedFloat::from(1e-9);
+        assert_eq!(influence.len(), 2);
+        assert!(trust_approx_eq(influence[&"trusted"], 6f64.into(), epsilon));
+        assert!(trust_approx_eq(influence[&"untrusted"], 2f64.into(), epsilon));
+
+        hp.set_attribution(false);
+        assert!(hp.sender_influence().is_empty());
+    }
+
+    #[test]
+    fn aggregates_should_not_panic_on_an_empty_precise_instance() {
+        use buckets::bucketizers::fw::FixedWidthBucketizer;
+
+        fn check<V>(mut hp: PreciseHonestPeer<&'static str, V>) 
+        where
+            V: std::ops::AddAssign 
+            + std::ops::DivAssign 
+            + std::ops::SubAssign 
+            + std::ops::Add<Output = V> 
+            + std::ops::Mul<Output = V> 
+            + std::ops::Div<Output = V> 
+            + std::ops::Sub<Output = V> 
+            + Copy 
+            + Default 
+            + Bounded 
+            + num_traits::One 
+            + std::hash::Hash 
+            + Ord 
+            + std::fmt::Debug 
+            + num_traits::ToPrimitive 
+            + num_traits::NumCast
+        {
+            assert_eq!(hp.most_trusted_local(), None);
+            assert_eq!(hp.least_trusted_local(), None);
+            assert_eq!(hp.most_trusted_global(), None);
+            assert_eq!(hp.least_trusted_global(), None);
+            assert!(hp.top_k_local(3).is_empty());
+            assert!(hp.top_k_global(3).is_empty());
+            assert_eq!(hp.local_quantile(0.5), None);
+            assert_eq!(hp.global_quantile(0.5), None);
+            assert_eq!(hp.local_trust_entropy(), 0.0);
+            assert_eq!(hp.effective_peer_count(), 0.0);
+            assert_eq!(hp.trust_similarity(&hp.clone()), 0.0);
+            assert_eq!(hp.get_normalized_local_within(&"node1", &[]), None);
+            assert!(hp.validate_normalized_local(V::default()));
+            assert!(hp.validate_normalized_global(V::default()));
+            assert!(!hp.has_invalid_values());
+            assert!(hp.sender_influence().is_empty());
+            assert!(diff_normalized_local(&hp, &hp.clone()).is_empty());
+            assert_eq!(weighted_median_report(&[], &hp), V::default());
+            assert_eq!(hp.max_bucket_seen(), None);
+
+            hp.normalize_local();
+            hp.normalize_global();
+            hp.rank_normalize_local();
+            hp.rank_normalize_global();
+            hp.decay_by_age(std::time::Duration::from_secs(1));
+            assert!(hp.converge_global_with_history(10, V::default()).len() <= 10);
+            assert!(hp.get_normalized_local_map().is_empty());
+            assert!(hp.get_normalized_global_map().is_empty());
+        }
+
+        check(PreciseHonestPeer::<&str, OrderedFloat<f64>>::new());
+        check(PreciseHonestPeer::<&str, u64>::new());
+
+        let hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let bucketizer = FixedWidthBucketizer::new(1f64.into(), 0f64.into());
+        assert!(hp.local_histogram(bucketizer).is_empty());
+    }
+
+    #[test]
+    fn scaled_normalization_should_give_integer_sketches_meaningful_ratios() {
+        let mut cms = CountMinSketch::<u32>::new(1000, 4, 0, u32::MAX);
+        cms.increment(&"node1", 1);
+        cms.increment(&"node2", 3);
+
+        // unscaled, every integer ratio below 1 truncates to 0
+        let truncated = CountMinSketch::from_matrix(
+            cms.normalize_estimates(), 0, u32::MAX, cms.seed()
+        ).unwrap();
+        assert_eq!(truncated.estimate(&"node1"), 0);
+
+        let scaled = CountMinSketch::from_matrix(
+            cms.normalize_estimates_scaled(10_000), 0, u32::MAX, cms.seed()
+        ).unwrap();
+        assert_eq!(scaled.estimate(&"node1"), 2_500);
+        assert_eq!(scaled.estimate(&"node2"), 7_500);
+
+        // empty rows are left at zero instead of dividing by zero
+        let empty = CountMinSketch::<u32>::new(100, 4, 0, u32::MAX);
+        assert!(empty.normalize_estimates_scaled(10_000).iter().flatten().all(|v| *v == 0));
+
+        let mut hp = LightHonestPeer::<&str, u32>::builder()
+            .normalization_target(10_000)
+            .build()
+            .unwrap();
+        hp.init_local(&"node1", 1);
+        hp.init_local(&"node2", 3);
+        assert_eq!(hp.get_normalized_local(&"node1"), Some(2_500));
+        assert_eq!(hp.get_normalized_global(&"node1"), Some(0));
+    }
+
+    #[test]
+    fn key_set_diff_should_report_keys_known_to_one_backend() {
+        use crate::probabilistic::key_set_diff;
+
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+        for key in ["node1", "node2"] {
+            precise.init_local(&key, 1f64.into());
+            light.init_local(&key, 1f64.into());
+        }
+        precise.init_local(&"precise_only", 1f64.into());
+        light.init_local(&"light_only", 1f64.into());
+
+        assert_eq!(key_set_diff(&precise, &light), (vec!["precise_only"], vec!["light_only"]));
+
+        precise.remove_local(&"precise_only");
+        precise.init_local(&"light_only", 1f64.into());
+        assert_eq!(key_set_diff(&precise, &light), (vec![], vec![]));
+
+        // the light instance evicts its least recently updated key
+        light.set_key_retention(3);
+        light.init_local(&"node3", 1f64.into());
+        precise.init_local(&"node3", 1f64.into());
+        let (precise_only, light_only) = key_set_diff(&precise, &light);
+        assert_eq!(precise_only.len(), 1);
+        assert!(light_only.is_empty());
+    }
+
+    #[test]
+    fn tiers_should_follow_thresholds_including_boundaries() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.init_local(&"node1", 1f64.into());
+        hp.init_local(&"node2", 2f64.into());
+        hp.init_local(&"node3", 3f64.into());
+        hp.init_local(&"node4", 4f64.into());
+
+        // normalized values are 0.1, 0.2, 0.3 and 0.4
+        let thresholds = [OrderedFloat::from(0.2), OrderedFloat::from(0.4)];
+        assert_eq!(hp.local_tier(&"node1", &thresholds), Some(TrustTier::Low));
+        assert_eq!(hp.local_tier(&"node2", &thresholds), Some(TrustTier::Medium));
+        assert_eq!(hp.local_tier(&"node3", &thresholds), Some(TrustTier::Medium));
+        assert_eq!(hp.local_tier(&"node4", &thresholds), Some(TrustTier::High));
+        assert_eq!(hp.local_tier(&"node5", &thresholds), None);
+        assert_eq!(hp.global_tier(&"node1", &thresholds), None);
+
+        assert_eq!(TrustTier::from_thresholds(5, &[5]), TrustTier::High);
+        assert_eq!(TrustTier::from_thresholds(4, &[5]), TrustTier::Low);
+        assert_eq!(TrustTier::from_thresholds(4, &[]), TrustTier::Low);
+        assert_eq!(TrustTier::from_thresholds(7, &[1, 5, 9]), TrustTier::Medium);
+        assert!(TrustTier::Low < TrustTier::Medium && TrustTier::Medium < TrustTier::High);
+    }
+
+    #[test]
+    fn normalize_estimates_should_leave_empty_rows_at_default() {
+        let matrix = vec![
+            vec![OrderedFloat::from(1.0), OrderedFloat::from(3.0)],
+            vec![OrderedFloat::from(0.0), OrderedFloat::from(0.0)],
+        ];
+        let cms = CountMinSketch::from_matrix(matrix, f64::MIN.into(), f64::MAX.into(), 0).unwrap();
+        let normalized = CountMinSketch::from_matrix(
+            cms.normalize_estimates(), f64::MIN.into(), f64::MAX.into(), 0
+        ).unwrap();
+
+        assert!(!normalized.has_invalid_values());
+        assert_eq!(normalized.matrix()[0], vec![OrderedFloat::from(0.25), OrderedFloat::from(0.75)]);
+        assert_eq!(normalized.matrix()[1], vec![OrderedFloat::from(0.0); 2]);
+
+        // a fresh light instance normalizes empty sketches on every update
+        let mut hp = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+        hp.init_local(&"node1", 1f64.into());
+        assert!(!hp.has_invalid_values());
+        assert_eq!(hp.get_normalized_global(&"node1"), Some(OrderedFloat::from(0.0)));
+
+        let mut ints = LightHonestPeer::<&str, u64>::new();
+        ints.init_local(&"node1", 1);
+        assert_eq!(ints.get_normalized_global(&"node1"), Some(0));
+    }
+
+    #[test]
+    fn collision_count_should_rise_as_keys_are_added() {
+        // a single cell, so every pair of keys collides
+        let mut hp: LightHonestPeer<String, OrderedFloat<f64>> = LightHonestPeer::new_from_bounds(
+            1000.0, 0.9, 1.0, f64::MIN.into(), f64::MAX.into()
+        );
+        assert_eq!((hp.get_width(), hp.get_depth()), (1, 1));
+
+        let mut counts = vec![];
+        for i in 0..4 {
+            hp.init_local(&format!("node{}", i), 1f64.into());
+            counts.push(hp.estimated_collision_count(&hp.local_keys()));
+        }
+        assert_eq!(counts, vec![0, 1, 3, 6]);
+
+        let wide = LightHonestPeer::<String, OrderedFloat<f64>>::new();
+        let keys: Vec<String> = (0..50).map(|i| format!("node{}", i)).collect();
+        let fewer = wide.estimated_collision_count(&keys[..25]);
+        let more = wide.estimated_collision_count(&keys);
+        assert!(fewer <= more);
+        assert!(more < 50 * 49 / 2);
+    }
+
+    #[test]
+    fn penalize_all_local_should_spare_whitelisted_peers() {
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new().with_floor(1f64.into())
+        };
+        let mut light = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+        light.set_floor(1f64.into());
+        for (key, value) in [("node1", 10.0), ("node2", 2.0), ("trusted", 10.0)] {
+            precise.init_local(&key, value.into());
+            light.init_local(&key, value.into());
+        }
+
+        precise.penalize_all_local(4f64.into(), &["trusted"]);
+        light.penalize_all_local(4f64.into(), &["trusted"]);
+
+        for raw in [precise.get_raw_local(&"node1"), light.get_raw_local(&"node1")] {
+            assert_eq!(raw, Some(OrderedFloat::from(6.0)));
+        }
+        for raw in [precise.get_raw_local(&"node2"), light.get_raw_local(&"node2")] {
+            assert_eq!(raw, Some(OrderedFloat::from(1.0)));
+        }
+        for raw in [precise.get_raw_local(&"trusted"), light.get_raw_local(&"trusted")] {
+            assert_eq!(raw, Some(OrderedFloat::from(10.0)));
+        }
+        assert_eq!(precise.get_normalized_local(&"trusted"), Some(OrderedFloat::from(10.0 / 17.0)));
+        assert!(trust_approx_eq(
+            light.get_normalized_local(&"trusted").unwrap(), 
+            OrderedFloat::from(10.0 / 17.0), 
+            OrderedFloat::from(1e-9)
+        ));
+    }
+
+    #[test]
+    fn propagate_trust_should_stop_at_max_hops() {
+        let chain = |max_hops: usize| {
+            let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+            hp.init_local(&"a", 1f64.into());
+            hp.set_local_vector("a", HashMap::from([("b", 1f64.into())]));
+            hp.set_local_vector("b", HashMap::from([("c", 1f64.into())]));
+            hp.propagate_trust(max_hops, 0.5.into());
+            hp
+        };
+
+        let one_hop = chain(1);
+        assert_eq!(one_hop.get_raw_global(&"a"), Some(OrderedFloat::from(1.0)));
+        assert_eq!(one_hop.get_raw_global(&"b"), Some(OrderedFloat::from(0.5)));
+        assert_eq!(one_hop.get_raw_global(&"c"), None);
+
+        let two_hops = chain(2);
+        assert_eq!(two_hops.get_raw_global(&"c"), Some(OrderedFloat::from(0.25)));
+        assert_eq!(two_hops.get_normalized_global(&"c"), Some(OrderedFloat::from(0.25 / 1.75)));
+
+        assert_eq!(chain(0).global_keys(), vec!["a"]);
+    }
+
+    #[test]
+    fn summary_should_match_individual_metrics() {
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+        for (key, value) in [("node1", 5.0), ("node2", 2.0), ("node3", 1.0)] {
+            precise.init_local(&key, value.into());
+            light.init_local(&key, value.into());
+        }
+
+        let summary = precise.summary();
+        assert_eq!(summary.peer_count, precise.local_keys().len());
+        assert_eq!(summary.total_trust, OrderedFloat::from(8.0));
+        assert_eq!(summary.top_peer, precise.most_trusted_local());
+        assert_eq!(summary.entropy, precise.local_trust_entropy());
+        assert_eq!(summary.load_factor, None);
+        assert_eq!(summary.estimated_collisions, None);
+        assert!(!summary.to_string().contains("load factor"));
+
+        let summary = light.summary();
+        assert_eq!(summary.peer_count, 3);
+        assert_eq!(summary.top_peer, light.most_trusted_local());
+        assert_eq!(summary.top_peer.as_ref().map(|(key, _)| *key), Some("node1"));
+        assert_eq!(summary.entropy, light.local_trust_entropy());
+        assert_eq!(summary.load_factor, Some(light.load_factor()));
+        assert_eq!(
+            summary.estimated_collisions, 
+            Some(light.estimated_collision_count(&light.local_keys()))
+        );
+        assert!(summary.to_string().contains("estimated collisions: "));
+
+        let empty: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        assert_eq!(
+            empty.summary().to_string(), 
+            "peers: 0, total trust: 0, top peer: none, entropy: 0.0000"
+        );
+
+        // rank normalization never sums the raw values
+        let mut saturated: PreciseHonestPeer<&str, u64> = PreciseHonestPeer::new();
+        saturated.set_normalization_mode(NormalizationMode::Rank);
+        saturated.init_local(&"node1", u64::MAX - 1);
+        saturated.init_local(&"node2", 10);
+        assert_eq!(saturated.summary().total_trust, u64::MAX);
+    }
+
+    #[test]
+    fn light_init_global_replay_should_be_idempotent() {
+        let mut hp = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+        hp.init_local(&"sender", 1f64.into());
+
+        hp.init_global(&"sender", &"node1", 4f64.into());
+        hp.init_global(&"sender", &"node1", 4f64.into());
+        assert_eq!(hp.get_raw_global(&"node1"), Some(OrderedFloat::from(4.0)));
+
+        hp.init_global_batch(&"sender", [("node1", 4f64.into()), ("node2", 2f64.into())]);
+        assert_eq!(hp.get_raw_global(&"node1"), Some(OrderedFloat::from(4.0)));
+        assert_eq!(hp.get_raw_global(&"node2"), Some(OrderedFloat::from(2.0)));
+
+        hp.update_global(&"sender", &"node1", 1f64.into(), Update::Increment);
+        assert_eq!(hp.get_raw_global(&"node1"), Some(OrderedFloat::from(5.0)));
+
+        fn init_from_two_senders<H>(hp: &mut H) -> Vec<Option<OrderedFloat<f64>>> 
+        where 
+            H: HonestPeer<Key = &'static str, Value = OrderedFloat<f64>>
+        {
+            hp.init_local(&"sender", 3f64.into());
+            hp.init_local(&"other", 1f64.into());
+            hp.init_local(&"idle", 0f64.into());
+
+            hp.init_global(&"stranger", &"node1", 8f64.into());
+            assert!(!hp.global_keys().contains(&"node1"));
+
+            // a zero weight init must not block a later trusted one
+            hp.init_global(&"idle", &"node1", 8f64.into());
+            hp.init_global(&"sender", &"node1", 8f64.into());
+            let first = hp.get_raw_global(&"node1");
+            hp.init_global(&"other", &"node1", 8f64.into());
+            let second = hp.get_raw_global(&"node1");
+            hp.init_global(&"other", &"node1", 8f64.into());
+            let replayed = hp.get_raw_global(&"node1");
+
+            vec![first, second, replayed]
+        }
+
+        let mut light = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+        let mut precise = PreciseHonestPeer::<&str, OrderedFloat<f64>>::new();
+        let expected = vec![Some(6f64.into()), Some(2f64.into()), Some(2f64.into())];
+        assert_eq!(init_from_two_senders(&mut light), expected);
+        assert_eq!(init_from_two_senders(&mut precise), expected);
+    }
+
+    #[test]
+    fn precise_bounds_should_clamp_updates() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new_with_bounds((-5f64).into(), 10f64.into()).with_floor((-100f64).into())
+        };
+        assert_eq!(hp.value_bounds(), ((-5f64).into(), 10f64.into()));
+
+        hp.init_local(&"node1", 8f64.into());
+        hp.init_local(&"node2", 3f64.into());
+        hp.update_local(&"node1", 5f64.into(), Update::Increment);
+        hp.update_local(&"node2", 20f64.into(), Update::Decrement);
+        assert_eq!(hp.get_raw_local(&"node1"), Some(OrderedFloat::from(10.0)));
+        assert_eq!(hp.get_raw_local(&"node2"), Some(OrderedFloat::from(-5.0)));
+
+        hp.update_local(&"node3", 50f64.into(), Update::Increment);
+        assert_eq!(hp.get_raw_local(&"node3"), Some(OrderedFloat::from(10.0)));
+
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+            PreciseHonestPeer::new_with_bounds(0f64.into(), 10f64.into()).with_attribution(true)
+        };
+        hp.init_local(&"sender", 1f64.into());
+        hp.update_global(&"sender", &"node1", 8f64.into(), Update::Increment);
+        hp.update_global(&"sender", &"node1", 8f64.into(), Update::Increment);
+        assert_eq!(hp.get_raw_global(&"node1"), Some(OrderedFloat::from(10.0)));
+        assert_eq!(hp.sender_influence()[&"sender"], OrderedFloat::from(10.0));
+
+        // every other write is clamped too
+        hp.init_local(&"node2", 50f64.into());
+        hp.reset_local(&"node3", (-3f64).into());
+        hp.reset_global(&"node4", 12f64.into());
+        hp.init_global(&"sender", &"node5", 40f64.into());
+        assert_eq!(hp.get_raw_local(&"node2"), Some(OrderedFloat::from(10.0)));
+        assert_eq!(hp.get_raw_local(&"node3"), Some(OrderedFloat::from(0.0)));
+        assert_eq!(hp.get_raw_global(&"node4"), Some(OrderedFloat::from(10.0)));
+        assert!(hp.get_raw_global(&"node5") <= Some(OrderedFloat::from(10.0)));
+
+        hp.transfer_global(&"node4", &"node1");
+        assert_eq!(hp.get_raw_global(&"node1"), Some(OrderedFloat::from(10.0)));
+
+        let unbounded: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        assert_eq!(unbounded.value_bounds(), (OrderedFloat::min_value(), OrderedFloat::max_value()));
+    }
+
+    #[test]
+    #[should_panic(expected = "min must not be greater than max")]
+    fn precise_bounds_should_reject_min_above_max() {
+        let _: PreciseHonestPeer<&str, u64> = PreciseHonestPeer::new_with_bounds(10, 5);
+    }
+
+    #[test]
+    fn ingest_report_should_weight_by_reporter_trust() {
+        let low_report = HashMap::from([("node1", OrderedFloat::from(10.0))]);
+        let high_report = HashMap::from([("node2", OrderedFloat::from(10.0))]);
+
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        precise.init_local(&"low", 1f64.into());
+        precise.init_local(&"high", 9f64.into());
+        precise.ingest_report(&"low", &low_report);
+        precise.ingest_report(&"high", &high_report);
+
+        assert_eq!(precise.get_raw_global(&"node1"), Some(OrderedFloat::from(1.0)));
+        assert_eq!(precise.get_raw_global(&"node2"), Some(OrderedFloat::from(9.0)));
+        assert!(precise.get_normalized_global(&"node2") > precise.get_normalized_global(&"node1"));
+
+        let mut light = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+        light.init_local(&"low", 1f64.into());
+        light.init_local(&"high", 9f64.into());
+        light.ingest_report(&"low", &low_report);
+        light.ingest_report(&"high", &high_report);
+
+        assert!(light.get_raw_global(&"node2") > light.get_raw_global(&"node1"));
+        let mut keys = light.global_keys();
+        keys.sort();
+        assert_eq!(keys, vec!["node1", "node2"]);
+
+        precise.ingest_report(&"unknown", &low_report);
+        assert_eq!(precise.get_raw_global(&"node1"), Some(OrderedFloat::from(1.0)));
+
+        let light_node1 = light.get_raw_global(&"node1");
+        light.ingest_report(&"unknown", &low_report);
+        assert_eq!(light.get_raw_global(&"node1"), light_node1);
+    }
+
+    #[test]
+    fn bucketize_local_should_match_across_backends_at_boundaries() {
+        let ranges: Vec<(OrderedFloat<f64>, OrderedFloat<f64>)> = vec![
+            (OrderedFloat::from(0.0), OrderedFloat::from(5.0)),
+            (OrderedFloat::from(5.0), OrderedFloat::from(15.0)), 
+            (OrderedFloat::from(15.0), OrderedFloat::<f64>::max_value())
+        ];
+        let scores = [0.0, 4.999, 5.0, 5.001, 14.999, 15.0, 15.001, 1000.0];
+
+        let mut precise: PreciseHonestPeer<usize, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light: LightHonestPeer<usize, OrderedFloat<f64>> = LightHonestPeer::new();
+        scores.iter().enumerate().for_each(|(i, score)| {
+            precise.init_local(&i, OrderedFloat::from(*score));
+            light.init_local(&i, OrderedFloat::from(*score));
+        });
+
+        fn generic<H: HonestPeer<Key = usize, Value = OrderedFloat<f64>>>(
+            hp: &H, 
+            ranges: &[(OrderedFloat<f64>, OrderedFloat<f64>)],
+            keys: usize
+        ) -> Vec<(usize, usize)> {
+            HonestPeer::bucketize_local_keys(hp, 0..keys, RangeBucketizer::new(ranges.to_vec())).collect()
+        }
+        assert_eq!(generic(&precise, &ranges, scores.len()), generic(&light, &ranges, scores.len()));
+
+        let mut inherent: Vec<(usize, usize)> = {
+            precise.bucketize_local(RangeBucketizer::new(ranges.clone())).collect()
+        };
+        inherent.sort();
+        let light_inherent: Vec<(usize, usize)> = {
+            light.bucketize_local(0..scores.len(), RangeBucketizer::new(ranges.clone())).collect()
+        };
+        assert_eq!(inherent, light_inherent);
+        assert_eq!(inherent, generic(&precise, &ranges, scores.len()));
+    }
+
+    #[test]
+    fn normalized_local_sorted_should_rank_descending_with_key_ties() {
+        let scores = [("node4", 2.0), ("node2", 4.0), ("node3", 2.0), ("node1", 2.0), ("node5", 0.0)];
+        let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        let mut light = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+        for (key, score) in scores {
+            precise.init_local(&key, score.into());
+            light.init_local(&key, score.into());
+        }
+
+        let expected: Vec<(&str, OrderedFloat<f64>)> = vec![
+            ("node2", 0.4.into()), 
+            ("node1", 0.2.into()), 
+            ("node3", 0.2.into()), 
+            ("node4", 0.2.into()), 
+            ("node5", 0.0.into())
+        ];
+        assert_eq!(precise.normalized_local_sorted(), expected);
+        assert_eq!(light.normalized_local_sorted(), expected);
+        assert_eq!(precise.top_k_local(2), expected[..2].to_vec());
+
+        let empty: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        assert!(empty.normalized_local_sorted().is_empty());
+    }
+
+    #[test]
+    fn set_local_vector_should_handle_zero_totals() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.set_local_vector("node1", HashMap::from([("node2", 0f64.into()), ("node3", 0f64.into())]));
+        hp.set_local_vector("node2", HashMap::new());
+
+        let matrix = hp.local_trust_matrix();
+        assert_eq!(matrix["node1"]["node2"], OrderedFloat::from(0.5));
+        assert_eq!(matrix["node1"]["node3"], OrderedFloat::from(0.5));
+        assert!(matrix["node2"].is_empty());
+
+        let mut hp: PreciseHonestPeer<&str, u64> = PreciseHonestPeer::new();
+        hp.set_local_vector("node1", HashMap::from([("node2", 0)]));
+        hp.set_local_vector("node2", HashMap::new());
+        assert!(hp.local_trust_matrix()["node2"].is_empty());
+    }
+
+    #[test]
+    fn precise_bounds_should_not_overflow_integer_values() {
+        let mut hp: PreciseHonestPeer<&str, u64> = {
+            PreciseHonestPeer::new_with_bounds(0, 100).with_attribution(true)
+        };
+        hp.init_local(&"node1", 50);
+        hp.update_local(&"node1", u64::MAX, Update::Increment);
+        assert_eq!(hp.get_raw_local(&"node1"), Some(100));
+
+        // the sender holds all local trust, so its reports are weighted by 1
+        hp.remove_local(&"node1");
+        hp.init_local(&"sender", 1);
+        hp.update_global(&"sender", &"node2", 60, Update::Increment);
+        hp.update_global(&"sender", &"node2", u64::MAX, Update::Increment);
+        assert_eq!(hp.get_raw_global(&"node2"), Some(100));
+        assert_eq!(hp.sender_influence()[&"sender"], 100);
+    }
+
+    #[test]
+    fn lazy_reads_should_follow_updates_made_after_a_read() {
+        let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+        hp.set_lazy_normalization(true);
+        hp.init_local(&"node1", 1f64.into());
+        hp.init_local(&"node2", 1f64.into());
+        assert_eq!(hp.get_normalized_local(&"node1"), Some(OrderedFloat::from(0.5)));
+
+        hp.update_local(&"node2", 2f64.into(), Update::Increment);
+        assert_eq!(hp.get_normalized_local(&"node1"), Some(OrderedFloat::from(0.25)));
+        assert_eq!(hp.get_normalized_local(&"node2"), Some(OrderedFloat::from(0.75)));
+
+        hp.remove_local(&"node2");
+        assert_eq!(hp.get_normalized_local(&"node1"), Some(OrderedFloat::from(1.0)));
+    }
+
+    #[test]
+    fn recommended_dimensions_should_reject_invalid_parameters() {
+        let recommend = CountMinSketch::<u64>::recommended_dimensions;
+
+        assert_eq!(recommend(100, 1.0, 1.0), Err(CmsParamError::InvalidProbability(1.0)));
+        assert_eq!(recommend(100, 1.0, 0.0), Err(CmsParamError::InvalidProbability(0.0)));
+        assert!(matches!(
+            recommend(100, 1.0, f64::NAN), 
+            Err(CmsParamError::InvalidProbability(p)) if p.is_nan()
+        ));
+        assert_eq!(recommend(100, 0.0, 0.9), Err(CmsParamError::InvalidErrorBound(0.0)));
+        assert_eq!(recommend(100, -1.0, 0.9), Err(CmsParamError::InvalidErrorBound(-1.0)));
+        assert_eq!(recommend(0, 1.0, 0.9), Err(CmsParamError::InvalidMaxEntries(0.0)));
+    }
 }
@@ -1,9 +1,25 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::hash::Hash;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 use std::ops::{AddAssign, DivAssign, SubAssign, Add, Mul, Div, Sub};
 use buckets::bucketize::BucketizeSingle;
-use num_traits::Bounded;
-use crate::honest_peer::{HonestPeer, Update};
+use num_traits::{Bounded, NumCast, One, ToPrimitive};
+use crate::honest_peer::{HonestPeer, Update, NormalizationMode, is_distribution};
+use crate::error::{UnderflowError, UnknownPeerError, DecodeError, DistributionError};
+use crate::codec::{ByteCodec, FORMAT_VERSION, decode_version, expect_end};
+use crate::weight::{WeightStrategy, Multiplicative};
+use crate::clock::Clock;
+use crate::rate::{RateLimit, TokenBucket};
+use crate::util::{
+    nearest_rank_quantile, bucket_histogram, floored_sub, saturating_add, percentile_ranks, 
+    decimal_rounding, scaled_share, BucketTracker, Rounding
+};
+
+/// How far from 1 the values passed to `init_from_normalized_local` may 
+/// sum to
+const DISTRIBUTION_TOLERANCE: f64 = 1e-6;
 
 /// A struct to track local and global trust of peers in a 
 /// peer to peer data sharing network. Trust scores 
@@ -11,9 +27,65 @@ use crate::honest_peer::{HonestPeer, Update};
 /// struct witnesses trustworthy or malicious behaviours by a peer 
 /// respectively. 
 ///
+/// Reports from other peers are weighted by a `WeightStrategy` before 
+/// they are applied to global trust, `Multiplicative` unless another 
+/// strategy is set with `with_weight_strategy`.
+///
+/// By default the normalized maps are recomputed after every update. 
+/// With `set_lazy_normalization` enabled, updates only mark them stale 
+/// and reads fall back to normalizing from the raw maps until 
+/// `normalize_local` or `normalize_global` refreshes them.
+///
+/// Normalization divides by the sum of all values unless another 
+/// `NormalizationMode` is set with `set_normalization_mode`, scaling 
+/// them to sum to 1 unless another target is set with 
+/// `set_normalization_target`, and rounded once a precision is set with 
+/// `set_normalized_precision`. When the values sum to zero, e.g. once 
+/// every peer was decremented to zero, there is no meaningful share to 
+/// compute and every peer is given an equal share of the target 
+/// instead, so the normalized values stay a valid distribution.
+///
+/// Decrements never take a value below the configured floor, 
+/// `V::default()` unless set with `with_floor` or `set_floor`.
+///
+/// With attribution enabled by `with_attribution`, the global trust each 
+/// sender contributed to each peer is recorded so that `revoke_sender` 
+/// can undo it.
+///
+/// Peers first seen through an update start from the newcomer default, 
+/// `V::default()` unless set with `with_newcomer_default`, before the 
+/// update's delta is applied.
+///
+/// In strict mode, enabled with `with_strict` or `set_strict`, updates to 
+/// peers that were never initialized are ignored instead of creating 
+/// them, see `try_update_local` to surface them as errors.
+///
+/// Once a `Clock` is set with `with_clock`, the time of the last update 
+/// to each peer's local trust is recorded so that `decay_by_age` can 
+/// age peers by how long ago they were last updated.
+///
+/// A `RateLimit` set with `with_rate_limit` caps how many reports each 
+/// sender can make through `update_global` and `update_global_multi`, 
+/// so that a single peer cannot flood global trust with reports. Reports 
+/// beyond a sender's limit are dropped. The limit reads the time from 
+/// the clock if one is set.
+///
 /// ```
 /// use std::collections::HashMap;
 /// use std::hash::Hash;
+/// use std::sync::{Arc, OnceLock};
+/// use std::time::Instant;
+/// use decentrust::weight::WeightStrategy;
+/// use decentrust::clock::Clock;
+/// use decentrust::rate::{RateLimit, TokenBucket};
+///
+/// #[derive(Clone, Copy, Debug, Default)]
+/// struct Contribution<V> {
+///     added: V,
+///     removed: V,
+/// }
+///
+/// use decentrust::honest_peer::NormalizationMode;
 /// use std::ops::{AddAssign, DivAssign, Add, Mul};
 ///
 /// pub struct PreciseHonestPeer<K, V> 
@@ -30,8 +102,32 @@ use crate::honest_peer::{HonestPeer, Update};
 ///     global_trust: HashMap<K, V>,
 ///     normalized_local_trust: HashMap<K, V>,
 ///     normalized_global_trust: HashMap<K, V>,
+///     local_trust_matrix: HashMap<K, HashMap<K, V>>,
+///     normalized_local_trust_matrix: HashMap<K, HashMap<K, V>>,
+///     weight_strategy: Arc<dyn WeightStrategy<V>>,
+///     lazy_normalization: bool,
+///     local_dirty: bool,
+///     global_dirty: bool,
+///     local_total: OnceLock<V>,
+///     global_total: OnceLock<V>,
+///     floor: V,
+///     min: V,
+///     max: V,
+///     normalization_mode: NormalizationMode,
+///     normalization_target: Option<V>,
+///     rounding: Option<(u32, Arc<dyn Fn(V) -> V + Send + Sync>)>,
+///     clock: Option<Arc<dyn Clock>>,
+///     last_updated: HashMap<K, Instant>,
+///     rate_limit: Option<RateLimit>,
+///     rate_buckets: HashMap<K, TokenBucket>,
+///     strict: bool,
+///     pretrusted: HashMap<K, V>,
+///     damping: V,
+///     newcomer_default: V,
+///     attribution: Option<HashMap<K, HashMap<K, Contribution<V>>>>,
 /// }
 /// ```
+#[derive(Clone)]
 pub struct PreciseHonestPeer<K, V> 
 where 
     K: Eq + Hash + Clone,
@@ -49,101 +145,1691 @@ where
     global_trust: HashMap<K, V>,
     normalized_local_trust: HashMap<K, V>,
     normalized_global_trust: HashMap<K, V>,
+    local_trust_matrix: HashMap<K, HashMap<K, V>>,
+    normalized_local_trust_matrix: HashMap<K, HashMap<K, V>>,
+    bucket_tracker: BucketTracker,
+    weight_strategy: Arc<dyn WeightStrategy<V>>,
+    lazy_normalization: bool,
+    local_dirty: bool,
+    global_dirty: bool,
+    // raw totals for reads while the maps are stale, reset on refresh
+    local_total: OnceLock<V>,
+    global_total: OnceLock<V>,
+    floor: V,
+    min: V,
+    max: V,
+    normalization_mode: NormalizationMode,
+    normalization_target: Option<V>,
+    rounding: Option<(u32, Rounding<V>)>,
+    clock: Option<Arc<dyn Clock>>,
+    last_updated: HashMap<K, Instant>,
+    rate_limit: Option<RateLimit>,
+    rate_buckets: HashMap<K, TokenBucket>,
+    strict: bool,
+    pretrusted: HashMap<K, V>,
+    damping: V,
+    newcomer_default: V,
+    attribution: Option<HashMap<K, HashMap<K, Contribution<V>>>>,
+}
+
+/// The weighted global trust a single sender has added to and removed 
+/// from a single peer, recorded when attribution is enabled
+#[derive(Clone, Copy, Debug, Default)]
+struct Contribution<V> {
+    added: V,
+    removed: V,
+}
+
+impl<V: ByteCodec> ByteCodec for Contribution<V> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.added.encode(out);
+        self.removed.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Contribution { added: V::decode(input)?, removed: V::decode(input)? })
+    }
 }
 
 
-impl<K, V> PreciseHonestPeer<K, V> 
-where 
-    K: Eq + Hash + Clone,
-    V: AddAssign 
-    + DivAssign 
-    + SubAssign 
-    + Add<Output = V> 
-    + Mul<Output = V> 
-    + Div<Output = V> 
-    + Sub<Output = V> 
-    + PartialOrd
-    + Copy 
-    + Default,
-{
-    /// Creates a new `PreciseHonestPeer` struct with no peers in it.
-    /// 
+impl<K, V> PreciseHonestPeer<K, V> 
+where 
+    K: Eq + Hash + Clone,
+    V: AddAssign 
+    + DivAssign 
+    + SubAssign 
+    + Add<Output = V> 
+    + Mul<Output = V> 
+    + Div<Output = V> 
+    + Sub<Output = V> 
+    + PartialOrd
+    + Copy 
+    + Default
+    + Bounded
+    + One,
+{
+    /// Creates a new `PreciseHonestPeer` struct with no peers in it.
+    /// 
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = {
+    ///     PreciseHonestPeer::new()
+    /// };
+    /// 
+    /// assert_eq!(0, hp.local_raw_len());
+    /// assert_eq!(0, hp.global_raw_len());
+    /// ```
+    pub fn new() -> Self {
+        PreciseHonestPeer { 
+            local_trust: HashMap::new(), 
+            global_trust: HashMap::new(),
+            normalized_local_trust: HashMap::new(),
+            normalized_global_trust: HashMap::new(),
+            local_trust_matrix: HashMap::new(),
+            normalized_local_trust_matrix: HashMap::new(),
+            bucket_tracker: BucketTracker::default(),
+            weight_strategy: Arc::new(Multiplicative),
+            lazy_normalization: false,
+            local_dirty: false,
+            global_dirty: false,
+            local_total: OnceLock::new(),
+            global_total: OnceLock::new(),
+            floor: V::default(),
+            min: V::min_value(),
+            max: V::max_value(),
+            normalization_mode: NormalizationMode::Sum,
+            normalization_target: None,
+            rounding: None,
+            clock: None,
+            last_updated: HashMap::new(),
+            rate_limit: None,
+            rate_buckets: HashMap::new(),
+            strict: false,
+            pretrusted: HashMap::new(),
+            damping: V::default(),
+            newcomer_default: V::default(),
+            attribution: None,
+        }
+    }
+
+    /// Creates a new `PreciseHonestPeer` with no peers in it whose raw 
+    /// trust is kept within `[min, max]`, like the bounds of a 
+    /// `LightHonestPeer` sketch. Every method writing raw trust clamps 
+    /// to the bounds, except `init_from_normalized_local`, which 
+    /// restores the given distribution as it is. `new` uses the bounds 
+    /// of `V` itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` is greater than `max`.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+    ///     PreciseHonestPeer::new_with_bounds(0f64.into(), 10f64.into())
+    /// };
+    /// hp.init_local(&"node1", 8f64.into());
+    /// hp.update_local(&"node1", 5f64.into(), Update::Increment);
+    ///
+    /// assert_eq!(hp.get_raw_local(&"node1"), Some(OrderedFloat::from(10.0)));
+    /// assert_eq!(hp.value_bounds(), (0f64.into(), 10f64.into()));
+    /// ```
+    pub fn new_with_bounds(min: V, max: V) -> Self {
+        assert!(min <= max, "min must not be greater than max");
+
+        let mut hp = Self::new();
+        hp.min = min;
+        hp.max = max;
+        hp
+    }
+
+    /// Sets the value decrements stop at, see `set_floor`
+    pub fn with_floor(mut self, floor: V) -> Self {
+        self.floor = floor;
+        self
+    }
+
+    /// Sets the value decrements stop at, e.g. a baseline granted to 
+    /// every newcomer. Values already below the floor are left as 
+    /// they are, they are only never decremented further.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.set_floor(1f64.into());
+    /// hp.init_local(&"node1", 5f64.into());
+    /// hp.update_local(&"node1", 10f64.into(), Update::Decrement);
+    ///
+    /// assert_eq!(hp.get_raw_local(&"node1"), Some(OrderedFloat::from(1.0)));
+    /// ```
+    pub fn set_floor(&mut self, floor: V) {
+        self.floor = floor;
+    }
+
+    /// Returns the value decrements stop at
+    pub fn floor(&self) -> V {
+        self.floor
+    }
+
+    /// Replaces the strategy used to weight reported deltas by their 
+    /// sender's normalized local trust, see `WeightStrategy`. Values 
+    /// already in the global trust map are left as they are.
+    pub fn with_weight_strategy<S>(mut self, strategy: S) -> Self 
+    where
+        S: WeightStrategy<V> + 'static
+    {
+        self.weight_strategy = Arc::new(strategy);
+        self
+    }
+
+    /// Sets the raw trust peers first seen through an update start 
+    /// from, see `set_newcomer_default`
+    pub fn with_newcomer_default(mut self, newcomer_default: V) -> Self {
+        self.newcomer_default = newcomer_default;
+        self
+    }
+
+    /// Sets the raw trust a peer starts from when `update_local` or 
+    /// `update_global` is called for it before it was initialized, e.g. 
+    /// a neutral baseline, so that the update's delta is applied on top 
+    /// of it. Defaults to `V::default()`. Peers added with `init_*` or 
+    /// `reset_*` take the given value as is.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.set_newcomer_default(5f64.into());
+    /// hp.update_local(&"node1", 1f64.into(), Update::Increment);
+    ///
+    /// assert_eq!(hp.get_raw_local(&"node1"), Some(OrderedFloat::from(6.0)));
+    /// ```
+    pub fn set_newcomer_default(&mut self, newcomer_default: V) {
+        self.newcomer_default = newcomer_default;
+    }
+
+    /// Returns the raw trust peers first seen through an update start from
+    pub fn newcomer_default(&self) -> V {
+        self.newcomer_default
+    }
+
+    /// Enables or disables attribution, see `set_attribution`
+    pub fn with_attribution(mut self, on: bool) -> Self {
+        self.set_attribution(on);
+        self
+    }
+
+    /// Enables or disables recording how much weighted global trust 
+    /// each sender has added to and removed from each peer, which 
+    /// `revoke_sender` needs to undo a sender's reports. This keeps an 
+    /// entry per sender and reported peer pair, so memory grows with 
+    /// the number of senders times the number of peers each reports 
+    /// on, on top of the trust maps themselves. Only reports made while 
+    /// enabled are recorded, and disabling drops every record.
+    pub fn set_attribution(&mut self, on: bool) {
+        match (on, self.attribution.is_some()) {
+            (true, false) => self.attribution = Some(HashMap::new()),
+            (false, true) => self.attribution = None,
+            _ => {}
+        }
+    }
+
+    /// Returns whether attribution is enabled
+    pub fn is_attributing(&self) -> bool {
+        self.attribution.is_some()
+    }
+
+    /// Returns the total weighted global trust each sender has pushed 
+    /// into global trust while attribution was enabled, counting what 
+    /// it added and what it removed alike, so that a single sender 
+    /// dominating aggregation stands out. Empty unless attribution is 
+    /// enabled.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+    ///     PreciseHonestPeer::new().with_attribution(true)
+    /// };
+    /// hp.init_local(&"node1", 1f64.into());
+    ///
+    /// hp.update_global(&"node1", &"node2", 2f64.into(), Update::Increment);
+    /// hp.update_global(&"node1", &"node3", 1f64.into(), Update::Decrement);
+    ///
+    /// assert_eq!(hp.sender_influence()[&"node1"], OrderedFloat::from(2.0));
+    /// ```
+    pub fn sender_influence(&self) -> HashMap<K, V> {
+        let Some(attribution) = &self.attribution else {
+            return HashMap::new();
+        };
+
+        attribution.iter()
+            .map(|(sender, contributions)| {
+                let influence = contributions.values().fold(V::default(), |acc, contribution| {
+                    saturating_add(saturating_add(acc, contribution.added), contribution.removed)
+                });
+                (sender.clone(), influence)
+            })
+            .collect()
+    }
+
+    /// Records global trust applied to `key` on behalf of `sender`, if 
+    /// attribution is enabled
+    fn record_contribution(&mut self, sender: &K, key: &K, amount: V, update: &Update) {
+        if let Some(attribution) = self.attribution.as_mut() {
+            let contribution = attribution.entry(sender.clone())
+                .or_default()
+                .entry(key.clone())
+                .or_default();
+            match update {
+                Update::Increment => contribution.added += amount,
+                Update::Decrement => contribution.removed += amount,
+            }
+        }
+    }
+
+    /// Enables or disables strict mode, see `set_strict`
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Enables or disables strict mode. While enabled, `update_local` 
+    /// ignores peers that are not in the local trust map and 
+    /// `update_global` ignores peers that are not in the global trust 
+    /// map, so that a peer must be initialized before it is updated.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Returns whether strict mode is enabled
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Starts recording when each peer's local trust was last updated, 
+    /// reading the time from `clock`, see `decay_by_age`. Use 
+    /// `SystemClock` outside of tests.
+    pub fn with_clock<C>(mut self, clock: C) -> Self 
+    where
+        C: Clock + 'static
+    {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// Returns when a peer's local trust was last updated, or last aged 
+    /// by `decay_by_age`. Always `None` unless a clock is set.
+    pub fn last_updated(&self, key: &K) -> Option<Instant> {
+        self.last_updated.get(key).copied()
+    }
+
+    /// Limits how many reports each sender can make, see `set_rate_limit`
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.set_rate_limit(Some(limit));
+        self
+    }
+
+    /// Sets or removes the limit on how many reports each sender can 
+    /// make through `update_global` and `update_global_multi`. Reports 
+    /// from a sender that is over its limit are dropped without being 
+    /// weighted or applied. Every sender starts with a full bucket 
+    /// whenever the limit is changed.
+    pub fn set_rate_limit(&mut self, limit: Option<RateLimit>) {
+        self.rate_limit = limit;
+        self.rate_buckets.clear();
+    }
+
+    /// Returns the limit on reports per sender, if one is set
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit
+    }
+
+    /// Takes a token from the sender's bucket, returning whether its 
+    /// report is within the rate limit. Always `true` without a limit. 
+    /// Callers check that the sender is known first, so that reports 
+    /// from spoofed senders never create a bucket.
+    fn within_rate_limit(&mut self, sender: &K) -> bool {
+        let Some(limit) = self.rate_limit else {
+            return true;
+        };
+
+        let now = self.clock.as_ref().map_or_else(Instant::now, |clock| clock.now());
+        self.rate_buckets.entry(sender.clone())
+            .or_insert_with(|| TokenBucket::new(limit, now))
+            .try_take(now)
+    }
+
+    /// Clamps a raw value into the configured `[min, max]` bounds
+    fn clamped(&self, value: V) -> V {
+        if value < self.min {
+            self.min
+        } else if value > self.max {
+            self.max
+        } else {
+            value
+        }
+    }
+
+    /// Records the current time as a peer's last update, if a clock is set
+    fn touch_local(&mut self, key: &K) {
+        if let Some(clock) = &self.clock {
+            self.last_updated.insert(key.clone(), clock.now());
+        }
+    }
+
+    /// Returns a handle to the strategy used to weight reported deltas
+    pub(crate) fn weight_strategy(&self) -> Arc<dyn WeightStrategy<V>> {
+        self.weight_strategy.clone()
+    }
+
+    /// Returns whether lazy normalization has left the normalized local 
+    /// or global map out of date with the raw maps
+    pub fn is_normalization_stale(&self) -> bool {
+        self.local_dirty || self.global_dirty
+    }
+
+    /// Returns the normalized local trust map, computed from the raw 
+    /// map if lazy normalization left the stored one out of date
+    fn normalized_local_view(&self) -> Cow<'_, HashMap<K, V>> {
+        if self.local_dirty {
+            Cow::Owned(self.round_map(normalize_map(&self.local_trust, self.normalization_target)))
+        } else {
+            Cow::Borrowed(&self.normalized_local_trust)
+        }
+    }
+
+    /// Returns the normalized global trust map, computed from the raw 
+    /// map if lazy normalization left the stored one out of date
+    fn normalized_global_view(&self) -> Cow<'_, HashMap<K, V>> {
+        if self.global_dirty {
+            Cow::Owned(self.round_map(normalize_map(&self.global_trust, self.normalization_target)))
+        } else {
+            Cow::Borrowed(&self.normalized_global_trust)
+        }
+    }
+
+    /// Rounds a normalized value to the configured precision, if any
+    fn rounded(&self, value: V) -> V {
+        match &self.rounding {
+            Some((_, round)) => round(value),
+            None => value,
+        }
+    }
+
+    /// Rounds every value of a normalized map, see `rounded`
+    fn round_map(&self, map: HashMap<K, V>) -> HashMap<K, V> {
+        if self.rounding.is_none() {
+            return map;
+        }
+
+        map.into_iter().map(|(k, v)| (k, self.rounded(v))).collect()
+    }
+
+    /// Yields every peer in the raw local trust map with the bucket its 
+    /// raw value falls into, in no particular order. Values are handed 
+    /// to the bucketizer unchanged, as `LightHonestPeer::bucketize_local` 
+    /// does with its estimates, so a value on a bucket boundary lands in 
+    /// whichever bucket the bucketizer assigns it on both backends.
+    pub fn bucketize_local<'a, B>(
+        &'a self, 
+        bucketizer: B
+    ) -> impl Iterator<Item = (K, usize)> + '_
+    where 
+        B: BucketizeSingle<V> + 'a
+    {
+        self.local_trust.iter().map(move |(k, v)| {
+            let bucketed = self.bucket_tracker.observe(bucketizer.bucketize(v));
+            (k.clone(), bucketed)
+        })
+    }
+
+    ///
+    pub fn bucketize_normalized_local<'a, B>(
+        &'a self, 
+        bucketizer: B
+    ) -> impl Iterator<Item = (K, usize)> + '_
+    where 
+        B: BucketizeSingle<V> + 'a
+    {
+        view_entries(self.normalized_local_view()).map(move |(k, v)| {
+            let bucketed = self.bucket_tracker.observe(bucketizer.bucketize(&v));
+            (k, bucketed)
+        })
+    }
+
+    pub fn bucketize_global<'a, B>(
+        &'a self, 
+        bucketizer: B
+    ) -> impl Iterator<Item = (K, usize)> + '_
+    where 
+        B: BucketizeSingle<V> + 'a
+    {
+        self.global_trust.iter().map(move |(k, v)| {
+            let bucketed = self.bucket_tracker.observe(bucketizer.bucketize(v));
+            (k.clone(), bucketed)
+
+        })
+    }
+
+    pub fn bucketize_normalized_global<'a, B>(
+        &'a self, 
+        bucketizer: B
+    ) -> impl Iterator<Item = (K, usize)> + '_
+    where 
+        B: BucketizeSingle<V> + 'a
+    {
+        view_entries(self.normalized_global_view()).map(move |(k, v)| {
+            let bucketed = self.bucket_tracker.observe(bucketizer.bucketize(&v));
+            (k, bucketed)
+        })
+    }
+
+    /// Stores the local trust vector reported by `owner`, i.e. how much 
+    /// `owner` trusts each of the peers it knows, replacing any vector 
+    /// previously reported by `owner`. The vector is normalized on its 
+    /// own, so each row of the trust matrix sums to 1 regardless of 
+    /// the scale used by the reporting peer. A vector whose values sum 
+    /// to zero gives every peer in it an equal share, and an empty 
+    /// vector gives an empty row.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use ordered_float::OrderedFloat;
+    /// use std::collections::HashMap;
+    ///
+    /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = {
+    ///     PreciseHonestPeer::new()
+    /// };
+    ///
+    /// let vector: HashMap<String, OrderedFloat<f64>> = vec![
+    ///     ("node2".to_string(), OrderedFloat::from(1.0)),
+    ///     ("node3".to_string(), OrderedFloat::from(3.0)),
+    /// ].into_iter().collect();
+    ///
+    /// hp.set_local_vector("node1".to_string(), vector);
+    ///
+    /// let matrix = hp.local_trust_matrix();
+    /// assert_eq!(matrix["node1"]["node3"], OrderedFloat::from(0.75));
+    /// ```
+    pub fn set_local_vector(&mut self, owner: K, vector: HashMap<K, V>) {
+        let normalized = normalize_map(&vector, None);
+
+        self.normalized_local_trust_matrix.insert(owner.clone(), normalized);
+        self.local_trust_matrix.insert(owner, vector);
+    }
+
+    /// Sets the pre-trusted peers `converge_global` always sends a share 
+    /// of trust to, replacing any previously set. The given weights are 
+    /// normalized to sum to 1, and an empty slice clears the set.
+    pub fn set_pretrusted(&mut self, peers: &[(K, V)]) {
+        let total = peers.iter().fold(V::default(), |acc, (_, v)| acc + *v);
+
+        self.pretrusted = if total == V::default() {
+            HashMap::new()
+        } else {
+            peers.iter().map(|(k, v)| (k.clone(), *v / total)).collect()
+        };
+    }
+
+    /// Returns the normalized weight of each pre-trusted peer
+    pub fn pretrusted(&self) -> &HashMap<K, V> {
+        &self.pretrusted
+    }
+
+    /// Sets the damping factor, see `set_damping`
+    pub fn with_damping(mut self, damping: V) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Sets the fraction of global trust, within `[0, 1]`, that 
+    /// `converge_global` sends to the pre-trusted peers on every 
+    /// iteration rather than along the trust matrix. Defaults to zero, 
+    /// i.e. no damping.
+    pub fn set_damping(&mut self, damping: V) {
+        self.damping = damping;
+    }
+
+    /// Returns the damping factor used by `converge_global`
+    pub fn damping(&self) -> V {
+        self.damping
+    }
+
+    /// Returns the trust matrix built from every reported local trust 
+    /// vector, keyed by the reporting peer, with each row normalized 
+    /// independently
+    pub fn local_trust_matrix(&self) -> HashMap<K, HashMap<K, V>> {
+        self.normalized_local_trust_matrix.clone()
+    }
+
+    /// Returns the trust matrix built from every reported local trust 
+    /// vector, keyed by the reporting peer, with the values as reported
+    pub fn raw_local_trust_matrix(&self) -> HashMap<K, HashMap<K, V>> {
+        self.local_trust_matrix.clone()
+    }
+
+    /// Applies an already weighted delta to the raw global trust of a 
+    /// given peer, without normalizing. Returns the amount actually 
+    /// added or removed, which is less than the delta when a decrement 
+    /// hits the floor or an update hits the bounds, and nothing when 
+    /// strict mode skips the peer.
+    fn apply_global_delta(&mut self, key: &K, weighted_delta: V, update: Update) -> V {
+        if self.strict && !self.global_trust.contains_key(key) {
+            return V::default()
+        }
+
+        let current = self.global_trust.get(key).copied().unwrap_or(self.newcomer_default);
+        match update {
+            Update::Increment => {
+                let updated = self.clamped(saturating_add(current, weighted_delta));
+                self.global_trust.insert(key.clone(), updated);
+                // strictly within the bounds nothing saturated or clamped
+                if self.min < updated && updated < self.max {
+                    weighted_delta
+                } else if updated > current {
+                    updated - current
+                } else {
+                    V::default()
+                }
+            },
+            Update::Decrement => {
+                let floored = self.clamped(floored_sub(current, weighted_delta, self.floor));
+                self.global_trust.insert(key.clone(), floored);
+                if floored < current { current - floored } else { V::default() }
+            }
+        }
+    }
+
+    /// Returns the number of peers that fall into each bucket of the 
+    /// raw local trust values, keyed by bucket index. Buckets no peer 
+    /// falls into are absent from the map.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use buckets::bucketizers::fw::FixedWidthBucketizer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = {
+    ///     PreciseHonestPeer::new()
+    /// };
+    ///
+    /// hp.init_local(&"node1".to_string(), 1f64.into());
+    /// hp.init_local(&"node2".to_string(), 2f64.into());
+    /// hp.init_local(&"node3".to_string(), 12f64.into());
+    ///
+    /// let bucketizer = FixedWidthBucketizer::<OrderedFloat<f64>>::new(
+    ///     OrderedFloat::from(10.0), OrderedFloat::from(0.0)
+    /// );
+    /// let histogram = hp.local_histogram(bucketizer);
+    ///
+    /// assert_eq!(histogram.get(&0), Some(&2));
+    /// assert_eq!(histogram.get(&1), Some(&1));
+    /// ```
+    pub fn local_histogram<B>(&self, bucketizer: B) -> HashMap<usize, usize>
+    where
+        B: BucketizeSingle<V>
+    {
+        bucket_histogram(self.bucketize_local(bucketizer))
+    }
+
+    /// Returns the number of peers that fall into each bucket of the 
+    /// normalized local trust values, keyed by bucket index
+    pub fn normalized_local_histogram<B>(&self, bucketizer: B) -> HashMap<usize, usize>
+    where
+        B: BucketizeSingle<V>
+    {
+        bucket_histogram(self.bucketize_normalized_local(bucketizer))
+    }
+
+    /// Returns the number of peers that fall into each bucket of the 
+    /// raw global trust values, keyed by bucket index
+    pub fn global_histogram<B>(&self, bucketizer: B) -> HashMap<usize, usize>
+    where
+        B: BucketizeSingle<V>
+    {
+        bucket_histogram(self.bucketize_global(bucketizer))
+    }
+
+    /// Returns the number of peers that fall into each bucket of the 
+    /// normalized global trust values, keyed by bucket index
+    pub fn normalized_global_histogram<B>(&self, bucketizer: B) -> HashMap<usize, usize>
+    where
+        B: BucketizeSingle<V>
+    {
+        bucket_histogram(self.bucketize_normalized_global(bucketizer))
+    }
+
+    /// Returns the number of buckets produced by the `bucketize_*` 
+    /// methods so far, every bucket index they have yielded is within 
+    /// `0..bucket_count()`. Buckets are only counted as the returned 
+    /// iterators are consumed.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use buckets::bucketizers::fw::FixedWidthBucketizer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = {
+    ///     PreciseHonestPeer::new()
+    /// };
+    ///
+    /// hp.init_local(&"node1".to_string(), 1f64.into());
+    /// hp.init_local(&"node2".to_string(), 3f64.into());
+    ///
+    /// let bucketizer = FixedWidthBucketizer::<OrderedFloat<f64>>::new(
+    ///     OrderedFloat::from(0.1), OrderedFloat::from(0.0)
+    /// );
+    /// let buckets: Vec<(String, usize)> = hp.bucketize_normalized_local(bucketizer).collect();
+    ///
+    /// assert!(buckets.iter().all(|(_, b)| *b < hp.bucket_count()));
+    /// ```
+    pub fn bucket_count(&self) -> usize {
+        self.bucket_tracker.bucket_count()
+    }
+
+    /// Returns the highest bucket index produced by the `bucketize_*` 
+    /// methods so far, or `None` if none have been produced yet
+    pub fn max_bucket_seen(&self) -> Option<usize> {
+        self.bucket_tracker.max_bucket_seen()
+    }
+
+    /// Returns the `q` quantile (nearest-rank) of the normalized local 
+    /// trust values across all known peers, e.g. `0.5` for the median 
+    /// or `0.9` for the p90. Returns `None` if there are no peers or 
+    /// `q` is outside of `[0, 1]`.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = {
+    ///     PreciseHonestPeer::new()
+    /// };
+    ///
+    /// assert_eq!(hp.local_quantile(0.5), None);
+    ///
+    /// hp.init_local(&"node1".to_string(), 1f64.into());
+    /// hp.init_local(&"node2".to_string(), 3f64.into());
+    ///
+    /// assert_eq!(hp.local_quantile(0.5), Some(OrderedFloat::from(0.25)));
+    /// assert_eq!(hp.local_quantile(1.0), Some(OrderedFloat::from(0.75)));
+    /// ```
+    pub fn local_quantile(&self, q: f64) -> Option<V> {
+        nearest_rank_quantile(self.normalized_local_view().values().cloned(), q)
+    }
+
+    /// Returns the `q` quantile (nearest-rank) of the normalized global 
+    /// trust values across all known peers. Returns `None` if there are 
+    /// no peers or `q` is outside of `[0, 1]`.
+    pub fn global_quantile(&self, q: f64) -> Option<V> {
+        nearest_rank_quantile(self.normalized_global_view().values().cloned(), q)
+    }
+
+    /// Returns the cosine similarity between the normalized local trust 
+    /// vectors of `self` and `other`, over the union of their keys, with 
+    /// missing peers counting as zero. Values close to `1.0` mean both 
+    /// nodes report near-identical trust, which can hint at collusion. 
+    /// Returns `0.0` if either side has no non-zero trust.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut a: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// let mut b: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    ///
+    /// a.init_local(&"node1", 1f64.into());
+    /// a.init_local(&"node2", 3f64.into());
+    /// b.init_local(&"node1", 2f64.into());
+    /// b.init_local(&"node2", 6f64.into());
+    ///
+    /// assert!((a.trust_similarity(&b) - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn trust_similarity(&self, other: &Self) -> f64 
+    where
+        V: ToPrimitive
+    {
+        let ours = self.normalized_local_view();
+        let theirs = other.normalized_local_view();
+        let as_f64 = |value: &V| value.to_f64().unwrap_or(0.0);
+
+        let dot: f64 = ours.iter()
+            .filter_map(|(key, value)| {
+                theirs.get(key).map(|other| as_f64(value) * as_f64(other))
+            })
+            .sum();
+        let norm = |map: &HashMap<K, V>| {
+            map.values().map(|value| as_f64(value).powi(2)).sum::<f64>().sqrt()
+        };
+        let denominator = norm(&ours) * norm(&theirs);
+
+        if denominator == 0.0 {
+            return 0.0
+        }
+
+        dot / denominator
+    }
+}
+
+impl<K, V> PreciseHonestPeer<K, V> 
+where 
+    K: Eq + Hash + Clone,
+    V: AddAssign 
+    + DivAssign 
+    + SubAssign 
+    + Add<Output = V> 
+    + Mul<Output = V> 
+    + Div<Output = V> 
+    + Sub<Output = V> 
+    + Copy 
+    + Default 
+    + Bounded
+    + One
+    + Hash 
+    + Ord
+{
+    /// Decrements a peer's raw local trust like `update_local` with 
+    /// `Update::Decrement`, but returns an `UnderflowError` instead of 
+    /// flooring when `trust_delta` exceeds the peer's current value 
+    /// above the floor. Unknown peers have nothing available. Nothing 
+    /// is modified on error.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node1", 3f64.into());
+    ///
+    /// assert!(hp.try_decrement_local(&"node1", 5f64.into()).is_err());
+    /// assert!(hp.try_decrement_local(&"node1", 2f64.into()).is_ok());
+    /// assert_eq!(hp.get_raw_local(&"node1"), Some(OrderedFloat::from(1.0)));
+    /// ```
+    pub fn try_decrement_local(
+        &mut self, 
+        key: &K, 
+        trust_delta: V
+    ) -> Result<(), UnderflowError<V>> {
+        let current = self.get_raw_local(key).unwrap_or_default();
+        let available = if current > self.floor {
+            current - self.floor
+        } else {
+            V::default()
+        };
+
+        if trust_delta > available {
+            return Err(UnderflowError { attempted: trust_delta, available });
+        }
+
+        self.update_local(key, trust_delta, Update::Decrement);
+        Ok(())
+    }
+
+    /// Updates a peer's local trust like `update_local`, but returns an 
+    /// `UnknownPeerError` instead of ignoring the update when strict mode 
+    /// is enabled and the peer was never initialized. Outside of strict 
+    /// mode this never fails.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+    ///     PreciseHonestPeer::new().with_strict(true)
+    /// };
+    /// hp.init_local(&"node1", 1f64.into());
+    ///
+    /// assert!(hp.try_update_local(&"node1", 1f64.into(), Update::Increment).is_ok());
+    /// assert!(hp.try_update_local(&"node2", 1f64.into(), Update::Increment).is_err());
+    /// assert_eq!(hp.get_raw_local(&"node2"), None);
+    /// ```
+    pub fn try_update_local(
+        &mut self, 
+        key: &K, 
+        trust_delta: V, 
+        update: Update
+    ) -> Result<(), UnknownPeerError<K>> {
+        if self.strict && !self.local_trust.contains_key(key) {
+            return Err(UnknownPeerError { key: key.clone() });
+        }
+
+        self.update_local(key, trust_delta, update);
+        Ok(())
+    }
+
+    /// Updates a peer's global trust like `update_global`, but returns an 
+    /// `UnknownPeerError` instead of ignoring the update when strict mode 
+    /// is enabled and the peer was never initialized in the global trust 
+    /// map. Outside of strict mode this never fails.
+    pub fn try_update_global(
+        &mut self, 
+        sender: &K,
+        key: &K, 
+        trust_delta: V, 
+        update: Update
+    ) -> Result<(), UnknownPeerError<K>> {
+        if self.strict && !self.global_trust.contains_key(key) {
+            return Err(UnknownPeerError { key: key.clone() });
+        }
+
+        self.update_global(sender, key, trust_delta, update);
+        Ok(())
+    }
+
+    /// Folds a full trust map reported by `reporter` into global trust, 
+    /// e.g. when joining a network, incrementing each reported peer by 
+    /// its value weighted by the reporter's normalized local trust and 
+    /// normalizing once at the end. The report counts as a single 
+    /// update against the rate limit, and reports from unknown or rate 
+    /// limited reporters are ignored, as with `update_global`.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"reporter", 1f64.into());
+    /// hp.init_local(&"other", 3f64.into());
+    ///
+    /// hp.ingest_report(&"reporter", &HashMap::from([("node1", 4f64.into())]));
+    ///
+    /// assert_eq!(hp.get_raw_global(&"node1"), Some(OrderedFloat::from(1.0)));
+    /// ```
+    pub fn ingest_report(&mut self, reporter: &K, report: &HashMap<K, V>) {
+        let Some(reporter_trust) = self.get_normalized_local(reporter) else {
+            return;
+        };
+        if !self.within_rate_limit(reporter) {
+            return;
+        }
+
+        for (key, trust) in report.iter() {
+            let weighted = self.weight_strategy.weight(*trust, reporter_trust);
+            let applied = self.apply_global_delta(key, weighted, Update::Increment);
+            self.record_contribution(reporter, key, applied, &Update::Increment);
+        }
+
+        self.refresh_global();
+    }
+
+    /// Undoes every global trust report `sender` made while attribution 
+    /// was enabled, subtracting what it added to each peer and adding 
+    /// back what it removed, then normalizes the global trust map. 
+    /// Peers removed since are skipped and values never drop below the 
+    /// floor. `init_global` and `reset_global` overwrite a peer's value, 
+    /// so the result is only exact if no such call touched the peers 
+    /// `sender` reported on after its reports. `converge_global` and 
+    /// `propagate_trust` replace the whole map and clear every recorded 
+    /// report. Does nothing if no report by `sender` was recorded.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+    ///     PreciseHonestPeer::new().with_attribution(true)
+    /// };
+    /// hp.init_local(&"honest", 1f64.into());
+    /// hp.init_local(&"liar", 1f64.into());
+    ///
+    /// hp.update_global(&"honest", &"node1", 2f64.into(), Update::Increment);
+    /// hp.update_global(&"liar", &"node1", 8f64.into(), Update::Increment);
+    /// hp.revoke_sender(&"liar");
+    ///
+    /// assert_eq!(hp.get_raw_global(&"node1"), Some(OrderedFloat::from(1.0)));
+    /// ```
+    pub fn revoke_sender(&mut self, sender: &K) {
+        let contributions = match self.attribution.as_mut().and_then(|a| a.remove(sender)) {
+            Some(contributions) => contributions,
+            None => return,
+        };
+
+        for (key, contribution) in contributions {
+            if let Some(value) = self.global_trust.get_mut(&key) {
+                let restored = *value + contribution.removed;
+                *value = floored_sub(restored, contribution.added, self.floor);
+            }
+        }
+
+        self.refresh_global();
+    }
+
+    /// Computes global trust from the local trust matrix the way 
+    /// EigenTrust does, iterating `t = (1 - a) * C^T * t + a * p` where 
+    /// `C` is the row-normalized trust matrix, `p` the pre-trusted 
+    /// distribution and `a` the damping factor. Iteration starts from 
+    /// `p`, or from a uniform distribution over every peer in the matrix 
+    /// if no peers are pre-trusted, and stops once no peer's trust moves 
+    /// by more than `tolerance` or after `max_iterations`. The vector is 
+    /// renormalized after each step so that trust held by peers without 
+    /// a reported vector is not lost.
+    ///
+    /// The result replaces the raw global trust map, which is then 
+    /// normalized, and clears the contributions recorded for 
+    /// `revoke_sender`. Returns the number of iterations run.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+    ///     PreciseHonestPeer::new().with_damping(0.5.into())
+    /// };
+    /// hp.set_local_vector("node1", HashMap::from([("node2", 1f64.into())]));
+    /// hp.set_local_vector("node2", HashMap::from([("node1", 1f64.into())]));
+    /// hp.set_pretrusted(&[("node1", 1f64.into())]);
+    ///
+    /// hp.converge_global(100, 1e-9.into());
+    ///
+    /// let node1 = hp.get_normalized_global(&"node1").unwrap();
+    /// assert!((node1.0 - 2.0 / 3.0).abs() < 1e-6);
+    /// ```
+    pub fn converge_global(&mut self, max_iterations: usize, tolerance: V) -> usize {
+        self.converge_global_with_history(max_iterations, tolerance).len()
+    }
+
+    /// Runs `converge_global`, returning the L1 residual of each 
+    /// iteration, i.e. the sum over every peer of how much its trust 
+    /// moved, so the number of iterations run is the length of the 
+    /// returned vector. A residual that shrinks slowly or oscillates 
+    /// points at a trust graph that is slow to converge, which more 
+    /// damping usually helps with.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.set_local_vector("node1", HashMap::from([("node2", 1f64.into())]));
+    /// hp.set_local_vector("node2", HashMap::from([("node1", 1f64.into())]));
+    ///
+    /// let history = hp.converge_global_with_history(100, 1e-9.into());
+    ///
+    /// assert_eq!(history, vec![OrderedFloat::from(0.0)]);
+    /// ```
+    pub fn converge_global_with_history(
+        &mut self, 
+        max_iterations: usize, 
+        tolerance: V
+    ) -> Vec<V> {
+        let one = V::one();
+        let mut trust = if self.pretrusted.is_empty() {
+            let mut peers: HashMap<K, V> = HashMap::new();
+            self.normalized_local_trust_matrix.iter().for_each(|(owner, row)| {
+                peers.insert(owner.clone(), one);
+                row.keys().for_each(|k| { peers.insert(k.clone(), one); });
+            });
+            normalize_map(&peers, None)
+        } else {
+            self.pretrusted.clone()
+        };
+
+        let mut history = Vec::new();
+        while history.len() < max_iterations {
+            let mut next: HashMap<K, V> = self.pretrusted.iter()
+                .map(|(k, p)| (k.clone(), self.damping * *p))
+                .collect();
+            for (owner, row) in self.normalized_local_trust_matrix.iter() {
+                let Some(owner_trust) = trust.get(owner).copied() else { continue };
+                for (k, c) in row.iter() {
+                    let flow = (one - self.damping) * *c * owner_trust;
+                    *next.entry(k.clone()).or_default() += flow;
+                }
+            }
+            let next = normalize_map(&next, None);
+
+            let distance = |a: V, b: V| if a > b { a - b } else { b - a };
+            let moves: Vec<V> = next.iter()
+                .map(|(k, v)| distance(*v, trust.get(k).copied().unwrap_or_default()))
+                .chain(
+                    trust.iter()
+                        .filter(|(k, _)| !next.contains_key(k))
+                        .map(|(_, v)| distance(*v, V::default()))
+                )
+                .collect();
+            let converged = moves.iter().all(|delta| *delta <= tolerance);
+            history.push(moves.into_iter().fold(V::default(), |acc, delta| acc + delta));
+
+            trust = next;
+            if converged {
+                break
+            }
+        }
+
+        self.replace_global(trust);
+        history
+    }
+
+    /// Computes global trust transitively from normalized local trust 
+    /// and the local trust matrix, for a bounded alternative to 
+    /// `converge_global`: trusting A, which trusts B, means trusting B 
+    /// somewhat. Each peer's normalized local trust is pushed along the 
+    /// row-normalized matrix for at most `max_hops` hops, scaled by 
+    /// `decay_per_hop` on every hop, and the trust a peer receives 
+    /// directly and over every hop is summed. Peers further than 
+    /// `max_hops` hops away receive nothing.
+    ///
+    /// The result replaces the raw global trust map, which is then 
+    /// normalized, and clears the contributions recorded for 
+    /// `revoke_sender`.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node1", 1f64.into());
+    /// hp.set_local_vector("node1", HashMap::from([("node2", 1f64.into())]));
+    ///
+    /// hp.propagate_trust(1, 0.5.into());
+    ///
+    /// assert_eq!(hp.get_raw_global(&"node2"), Some(OrderedFloat::from(0.5)));
+    /// ```
+    pub fn propagate_trust(&mut self, max_hops: usize, decay_per_hop: V) {
+        let mut frontier = normalize_map(&self.local_trust, None);
+        let mut propagated = frontier.clone();
+
+        for _ in 0..max_hops {
+            let mut next: HashMap<K, V> = HashMap::new();
+            for (owner, owner_trust) in frontier.iter() {
+                let Some(row) = self.normalized_local_trust_matrix.get(owner) else { continue };
+                for (k, c) in row.iter() {
+                    *next.entry(k.clone()).or_default() += decay_per_hop * *c * *owner_trust;
+                }
+            }
+            if next.is_empty() {
+                break
+            }
+
+            next.iter().for_each(|(k, v)| *propagated.entry(k.clone()).or_default() += *v);
+            frontier = next;
+        }
+
+        self.replace_global(propagated);
+    }
+
+    /// Replaces the raw global trust map with `trust`, clamped to the 
+    /// bounds, and normalizes it. The contributions recorded for 
+    /// `revoke_sender` no longer describe the new values, so they are 
+    /// cleared.
+    fn replace_global(&mut self, trust: HashMap<K, V>) {
+        self.global_trust = trust.into_iter().map(|(k, v)| (k, self.clamped(v))).collect();
+        if let Some(attribution) = self.attribution.as_mut() {
+            attribution.clear();
+        }
+        self.refresh_global();
+    }
+
+    /// Enables or disables lazy normalization. While enabled, updates 
+    /// only mark the normalized maps as stale instead of recomputing 
+    /// them, which saves repeated work when many updates are applied 
+    /// before reading. Reads of a stale map normalize from the raw map 
+    /// on the fly without storing the result, so call `normalize_local` 
+    /// and `normalize_global` once a batch of updates is done to refresh 
+    /// them. Disabling lazy normalization refreshes any stale map.
+    ///
+    /// Only `NormalizationMode::Sum` can be deferred, with 
+    /// `NormalizationMode::Rank` updates always normalize right away.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.set_lazy_normalization(true);
+    ///
+    /// hp.init_local(&"node1", 1f64.into());
+    /// hp.update_local(&"node2", 3f64.into(), Update::Increment);
+    /// assert!(hp.is_normalization_stale());
+    /// assert_eq!(hp.get_normalized_local(&"node2"), Some(OrderedFloat::from(0.75)));
+    ///
+    /// hp.normalize_local();
+    /// assert!(!hp.is_normalization_stale());
+    /// ```
+    pub fn set_lazy_normalization(&mut self, on: bool) {
+        self.lazy_normalization = on;
+        if !on {
+            if self.local_dirty {
+                self.normalize_local();
+            }
+            if self.global_dirty {
+                self.normalize_global();
+            }
+        }
+    }
+
+    /// Sets how raw trust is normalized and renormalizes both maps with 
+    /// the new mode. Every later normalization uses it as well.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::{HonestPeer, NormalizationMode};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.set_normalization_mode(NormalizationMode::Rank);
+    /// hp.init_local(&"node1", 1f64.into());
+    /// hp.init_local(&"node2", 1000f64.into());
+    ///
+    /// assert_eq!(hp.get_normalized_local(&"node1"), Some(OrderedFloat::from(0.5)));
+    /// assert_eq!(hp.get_normalized_local(&"node2"), Some(OrderedFloat::from(1.0)));
+    /// ```
+    pub fn set_normalization_mode(&mut self, mode: NormalizationMode) {
+        self.normalization_mode = mode;
+        self.normalize_local();
+        self.normalize_global();
+    }
+
+    /// Returns how raw trust is normalized
+    pub fn normalization_mode(&self) -> NormalizationMode {
+        self.normalization_mode
+    }
+
+    /// Sets the sum normalized values are scaled to in 
+    /// `NormalizationMode::Sum`, e.g. a fixed budget of 10000 trust 
+    /// points so that consumers can work with integers. Each value is 
+    /// multiplied by `target` before it is divided by the total, so 
+    /// integer values keep their precision, unless `value * target` 
+    /// would overflow `V`, see `util::scaled_share`. Both normalized 
+    /// maps are recomputed right away. 
+    /// Percentile ranks in `NormalizationMode::Rank` are not scaled.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, u64> = PreciseHonestPeer::new();
+    /// hp.set_normalization_target(10_000);
+    /// hp.init_local(&"node1", 1);
+    /// hp.init_local(&"node2", 3);
+    ///
+    /// assert_eq!(hp.get_normalized_local(&"node1"), Some(2_500));
+    /// assert_eq!(hp.get_normalized_local(&"node2"), Some(7_500));
+    /// ```
+    pub fn set_normalization_target(&mut self, target: V) {
+        self.normalization_target = Some(target);
+        self.normalize_local();
+        self.normalize_global();
+    }
+
+    /// Returns the sum normalized values are scaled to, `None` if they 
+    /// sum to 1
+    pub fn normalization_target(&self) -> Option<V> {
+        self.normalization_target
+    }
+
+    /// Rounds normalized values to `decimals` decimal places every time 
+    /// they are computed, e.g. to keep logged or gossiped values short, 
+    /// and rounds the current normalized maps right away. Rounding is 
+    /// applied per value, so normalized values may no longer sum to 
+    /// exactly 1 (or the normalization target): three equally trusted 
+    /// peers each get 0.33 at 2 decimals, summing to 0.99.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.set_normalized_precision(2);
+    /// hp.init_local(&"node1", 1f64.into());
+    /// hp.init_local(&"node2", 2f64.into());
+    ///
+    /// assert_eq!(hp.get_normalized_local(&"node1"), Some(OrderedFloat::from(0.33)));
+    /// assert_eq!(hp.get_normalized_local(&"node2"), Some(OrderedFloat::from(0.67)));
+    /// ```
+    pub fn set_normalized_precision(&mut self, decimals: u32) 
+    where
+        V: ToPrimitive + NumCast
+    {
+        self.rounding = Some((decimals, decimal_rounding(decimals)));
+        self.normalize_local();
+        self.normalize_global();
+    }
+
+    /// Returns the number of decimal places normalized values are 
+    /// rounded to, `None` if they are not rounded
+    pub fn normalized_precision(&self) -> Option<u32> {
+        self.rounding.as_ref().map(|(decimals, _)| *decimals)
+    }
+
+    /// Replaces each peer's normalized local trust with its percentile 
+    /// rank among all peers, see `NormalizationMode::Rank`. Unless the 
+    /// mode is set to `Rank`, the next update normalizes by sum again.
+    pub fn rank_normalize_local(&mut self) {
+        let entries: Vec<(K, V)> = self.local_trust.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        let ranks = self.round_map(percentile_ranks(entries).into_iter().collect());
+        self.normalized_local_trust.extend(ranks);
+        self.local_dirty = false;
+    }
+
+    /// Replaces each peer's normalized global trust with its percentile 
+    /// rank among all peers, see `NormalizationMode::Rank`
+    pub fn rank_normalize_global(&mut self) {
+        let entries: Vec<(K, V)> = self.global_trust.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        let ranks = self.round_map(percentile_ranks(entries).into_iter().collect());
+        self.normalized_global_trust.extend(ranks);
+        self.global_dirty = false;
+    }
+
+    /// Normalizes the local trust map, or only marks it stale when lazy 
+    /// normalization is enabled
+    fn refresh_local(&mut self) {
+        self.local_total.take();
+        if self.lazy_normalization && self.normalization_mode == NormalizationMode::Sum {
+            self.local_dirty = true;
+        } else {
+            self.normalize_local();
+        }
+    }
+
+    /// Normalizes the global trust map, or only marks it stale when lazy 
+    /// normalization is enabled
+    fn refresh_global(&mut self) {
+        self.global_total.take();
+        if self.lazy_normalization && self.normalization_mode == NormalizationMode::Sum {
+            self.global_dirty = true;
+        } else {
+            self.normalize_global();
+        }
+    }
+
+    /// Removes a peer from the local trust map, returning its raw local 
+    /// trust if it was known, and normalizes the local trust map
+    pub fn remove_local(&mut self, key: &K) -> Option<V> {
+        let removed = self.local_trust.remove(key);
+        self.normalized_local_trust.remove(key);
+        self.last_updated.remove(key);
+        self.rate_buckets.remove(key);
+        self.refresh_local();
+        removed
+    }
+
+    /// Scales the raw local trust of every peer by 
+    /// `0.5^(elapsed / half_life)`, where `elapsed` is the time since the 
+    /// peer was last updated, so that recently updated peers decay less 
+    /// than stale ones. Each decayed peer's last update time is moved to 
+    /// now, so repeated calls compound to the same result as a single 
+    /// one. Values never decay below the floor, and the local trust map 
+    /// is normalized afterwards.
+    ///
+    /// Does nothing unless a clock is set with `with_clock`, or if 
+    /// `half_life` is zero.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use decentrust::clock::SystemClock;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+    ///     PreciseHonestPeer::new().with_clock(SystemClock)
+    /// };
+    /// hp.init_local(&"node1", 8f64.into());
+    /// hp.decay_by_age(Duration::from_secs(3600));
+    ///
+    /// assert!(hp.get_raw_local(&"node1").unwrap() <= OrderedFloat::from(8.0));
+    /// ```
+    pub fn decay_by_age(&mut self, half_life: Duration) 
+    where
+        V: ToPrimitive + NumCast
+    {
+        let now = match &self.clock {
+            Some(clock) if !half_life.is_zero() => clock.now(),
+            _ => return,
+        };
+
+        for (key, last) in self.last_updated.iter_mut() {
+            let elapsed = now.saturating_duration_since(*last);
+            *last = now;
+
+            let Some(value) = self.local_trust.get_mut(key) else { continue };
+            if *value <= self.floor {
+                continue
+            }
+
+            let factor = 0.5f64.powf(elapsed.as_secs_f64() / half_life.as_secs_f64());
+            let decayed = value.to_f64()
+                .and_then(|raw| <V as NumCast>::from(raw * factor));
+            if let Some(decayed) = decayed {
+                *value = if decayed < self.floor { self.floor } else { decayed };
+            }
+        }
+
+        self.refresh_local();
+    }
+
+    /// Decrements the raw local trust of every peer but those in 
+    /// `except` by `delta`, flooring at the configured floor, then 
+    /// normalizes once, e.g. to uniformly penalize a network after an 
+    /// attack while sparing known good peers.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node1", 5f64.into());
+    /// hp.init_local(&"node2", 1f64.into());
+    /// hp.init_local(&"node3", 5f64.into());
+    ///
+    /// hp.penalize_all_local(2f64.into(), &["node3"]);
+    ///
+    /// assert_eq!(hp.get_raw_local(&"node1"), Some(OrderedFloat::from(3.0)));
+    /// assert_eq!(hp.get_raw_local(&"node2"), Some(OrderedFloat::from(0.0)));
+    /// assert_eq!(hp.get_raw_local(&"node3"), Some(OrderedFloat::from(5.0)));
+    /// ```
+    pub fn penalize_all_local(&mut self, delta: V, except: &[K]) {
+        let floor = self.floor;
+        let penalized: Vec<K> = self.local_trust.iter_mut()
+            .filter(|(key, _)| !except.contains(key))
+            .map(|(key, value)| {
+                *value = floored_sub(*value, delta, floor);
+                key.clone()
+            })
+            .collect();
+
+        penalized.iter().for_each(|key| self.touch_local(key));
+        self.refresh_local();
+    }
+
+    /// Replaces local trust with a known normalized distribution, to 
+    /// resume from a snapshot of another node's normalized trust rather 
+    /// than replaying the raw updates that produced it. Each peer's raw 
+    /// value is set to its normalized value times `total_mass`, and the 
+    /// normalized map is set to `dist` as given, regardless of the 
+    /// normalization mode. Peers not in `dist` are removed.
+    ///
+    /// The values must each be within `[0, 1]` and sum to 1 within 
+    /// `1e-6`, otherwise a `DistributionError` carrying their sum is 
+    /// returned and nothing is modified.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_from_normalized_local(
+    ///     &[("node1", 0.25.into()), ("node2", 0.75.into())], 
+    ///     100f64.into()
+    /// ).unwrap();
+    ///
+    /// assert_eq!(hp.get_raw_local(&"node2"), Some(OrderedFloat::from(75.0)));
+    /// assert_eq!(hp.get_normalized_local(&"node2"), Some(OrderedFloat::from(0.75)));
+    /// assert!(hp.init_from_normalized_local(&[("node1", 0.5.into())], 1f64.into()).is_err());
+    /// ```
+    pub fn init_from_normalized_local(
+        &mut self, 
+        dist: &[(K, V)], 
+        total_mass: V
+    ) -> Result<(), DistributionError<V>> 
+    where
+        V: NumCast
+    {
+        let epsilon = <V as NumCast>::from(DISTRIBUTION_TOLERANCE).unwrap_or_default();
+        if !is_distribution(dist.iter().map(|(_, v)| Some(*v)), None, epsilon) {
+            let total = dist.iter().fold(V::default(), |acc, (_, v)| acc + *v);
+            return Err(DistributionError { total });
+        }
+
+        self.local_trust.clear();
+        self.normalized_local_trust.clear();
+        self.last_updated.clear();
+        for (key, normalized) in dist {
+            self.local_trust.insert(key.clone(), *normalized * total_mass);
+            self.normalized_local_trust.insert(key.clone(), *normalized);
+            self.touch_local(key);
+        }
+        self.local_dirty = false;
+
+        Ok(())
+    }
+
+    /// Removes a peer from the global trust map, returning its raw 
+    /// global trust if it was known, and normalizes the global trust map
+    pub fn remove_global(&mut self, key: &K) -> Option<V> {
+        let removed = self.global_trust.remove(key);
+        self.normalized_global_trust.remove(key);
+        self.refresh_global();
+        removed
+    }
+
+    /// Moves the raw local trust of `from` onto `to`, e.g. when a peer 
+    /// rotates its identity key, adding it to any trust `to` already 
+    /// has. `from` is removed and the local trust map is normalized. 
+    /// Does nothing if `from` is not in the local trust map. Reported 
+    /// local trust vectors, pre-trust and attributions stay keyed by 
+    /// `from`.
+    ///
     /// ```
     /// use decentrust::precise::PreciseHonestPeer;
     /// use decentrust::honest_peer::HonestPeer;
     /// use ordered_float::OrderedFloat;
     ///
-    /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = {
-    ///     PreciseHonestPeer::new()
-    /// };
-    /// 
-    /// assert_eq!(0, hp.local_raw_len());
-    /// assert_eq!(0, hp.global_raw_len());
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"old", 3f64.into());
+    /// hp.init_local(&"new", 1f64.into());
+    ///
+    /// hp.transfer_local(&"old", &"new");
+    ///
+    /// assert_eq!(hp.get_raw_local(&"new"), Some(OrderedFloat::from(4.0)));
+    /// assert_eq!(hp.get_raw_local(&"old"), None);
     /// ```
-    pub fn new() -> Self {
-        PreciseHonestPeer { 
-            local_trust: HashMap::new(), 
-            global_trust: HashMap::new(),
-            normalized_local_trust: HashMap::new(),
-            normalized_global_trust: HashMap::new(),
-        }
+    pub fn transfer_local(&mut self, from: &K, to: &K) {
+        let Some(moved) = self.local_trust.remove(from) else {
+            return;
+        };
+
+        self.normalized_local_trust.remove(from);
+        self.last_updated.remove(from);
+        let current = self.local_trust.get(to).copied().unwrap_or_default();
+        self.local_trust.insert(to.clone(), self.clamped(saturating_add(current, moved)));
+        self.touch_local(to);
+        self.refresh_local();
+    }
+
+    /// Moves the raw global trust of `from` onto `to`, adding it to any 
+    /// trust `to` already has, see `transfer_local`
+    pub fn transfer_global(&mut self, from: &K, to: &K) {
+        let Some(moved) = self.global_trust.remove(from) else {
+            return;
+        };
+
+        self.normalized_global_trust.remove(from);
+        let current = self.global_trust.get(to).copied().unwrap_or_default();
+        self.global_trust.insert(to.clone(), self.clamped(saturating_add(current, moved)));
+        self.refresh_global();
     }
 
+    /// Shrinks the capacity of the raw and normalized trust maps as 
+    /// much as possible, reclaiming memory held for peers that have 
+    /// since been removed.
     ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
     ///
-    pub fn bucketize_local<'a, B>(
-        &'a self, 
-        bucketizer: B
-    ) -> impl Iterator<Item = (K, usize)> + '_
-    where 
-        B: BucketizeSingle<V> + 'a
-    {
-        self.local_trust.iter().map(move |(k, v)| {
-            let bucketed = bucketizer.bucketize(v);
-            (k.clone(), bucketed)
-        })
+    /// let mut hp: PreciseHonestPeer<usize, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// (0..1000).for_each(|i| hp.init_local(&i, 1f64.into()));
+    /// (0..1000).for_each(|i| { hp.remove_local(&i); });
+    ///
+    /// let before = hp.capacity();
+    /// hp.shrink_to_fit();
+    ///
+    /// assert!(hp.capacity() < before);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.local_trust.shrink_to_fit();
+        self.global_trust.shrink_to_fit();
+        self.normalized_local_trust.shrink_to_fit();
+        self.normalized_global_trust.shrink_to_fit();
+        self.local_trust_matrix.shrink_to_fit();
+        self.normalized_local_trust_matrix.shrink_to_fit();
+    }
+
+    /// Returns the capacity of the raw local trust map, i.e. how many 
+    /// peers it can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.local_trust.capacity()
     }
 
+    /// Returns the keys of every peer in the raw local trust map in 
+    /// ascending order. Unlike `local_keys`, which follows the map's 
+    /// iteration order, the result is the same across runs, which keeps 
+    /// exports, golden tests and gossiped key lists reproducible.
     ///
-    pub fn bucketize_normalized_local<'a, B>(
-        &'a self, 
-        bucketizer: B
-    ) -> impl Iterator<Item = (K, usize)> + '_
-    where 
-        B: BucketizeSingle<V> + 'a
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node3", 1f64.into());
+    /// hp.init_local(&"node1", 1f64.into());
+    /// hp.init_local(&"node2", 1f64.into());
+    ///
+    /// assert_eq!(hp.local_keys_sorted(), vec!["node1", "node2", "node3"]);
+    /// ```
+    pub fn local_keys_sorted(&self) -> Vec<K> 
+    where
+        K: Ord
     {
-        self.normalized_local_trust.iter().map(move |(k, v)| {
-            let bucketed = bucketizer.bucketize(v);
-            (k.clone(), bucketed)
-
-        })
+        let mut keys: Vec<K> = self.local_trust.keys().cloned().collect();
+        keys.sort();
+        keys
     }
 
-    pub fn bucketize_global<'a, B>(
-        &'a self, 
-        bucketizer: B
-    ) -> impl Iterator<Item = (K, usize)> + '_
-    where 
-        B: BucketizeSingle<V> + 'a
+    /// Returns the keys of every peer in the raw global trust map in 
+    /// ascending order, see `local_keys_sorted`
+    pub fn global_keys_sorted(&self) -> Vec<K> 
+    where
+        K: Ord
     {
-        self.global_trust.iter().map(move |(k, v)| {
-            let bucketed = bucketizer.bucketize(v);
-            (k.clone(), bucketed)
+        let mut keys: Vec<K> = self.global_trust.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
 
-        })
+    /// Encodes the raw trust maps, the raw local trust matrix, the 
+    /// pre-trusted peers, the attributed contributions if attribution is 
+    /// enabled, and the floor, damping, normalization mode, strict mode 
+    /// and newcomer default settings in a compact binary format, 
+    /// prefixed with the `codec::FORMAT_VERSION` tag. Maps are 
+    /// written sorted by key, so equal instances always encode to the 
+    /// same bytes. The weight strategy, clock, lazy normalization 
+    /// setting, normalization target, precision, rate limit and bounds 
+    /// are not encoded. See `from_bytes`.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// hp.init_local(&"node1".to_string(), 3f64.into());
+    /// hp.init_local(&"node2".to_string(), 1f64.into());
+    ///
+    /// let bytes = hp.to_bytes();
+    /// let decoded = PreciseHonestPeer::<String, OrderedFloat<f64>>::from_bytes(&bytes).unwrap();
+    ///
+    /// assert_eq!(decoded.get_normalized_local(&"node1".to_string()), Some(OrderedFloat::from(0.75)));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> 
+    where
+        K: ByteCodec,
+        V: ByteCodec
+    {
+        let mut out = vec![FORMAT_VERSION];
+        self.local_trust.encode(&mut out);
+        self.global_trust.encode(&mut out);
+        self.local_trust_matrix.encode(&mut out);
+        self.pretrusted.encode(&mut out);
+        self.floor.encode(&mut out);
+        self.damping.encode(&mut out);
+        self.normalization_mode.encode(&mut out);
+        self.strict.encode(&mut out);
+        self.newcomer_default.encode(&mut out);
+        self.attribution.is_some().encode(&mut out);
+        if let Some(attribution) = &self.attribution {
+            attribution.encode(&mut out);
+        }
+        out
     }
 
-    pub fn bucketize_normalized_global<'a, B>(
-        &'a self, 
-        bucketizer: B
-    ) -> impl Iterator<Item = (K, usize)> + '_
-    where 
-        B: BucketizeSingle<V> + 'a
+    /// Decodes an instance encoded by `to_bytes`, renormalizing every 
+    /// map. Settings that are not encoded keep their defaults. Fails if 
+    /// the version is unknown or the input is truncated, malformed or 
+    /// has trailing bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> 
+    where
+        K: ByteCodec,
+        V: ByteCodec
     {
-        self.normalized_global_trust.iter().map(move |(k, v)| {
-            let bucketed = bucketizer.bucketize(v);
-            (k.clone(), bucketed)
-        })
+        let mut input = bytes;
+        decode_version(&mut input)?;
+        let local_trust = HashMap::<K, V>::decode(&mut input)?;
+        let global_trust = HashMap::<K, V>::decode(&mut input)?;
+        let matrix = HashMap::<K, HashMap<K, V>>::decode(&mut input)?;
+        let pretrusted = HashMap::<K, V>::decode(&mut input)?;
+        let floor = V::decode(&mut input)?;
+        let damping = V::decode(&mut input)?;
+        let normalization_mode = NormalizationMode::decode(&mut input)?;
+        let strict = bool::decode(&mut input)?;
+        let newcomer_default = V::decode(&mut input)?;
+        let attribution = match bool::decode(&mut input)? {
+            true => Some(HashMap::<K, HashMap<K, Contribution<V>>>::decode(&mut input)?),
+            false => None,
+        };
+        expect_end(input)?;
+
+        let mut hp = Self::new()
+            .with_floor(floor)
+            .with_damping(damping)
+            .with_newcomer_default(newcomer_default);
+        hp.local_trust = local_trust;
+        hp.global_trust = global_trust;
+        hp.pretrusted = pretrusted;
+        hp.normalization_mode = normalization_mode;
+        hp.strict = strict;
+        hp.attribution = attribution;
+        matrix.into_iter().for_each(|(owner, vector)| hp.set_local_vector(owner, vector));
+        hp.normalize_local();
+        hp.normalize_global();
+
+        Ok(hp)
     }
 }
 
@@ -160,7 +1846,8 @@ where
         + Sub<Output = V> 
         + Copy 
         + Default 
-        + Bounded 
+        + Bounded
+        + One
         + Hash 
         + Ord
 
@@ -188,22 +1875,27 @@ where
     ///
     /// ```
     fn init_local(&mut self, key: &Self::Key, init_value: Self::Value) {
-        self.local_trust.insert(key.clone(), init_value);
-        self.normalize_local();
+        self.local_trust.insert(key.clone(), self.clamped(init_value));
+        self.touch_local(key);
+        self.refresh_local();
     }
 
     /// Updates the local trust score of a peer, and normalizes 
     /// the trust score map.
     ///
+    /// Decrements floor at the configured floor, `V::default()` unless 
+    /// set with `set_floor`, and unknown peers start from the newcomer 
+    /// default, `V::default()` unless set with `set_newcomer_default`. This intentionally mirrors 
+    /// the flooring of `CountMinSketch::decrement`, so that 
+    /// `PreciseHonestPeer` and `LightHonestPeer` behave the same given 
+    /// the same sequence of updates.
+    ///
     /// ```
     /// use decentrust::precise::PreciseHonestPeer;
     /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use decentrust::util::trust_approx_eq;
     /// use ordered_float::OrderedFloat;
     ///
-    /// fn equal_floats(a: f64, b: f64, epsilon: f64) -> bool {
-    ///     (a - b).abs() < epsilon
-    /// }
-    ///
     /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = {
     ///     PreciseHonestPeer::new()
     /// };
@@ -217,62 +1909,73 @@ where
     /// let local_total_trust = 0.01 + 0.01 + 0.05;
     /// let node_1_local_trust: OrderedFloat<f64> = (0.06 / local_total_trust).into();
     /// let node_2_local_trust: OrderedFloat<f64> = (0.01 / local_total_trust).into();
+    /// let epsilon = OrderedFloat::from(1e-9f64);
     ///
-    /// // Since the init calls are the first in this instance,
-    /// // normalization will necessarily mean that they equal 100% of the 
-    /// // weight of the scores.
-    /// if let Some(val) = hp.get_normalized_local(&"node1".to_string()) {
-    ///     assert!(equal_floats(
-    ///         val.into_inner(), 
-    ///         node_1_local_trust.into_inner(), 
-    ///         1e-9f64)
-    ///     );
-    /// }
+    /// assert_eq!(
+    ///     hp.get_normalized_local_approx(&"node1".to_string(), node_1_local_trust, epsilon),
+    ///     Some(true)
+    /// );
     ///
     /// if let Some(val) = hp.get_raw_local(&"node1".to_string()) {
-    ///     assert!(equal_floats(val.into_inner(), 0.06f64, 1e-9f64));
+    ///     assert!(trust_approx_eq(val, 0.06f64.into(), epsilon));
     /// }
     ///
-    /// if let Some(val) = hp.get_normalized_local(&"node2".to_string()) {
-    ///     assert!(equal_floats(
-    ///         val.into_inner(), 
-    ///         node_2_local_trust.into_inner(),
-    ///         1e-9f64)
-    ///     );
-    /// }
+    /// assert_eq!(
+    ///     hp.get_normalized_local_approx(&"node2".to_string(), node_2_local_trust, epsilon),
+    ///     Some(true)
+    /// );
     ///
     /// if let Some(val) = hp.get_raw_local(&"node2".to_string()) {
-    ///     assert!(equal_floats(val.into_inner(), 0.01f64, 1e-9f64));
+    ///     assert!(trust_approx_eq(val, 0.01f64.into(), epsilon));
     /// }
     ///
     /// ```
+    ///
+    /// Increments and decrements can be mixed freely, a decrement larger 
+    /// than the current value floors the peer at zero:
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// let node1 = "node1".to_string();
+    /// let node2 = "node2".to_string();
+    ///
+    /// hp.init_local(&node1, 2f64.into());
+    /// hp.init_local(&node2, 2f64.into());
+    ///
+    /// hp.update_local(&node1, 4f64.into(), Update::Increment);
+    /// hp.update_local(&node2, 1f64.into(), Update::Decrement);
+    ///
+    /// assert_eq!(hp.get_raw_local(&node1), Some(OrderedFloat::from(6.0)));
+    /// assert_eq!(hp.get_raw_local(&node2), Some(OrderedFloat::from(1.0)));
+    /// assert_eq!(hp.get_normalized_local(&node1), Some(OrderedFloat::from(6.0 / 7.0)));
+    ///
+    /// hp.update_local(&node2, 5f64.into(), Update::Decrement);
+    ///
+    /// assert_eq!(hp.get_raw_local(&node2), Some(OrderedFloat::from(0.0)));
+    /// assert_eq!(hp.get_normalized_local(&node1), Some(OrderedFloat::from(1.0)));
+    /// ```
     fn update_local(
         &mut self, 
         key: &Self::Key, 
         trust_delta: Self::Value, 
         update: Update
     ) {
-        match update {
-            Update::Increment => {
-                if let Some(trust_score) = self.local_trust.get_mut(key) {
-                    *trust_score += trust_delta
-                } else {
-                    self.local_trust.insert(key.clone(), trust_delta);
-                }
-            },
-            Update::Decrement => {
-                if let Some(trust_score) = self.local_trust.get_mut(key) {
-                    if trust_delta > *trust_score {
-                        *trust_score = V::default();
-                    } else {
-                        *trust_score -= trust_delta
-                    }
-                } else {
-                    self.local_trust.insert(key.clone(), trust_delta);
-                }
-            }
+        if self.strict && !self.local_trust.contains_key(key) {
+            return
         }
-        self.normalize_local()
+
+        let current = self.local_trust.get(key).copied().unwrap_or(self.newcomer_default);
+        let updated = match update {
+            Update::Increment => saturating_add(current, trust_delta),
+            Update::Decrement => floored_sub(current, trust_delta, self.floor),
+        };
+        self.local_trust.insert(key.clone(), self.clamped(updated));
+        self.touch_local(key);
+        self.refresh_local()
     }
 
     /// gets a value from the raw local trust map
@@ -286,13 +1989,21 @@ where
 
     /// gets a value from the normalized local trust map
     fn get_normalized_local(&self, key: &Self::Key) -> Option<Self::Value> {
+        if self.local_dirty {
+            let total = *self.local_total.get_or_init(|| map_total(&self.local_trust));
+            return normalized_value(&self.local_trust, key, total, self.normalization_target)
+                .map(|value| self.rounded(value));
+        }
+
         if let Some(val) = self.normalized_local_trust.get(key) {
             return Some(*val)
         }
         return None
     }
 
-    /// Initialize the global trust score of a newly discovered peer 
+    /// Initialize the global trust score of a newly discovered peer, 
+    /// weighted by the reporting sender's normalized local trust. 
+    /// Nothing is initialized if the sender is unknown.
     ///
     /// ```
     /// use decentrust::precise::PreciseHonestPeer;
@@ -304,52 +2015,62 @@ where
     /// };
     /// 
     /// // Insert and normalize initial trust scores
-    /// hp.init_global(&"node1".to_string(), 0.01f64.into());
+    /// hp.init_local(&"node1".to_string(), 0.01f64.into());
+    /// hp.init_global(&"node1".to_string(), &"node2".to_string(), 0.01f64.into());
     ///
     /// assert_eq!(hp.global_raw_len(), 1);
     /// assert_eq!(hp.global_normalized_len(), 1);
     ///
+    /// hp.init_global(&"node3".to_string(), &"node4".to_string(), 0.01f64.into());
+    ///
+    /// assert_eq!(hp.global_raw_len(), 1);
     /// ```
     fn init_global(&mut self, sender: &Self::Key, key: &Self::Key, init_value: Self::Value) {
         let sender_trust = self.get_normalized_local(sender);
         if let Some(sender_trust) = sender_trust {
-            let weighted_init = init_value * sender_trust;
+            let weighted_init = self.clamped(self.weight_strategy.weight(init_value, sender_trust));
             self.global_trust.insert(key.clone(), weighted_init);
-            self.normalize_global()
+            self.record_contribution(sender, key, weighted_init, &Update::Increment);
+            self.refresh_global()
         }
     }
 
     /// Updates a global trust value for a given peer and normalizes
-    /// the normalized global trust map.
+    /// the normalized global trust map. Like `update_local`, decrements 
+    /// floor at the configured floor for parity with `LightHonestPeer`.
     /// ```
     /// use decentrust::precise::PreciseHonestPeer;
     /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use decentrust::util::trust_approx_eq;
     /// use ordered_float::OrderedFloat;
     ///
     /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = PreciseHonestPeer::new();
     /// 
-    /// // Insert and normalize initial trust scores
-    /// hp.init_global(&"node1".to_string(), 0.01f64.into());
-    /// hp.init_global(&"node2".to_string(), 0.01f64.into());
+    /// // Both senders hold half of the normalized local trust
+    /// hp.init_local(&"node1".to_string(), 1f64.into());
+    /// hp.init_local(&"node2".to_string(), 1f64.into());
+    ///
+    /// // Insert and normalize initial trust scores, weighted by 0.5
+    /// hp.init_global(&"node1".to_string(), &"node1".to_string(), 0.02f64.into());
+    /// hp.init_global(&"node1".to_string(), &"node2".to_string(), 0.02f64.into());
     ///
     /// hp.update_global(
     ///     &"node2".to_string(), 
     ///     &"node1".to_string(), 
-    ///     0.02f64.into(), 
+    ///     0.04f64.into(), 
     ///     Update::Increment
     /// );
     ///
     /// let global_total_trust = 0.01 + 0.01 + 0.02;
     /// let node_1_global_trust: OrderedFloat<f64> = (0.03 / global_total_trust).into();
     /// let node_2_global_trust: OrderedFloat<f64> = (0.01 / global_total_trust).into();
+    /// let epsilon = OrderedFloat::from(1e-9f64);
     ///
-    /// // Since the init calls are the first in this instance,
-    /// // normalization will necessarily mean that they equal 100% of the 
-    /// // weight of the scores.
-    /// println!("{:?}", hp.get_normalized_global(&"node1".to_string())); 
-    /// println!("{:?}", hp.get_raw_global(&"node1".to_string())); 
-    /// println!("{:?}", hp.get_normalized_global(&"node2".to_string())); 
-    /// println!("{:?}", hp.get_raw_global(&"node2".to_string())); 
+    /// let node_1_actual = hp.get_normalized_global(&"node1".to_string()).unwrap();
+    /// let node_2_actual = hp.get_normalized_global(&"node2".to_string()).unwrap();
+    ///
+    /// assert!(trust_approx_eq(node_1_actual, node_1_global_trust, epsilon));
+    /// assert!(trust_approx_eq(node_2_actual, node_2_global_trust, epsilon));
     /// ```
     fn update_global(
         &mut self, 
@@ -360,28 +2081,151 @@ where
     ) {
         let sender_trust = self.get_normalized_local(sender);
         if let Some(sender_trust) = sender_trust {
-            let weighted_delta = trust_delta * sender_trust;
-            match update {
-                Update::Increment => {
-                    if let Some(trust_score) = self.global_trust.get_mut(key) {
-                        *trust_score += weighted_delta;
-                    } else {
-                        self.global_trust.insert(key.clone(), weighted_delta);
-                    }
-                },
-                Update::Decrement => {
-                    if let Some(trust_score) = self.global_trust.get_mut(key) {
-                        if weighted_delta > *trust_score {
-                            *trust_score = V::default();
-                        } else {
-                            *trust_score -= weighted_delta;
-                        }
-                    }
+            if !self.within_rate_limit(sender) {
+                return;
+            }
+
+            let weighted_delta = self.weight_strategy.weight(trust_delta, sender_trust);
+            let applied = self.apply_global_delta(key, weighted_delta, update.clone());
+            self.record_contribution(sender, key, applied, &update);
+        }
+
+        self.refresh_global();
+    }
+
+    /// Applies reports about a given peer from several senders at once. 
+    /// Each report's delta is weighted by its sender's normalized local 
+    /// trust using the weight strategy, reports from unknown or rate 
+    /// limited senders are ignored, and the summed weighted delta is 
+    /// applied once before normalizing a single time.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    ///
+    /// hp.init_local(&"node1".to_string(), 3f64.into());
+    /// hp.init_local(&"node2".to_string(), 1f64.into());
+    ///
+    /// let reports = vec![
+    ///     ("node1".to_string(), OrderedFloat::from(4.0)),
+    ///     ("node2".to_string(), OrderedFloat::from(4.0)),
+    /// ];
+    /// hp.update_global_multi(&reports, &"node3".to_string(), Update::Increment);
+    ///
+    /// assert_eq!(hp.get_raw_global(&"node3".to_string()), Some(OrderedFloat::from(4.0)));
+    /// ```
+    fn update_global_multi(
+        &mut self, 
+        reports: &[(Self::Key, Self::Value)], 
+        key: &Self::Key, 
+        update: Update
+    ) {
+        let known: Vec<(K, V)> = reports.iter()
+            .filter_map(|(sender, trust_delta)| {
+                self.get_normalized_local(sender).map(|sender_trust| {
+                    (sender.clone(), self.weight_strategy.weight(*trust_delta, sender_trust))
+                })
+            })
+            .collect();
+        let weighted_deltas: Vec<(K, V)> = known.into_iter()
+            .filter(|(sender, _)| self.within_rate_limit(sender))
+            .collect();
+
+        if !weighted_deltas.is_empty() {
+            let weighted_delta = weighted_deltas.iter()
+                .fold(V::default(), |acc, (_, delta)| acc + *delta);
+            let applied = self.apply_global_delta(key, weighted_delta, update.clone());
+
+            // a floored decrement removes less than requested, so each 
+            // sender is attributed its share of what was removed
+            if self.attribution.is_some() && weighted_delta != V::default() {
+                for (sender, delta) in weighted_deltas {
+                    let share = applied * delta / weighted_delta;
+                    self.record_contribution(&sender, key, share, &update);
                 }
             }
         }
 
-        self.normalize_global();
+        self.refresh_global();
+    }
+
+    /// Initializes local trust values for many newly discovered peers 
+    /// at once, e.g. from a known peer directory, and normalizes the 
+    /// local trust map a single time.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    ///
+    /// hp.init_local_batch(vec![
+    ///     ("node1".to_string(), OrderedFloat::from(1.0)),
+    ///     ("node2".to_string(), OrderedFloat::from(3.0)),
+    /// ]);
+    ///
+    /// assert_eq!(hp.get_normalized_local(&"node2".to_string()), Some(OrderedFloat::from(0.75)));
+    /// ```
+    fn init_local_batch(&mut self, peers: impl IntoIterator<Item = (Self::Key, Self::Value)>) {
+        for (key, value) in peers {
+            self.touch_local(&key);
+            self.local_trust.insert(key, self.clamped(value));
+        }
+        self.refresh_local();
+    }
+
+    /// Initializes global trust values for many newly discovered peers 
+    /// reported by a single sender, each weighted by the sender's 
+    /// normalized local trust, and normalizes the global trust map a 
+    /// single time. Nothing is initialized if the sender is unknown.
+    fn init_global_batch(
+        &mut self, 
+        sender: &Self::Key, 
+        peers: impl IntoIterator<Item = (Self::Key, Self::Value)>
+    ) {
+        if let Some(sender_trust) = self.get_normalized_local(sender) {
+            for (key, value) in peers {
+                let weighted_init = self.clamped(self.weight_strategy.weight(value, sender_trust));
+                self.record_contribution(sender, &key, weighted_init, &Update::Increment);
+                self.global_trust.insert(key, weighted_init);
+            }
+            self.refresh_global();
+        }
+    }
+
+    /// Sets the raw local trust of a given peer to exactly `value`, 
+    /// discarding everything accumulated for it, and normalizes the 
+    /// local trust map. Unknown peers are inserted.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp: PreciseHonestPeer<String, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    ///
+    /// hp.init_local(&"node1".to_string(), 5f64.into());
+    /// hp.update_local(&"node1".to_string(), 20f64.into(), Update::Increment);
+    /// hp.reset_local(&"node1".to_string(), 1f64.into());
+    ///
+    /// assert_eq!(hp.get_raw_local(&"node1".to_string()), Some(OrderedFloat::from(1.0)));
+    /// ```
+    fn reset_local(&mut self, key: &Self::Key, value: Self::Value) {
+        self.local_trust.insert(key.clone(), self.clamped(value));
+        self.touch_local(key);
+        self.refresh_local();
+    }
+
+    /// Sets the raw global trust of a given peer to exactly `value`, 
+    /// without weighting it by any sender, and normalizes the global 
+    /// trust map. Unknown peers are inserted.
+    fn reset_global(&mut self, key: &Self::Key, value: Self::Value) {
+        self.global_trust.insert(key.clone(), self.clamped(value));
+        self.refresh_global();
     }
 
     /// gets the raw global trust value for a given peer
@@ -396,6 +2240,12 @@ where
 
     /// gets the normalized global trust value for a given peer
     fn get_normalized_global(&self, key: &Self::Key) -> Option<Self::Value> {
+        if self.global_dirty {
+            let total = *self.global_total.get_or_init(|| map_total(&self.global_trust));
+            return normalized_value(&self.global_trust, key, total, self.normalization_target)
+                .map(|value| self.rounded(value));
+        }
+
         if let Some(val) = self.normalized_global_trust.get(key) {
             return Some(*val)
         }
@@ -410,7 +2260,7 @@ where
 
     /// returns the entire normalized local trust map from the `PreciseHonestPeer` instance
     fn get_normalized_local_map(&self) -> Self::Map {
-        self.normalized_local_trust.clone()
+        self.normalized_local_view().into_owned()
     }
 
     /// returns the entire raw global trust map from the `PreciseHonestPeer` instance
@@ -421,35 +2271,77 @@ where
 
     /// returns the entire normalized global trust map from the `PreciseHonestPeer` instance
     fn get_normalized_global_map(&self) -> Self::Map {
-        self.normalized_global_trust.clone()
+        self.normalized_global_view().into_owned()
     }
 
     /// normalizes all the local trust values after a new entry or update 
     /// to an existing entry, and saves them in the `normalized_local_trust` 
     /// map.
     fn normalize_local(&mut self) {
-        let total_trust = self.local_trust.values()
-            .cloned()
-            .fold(V::default(), |acc, x| acc + x);
+        if self.normalization_mode == NormalizationMode::Rank {
+            return self.rank_normalize_local();
+        }
 
-        self.local_trust.iter().for_each(|(k, v)| {
-            let normalized_trust = *v / total_trust;
-            self.normalized_local_trust.insert(k.clone(), normalized_trust);
-        });
+        let normalized = self.round_map(normalize_map(&self.local_trust, self.normalization_target));
+        self.normalized_local_trust.extend(normalized);
+        self.local_dirty = false;
     }
 
     /// normalizes all the global trust values after a new entry of update 
     /// to an existing entry and saves them in the `normalized_global_trust`
     /// map
     fn normalize_global(&mut self) {
-        let total_trust = self.global_trust.values()
-            .cloned()
-            .fold(V::default(), |acc, x| acc + x);
+        if self.normalization_mode == NormalizationMode::Rank {
+            return self.rank_normalize_global();
+        }
+
+        let normalized = self.round_map(normalize_map(&self.global_trust, self.normalization_target));
+        self.normalized_global_trust.extend(normalized);
+        self.global_dirty = false;
+    }
+
+    /// returns the keys of every peer in the raw local trust map
+    fn local_keys(&self) -> Vec<Self::Key> {
+        self.local_trust.keys().cloned().collect()
+    }
+
+    /// returns the keys of every peer in the raw global trust map
+    fn global_keys(&self) -> Vec<Self::Key> {
+        self.global_trust.keys().cloned().collect()
+    }
+
+    /// returns the (min, max) bounds updates clamp raw trust to, the 
+    /// bounds of `V` itself unless created with `new_with_bounds`
+    fn value_bounds(&self) -> (Self::Value, Self::Value) {
+        (self.min, self.max)
+    }
+
+    /// returns `true`, every value is stored exactly in a `HashMap`
+    fn is_exact(&self) -> bool {
+        true
+    }
+
+    /// returns the sum normalized values are scaled to, `None` if they 
+    /// sum to 1
+    fn normalization_target(&self) -> Option<Self::Value> {
+        self.normalization_target
+    }
 
-        self.global_trust.iter_mut().for_each(|(k, v)| {
-            let normalized_trust = *v / total_trust;
-            self.normalized_global_trust.insert(k.clone(), normalized_trust);
-        });
+    /// Only yields the given keys present in the raw local map, and 
+    /// records the buckets seen like the inherent `bucketize_local`
+    fn bucketize_local_keys<'a, B>(
+        &'a self, 
+        keys: impl Iterator<Item = Self::Key> + 'a, 
+        bucketizer: B
+    ) -> impl Iterator<Item = (Self::Key, usize)> + 'a
+    where 
+        B: BucketizeSingle<Self::Value> + 'a
+    {
+        keys.filter_map(move |key| {
+            let value = self.local_trust.get(&key)?;
+            let bucketed = self.bucket_tracker.observe(bucketizer.bucketize(value));
+            Some((key, bucketed))
+        })
     }
 
     /// returns the number of key, value pairs in the raw local trust map 
@@ -459,6 +2351,10 @@ where
 
     /// returns the number of key, value pairs in the normalized local trust map 
     fn local_normalized_len(&self) -> usize {
+        if self.local_dirty {
+            return self.local_trust.len();
+        }
+
         self.normalized_local_trust.len()
     }
 
@@ -469,6 +2365,97 @@ where
 
     /// returns the number of key, value pairs in the normalized global trust map 
     fn global_normalized_len(&self) -> usize {
+        if self.global_dirty {
+            return self.global_trust.len();
+        }
+
         self.normalized_global_trust.len()
     }
 }
+
+/// Divides every value of a raw trust map by the map's total, or gives 
+/// every value an equal share if the total is zero
+fn normalize_map<K, V>(raw: &HashMap<K, V>, target: Option<V>) -> HashMap<K, V> 
+where
+    K: Eq + Hash + Clone,
+    V: AddAssign + SubAssign + Add<Output = V> + Mul<Output = V> + Div<Output = V> 
+        + PartialOrd + Copy + Default + Bounded + One
+{
+    let total_trust = raw.values()
+        .cloned()
+        .fold(V::default(), |acc, x| acc + x);
+
+    if total_trust == V::default() && !raw.is_empty() {
+        let share = uniform_share(raw.len(), target);
+        return raw.keys().map(|k| (k.clone(), share)).collect();
+    }
+
+    raw.iter()
+        .map(|(k, v)| (k.clone(), scale_to_target(*v, total_trust, target)))
+        .collect()
+}
+
+/// Sums every value of a raw trust map
+fn map_total<K, V: Add<Output = V> + Copy + Default>(raw: &HashMap<K, V>) -> V {
+    raw.values().fold(V::default(), |acc, x| acc + *x)
+}
+
+/// Normalizes a single value of a raw trust map by the map's total, 
+/// `total_trust`, see `normalize_map`
+fn normalized_value<K, V>(
+    raw: &HashMap<K, V>, 
+    key: &K, 
+    total_trust: V, 
+    target: Option<V>
+) -> Option<V> 
+where
+    K: Eq + Hash,
+    V: AddAssign + SubAssign + Add<Output = V> + Mul<Output = V> + Div<Output = V> 
+        + PartialOrd + Copy + Default + Bounded + One
+{
+    let value = *raw.get(key)?;
+
+    if total_trust == V::default() {
+        return Some(uniform_share(raw.len(), target));
+    }
+
+    Some(scale_to_target(value, total_trust, target))
+}
+
+/// Returns an equal share of `target`, or of 1 if there is none, for 
+/// each of `count` peers. `V` has no conversion from `usize`, so the 
+/// count is built up by adding one at a time, saturating at 
+/// `V::max_value()`. Integer values truncate the share to 0 unless the 
+/// target is at least `count`.
+fn uniform_share<V>(count: usize, target: Option<V>) -> V 
+where
+    V: AddAssign + SubAssign + Div<Output = V> + PartialOrd + Copy + Default + Bounded + One
+{
+    let one = V::one();
+    let count = (0..count).fold(V::default(), |acc, _| saturating_add(acc, one));
+    target.unwrap_or(one) / count
+}
+
+/// Divides a value by its map's total, scaled so that the values of the 
+/// map sum to `target`, or to 1 if there is none
+fn scale_to_target<V>(value: V, total: V, target: Option<V>) -> V 
+where
+    V: Mul<Output = V> + Div<Output = V> + PartialOrd + Copy + Default + Bounded
+{
+    match target {
+        Some(target) => scaled_share(value, total, target),
+        None => value / total,
+    }
+}
+
+/// Iterates over owned entries of a borrowed or computed trust map
+fn view_entries<'a, K, V>(view: Cow<'a, HashMap<K, V>>) -> Box<dyn Iterator<Item = (K, V)> + 'a> 
+where
+    K: Clone,
+    V: Copy
+{
+    match view {
+        Cow::Borrowed(map) => Box::new(map.iter().map(|(k, v)| (k.clone(), *v))),
+        Cow::Owned(map) => Box::new(map.into_iter()),
+    }
+}
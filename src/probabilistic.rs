@@ -1,10 +1,20 @@
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
+use std::sync::Arc;
 use std::ops::{AddAssign, DivAssign, SubAssign, Add, Mul, Div, Sub};
 use buckets::bucketize::BucketizeSingle;
-use num_traits::Bounded;
+use num_traits::{Bounded, NumCast, One, ToPrimitive};
 use std::marker::PhantomData;
-use crate::cms::CountMinSketch;
-use crate::honest_peer::{HonestPeer, Update};
+use crate::cms::{CountMinSketch, DEFAULT_WIDTH, DEFAULT_DEPTH};
+use crate::precise::PreciseHonestPeer;
+use crate::honest_peer::{HonestPeer, Update, NormalizationMode, TrustSummary, local_summary};
+use crate::util::{
+    nearest_rank_quantile, bucket_histogram, floored_sub, percentile_ranks, decimal_rounding, 
+    BucketTracker, RecencyIndex, Rounding
+};
+use crate::weight::{WeightStrategy, Multiplicative};
+use crate::error::{MergeError, UnknownPeerError, CmsParamError};
+use crate::bloom::BloomFilter;
 use std::fmt::Debug;
 
 /// A struct to track local and global trust of peers in a 
@@ -13,15 +23,65 @@ use std::fmt::Debug;
 /// struct witnesses trustworthy or malicious behaviours by a peer 
 /// respectively. 
 ///
+/// The normalized sketches are never incremented directly, their 
+/// matrices are overwritten with values derived from the raw sketches 
+/// on every normalization. This only lines up if each raw sketch and 
+/// its normalized counterpart hash items identically, so all four 
+/// sketches are always cloned from a single seeded `CountMinSketch`.
+///
+/// Since a `CountMinSketch` cannot enumerate the items it has seen, 
+/// the keys of every peer that has been initialized or updated are 
+/// retained alongside the sketches, separately for local and global 
+/// trust. Each key set grows with every peer ever seen unless a limit 
+/// is set with `set_key_retention`, which evicts the least recently 
+/// updated keys.
+///
+/// The number of `update_local` and `update_global` calls made for each 
+/// peer is counted in a separate pair of `u64` sketches, so a score 
+/// built from one large delta can be told apart from one built from 
+/// many small deltas.
+///
+/// As with `PreciseHonestPeer`, reported deltas are weighted by a 
+/// `WeightStrategy`, `Multiplicative` unless set with 
+/// `with_weight_strategy`.
+///
+/// Normalization divides by the sum of all cells unless another 
+/// `NormalizationMode` is set with `set_normalization_mode`, scaling 
+/// each row to sum to 1 unless another target is set with 
+/// `set_normalization_target`, and rounding every cell once a precision 
+/// is set with `set_normalized_precision`.
+///
+/// Integer values truncate every normalized value below 1 to 0, so 
+/// integer stores need a normalization target. `u32` cells take half 
+/// the memory of `OrderedFloat<f64>` ones; with a target of `10_000` 
+/// normalized values are in basis points of the row total, as long as 
+/// no raw value times the target exceeds `u32::MAX`. See 
+/// `LightHonestPeerBuilder::normalization_target`.
+///
+/// Decrements stop at a configurable floor, `V::default()` unless set 
+/// with `with_floor` or `set_floor`. Only the part of a delta above the 
+/// floor is taken from the peer's cells.
+///
 /// ```
-/// use std::collections::HashMap;
+/// use std::collections::{BTreeMap, HashMap, HashSet};
 /// use std::hash::Hash;
+/// use std::sync::Arc;
+/// use decentrust::weight::WeightStrategy;
+/// use decentrust::honest_peer::NormalizationMode;
 /// use std::ops::{AddAssign, SubAssign, DivAssign, Add, Mul, Div, Sub};
 /// use std::marker::PhantomData;
 /// use decentrust::cms::CountMinSketch;
+/// use decentrust::bloom::BloomFilter;
 /// use num_traits::Bounded;
 /// use std::fmt::Debug;
 ///
+/// #[derive(Clone, Debug)]
+/// struct RecencyIndex<K> {
+///     ticks: HashMap<K, u64>,
+///     order: BTreeMap<u64, K>,
+///     next_tick: u64,
+/// }
+///
 /// pub struct LightHonestPeer<K, V> 
 /// where 
 ///     K: Eq + Hash + Clone + Debug + ToString,
@@ -44,9 +104,25 @@ use std::fmt::Debug;
 ///     global_trust: CountMinSketch<V>,
 ///     normalized_local_trust: CountMinSketch<V>,
 ///     normalized_global_trust: CountMinSketch<V>,
+///     local_keys: HashSet<K>,
+///     global_keys: HashSet<K>,
+///     key_retention: Option<usize>,
+///     local_recency: RecencyIndex<K>,
+///     global_recency: RecencyIndex<K>,
+///     local_observations: CountMinSketch<u64>,
+///     global_observations: CountMinSketch<u64>,
+///     weight_strategy: Arc<dyn WeightStrategy<V>>,
+///     floor: V,
+///     normalization_mode: NormalizationMode,
+///     normalization_target: Option<V>,
+///     rounding: Option<(u32, Arc<dyn Fn(V) -> V + Send + Sync>)>,
+///     strict: bool,
+///     seen: Option<BloomFilter>,
+///     newcomer_default: V,
 ///     id: Option<PhantomData<K>>
 /// }
 /// ```
+#[derive(Clone)]
 pub struct LightHonestPeer<K, V> 
 where 
     K: Eq + Hash + Clone + Debug + ToString,
@@ -68,6 +144,22 @@ where
     global_trust: CountMinSketch<V>,
     normalized_local_trust: CountMinSketch<V>,
     normalized_global_trust: CountMinSketch<V>,
+    local_keys: HashSet<K>,
+    global_keys: HashSet<K>,
+    key_retention: Option<usize>,
+    local_recency: RecencyIndex<K>,
+    global_recency: RecencyIndex<K>,
+    local_observations: CountMinSketch<u64>,
+    global_observations: CountMinSketch<u64>,
+    bucket_tracker: BucketTracker,
+    weight_strategy: Arc<dyn WeightStrategy<V>>,
+    floor: V,
+    normalization_mode: NormalizationMode,
+    normalization_target: Option<V>,
+    rounding: Option<(u32, Rounding<V>)>,
+    strict: bool,
+    seen: Option<BloomFilter>,
+    newcomer_default: V,
     pub id_type: Option<PhantomData<K>>
 }
 
@@ -84,12 +176,16 @@ where
     + Sub<Output = V> 
     + Copy 
     + Default 
-    + Bounded 
+    + Bounded
+    + One
     + Ord 
     + Hash
     + Debug
 {
     /// Creates a new `LightHonestPeer` struct with no peers in it.
+    /// Every sketch is sized by `CountMinSketch::default()`, see
+    /// `cms::DEFAULT_WIDTH` for the trade-off; use `new_from_bounds`
+    /// when tracking more than a few hundred peers.
     /// 
     /// ```
     /// use decentrust::probabilistic::LightHonestPeer;
@@ -102,18 +198,15 @@ where
     /// assert_eq!(0, hp.global_raw_len());
     /// ```
     pub fn new() -> Self {
-        LightHonestPeer { 
-            local_trust: CountMinSketch::<V>::default(), 
-            global_trust: CountMinSketch::<V>::default(),
-            normalized_local_trust: CountMinSketch::<V>::default(),
-            normalized_global_trust: CountMinSketch::<V>::default(),
-            id_type: None,
-        }
+        let sketch = CountMinSketch::<V>::default();
+
+        LightHonestPeer::from_sketch(sketch)
     }
 
     /// Creates a new `LightHonestPeer` instance from a given 
     /// `CountMinSketch` error bound, an overestimation probability,
-    /// and the maximum expected number of entries.
+    /// and the maximum expected number of entries. Panics if the
+    /// parameters are invalid, see `CountMinSketch::try_new_from_bounds`.
     /// ```
     /// use decentrust::probabilistic::LightHonestPeer;
     /// use decentrust::honest_peer::HonestPeer;
@@ -146,13 +239,520 @@ where
             max
         );
 
+        LightHonestPeer::from_sketch(sketch)
+    }
+
+    /// Returns a `LightHonestPeerBuilder` to configure a new instance 
+    /// with labeled settings
+    pub fn builder() -> LightHonestPeerBuilder<K, V> {
+        LightHonestPeerBuilder::new()
+    }
+
+    /// Creates a new `LightHonestPeer` with no peers in it, cloning every 
+    /// sketch from `sketch` so they all share its dimensions and seed
+    fn from_sketch(sketch: CountMinSketch<V>) -> Self {
         LightHonestPeer {
-            local_trust: sketch.clone(),
+            local_trust: sketch.clone(), 
             global_trust: sketch.clone(),
             normalized_local_trust: sketch.clone(),
             normalized_global_trust: sketch.clone(),
-            id_type: None
+            local_keys: HashSet::new(),
+            global_keys: HashSet::new(),
+            key_retention: None,
+            local_recency: RecencyIndex::default(),
+            global_recency: RecencyIndex::default(),
+            local_observations: observation_sketch(&sketch),
+            global_observations: observation_sketch(&sketch),
+            bucket_tracker: BucketTracker::default(),
+            weight_strategy: Arc::new(Multiplicative),
+            floor: V::default(),
+            normalization_mode: NormalizationMode::Sum,
+            normalization_target: None,
+            rounding: None,
+            strict: false,
+            seen: None,
+            newcomer_default: V::default(),
+            id_type: None,
+        }
+    }
+
+    /// Creates a new `LightHonestPeer` holding the raw local and global 
+    /// trust of a `PreciseHonestPeer`, sized as in `new_from_bounds` 
+    /// with the precise instance's value bounds, and keeping its key 
+    /// sets and weight strategy. Panics if the parameters are invalid.
+    ///
+    /// Every raw value is replayed with a single `increment`, so each 
+    /// estimate starts out at least the precise value and overestimates 
+    /// it by no more than `error_bound` with probability `1 - probability`, 
+    /// as long as no more than `max_entries` worth of trust is recorded. 
+    /// Normalized values are recomputed from the sketches and inherit 
+    /// that error. Observation counts start at zero, since the precise 
+    /// instance does not track them.
+    ///
+    /// ```
+    /// use decentrust::precise::PreciseHonestPeer;
+    /// use decentrust::probabilistic::LightHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut precise: PreciseHonestPeer<String, OrderedFloat<f64>> = PreciseHonestPeer::new();
+    /// precise.init_local(&"node1".to_string(), 3f64.into());
+    ///
+    /// let light = LightHonestPeer::from_precise(&precise, 1f64, 0.0001f64, 3000f64);
+    ///
+    /// assert_eq!(light.local_keys(), vec!["node1".to_string()]);
+    /// assert!(light.get_raw_local(&"node1".to_string()) >= Some(OrderedFloat::from(3.0)));
+    /// ```
+    pub fn from_precise(
+        precise: &PreciseHonestPeer<K, V>,
+        error_bound: f64, 
+        probability: f64, 
+        max_entries: f64
+    ) -> Self {
+        let (min, max) = precise.value_bounds();
+        let mut light = LightHonestPeer::new_from_bounds(
+            error_bound, 
+            probability, 
+            max_entries, 
+            min, 
+            max
+        );
+        light.weight_strategy = precise.weight_strategy();
+
+        precise.get_raw_local_map().into_iter().for_each(|(key, value)| {
+            light.local_trust.increment(&key, value);
+            light.local_keys.insert(key);
+        });
+        precise.get_raw_global_map().into_iter().for_each(|(key, value)| {
+            light.global_trust.increment(&key, value);
+            light.global_keys.insert(key);
+        });
+        light.normalize_local();
+        light.normalize_global();
+
+        light
+    }
+
+    /// Replaces the strategy used to weight reported deltas by their 
+    /// sender's normalized local trust, see `WeightStrategy`. Estimates 
+    /// already in the global sketch are left as they are.
+    pub fn with_weight_strategy<S>(mut self, strategy: S) -> Self 
+    where
+        S: WeightStrategy<V> + 'static
+    {
+        self.weight_strategy = Arc::new(strategy);
+        self
+    }
+
+    /// Sets the value decrements stop at, see `set_floor`
+    pub fn with_floor(mut self, floor: V) -> Self {
+        self.floor = floor;
+        self
+    }
+
+    /// Sets the value decrements stop at, e.g. a baseline granted to 
+    /// every newcomer. Estimates already below the floor are left as 
+    /// they are, they are only never decremented further.
+    pub fn set_floor(&mut self, floor: V) {
+        self.floor = floor;
+    }
+
+    /// Returns the value decrements stop at
+    pub fn floor(&self) -> V {
+        self.floor
+    }
+
+    /// Records every peer whose local trust is initialized or updated 
+    /// in `filter`, so that `maybe_seen_local` can answer from it
+    pub fn with_seen_filter(mut self, filter: BloomFilter) -> Self {
+        self.seen = Some(filter);
+        self
+    }
+
+    /// Returns whether a peer's local trust may have been initialized 
+    /// or updated. With a filter set by `with_seen_filter` this can be 
+    /// a false positive, with a probability set by how the filter is 
+    /// sized, but never a false negative for peers recorded since the 
+    /// filter was set. Without a filter the retained local key set is 
+    /// checked, which is exact.
+    ///
+    /// ```
+    /// use decentrust::probabilistic::LightHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use decentrust::bloom::BloomFilter;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp = LightHonestPeer::<&str, OrderedFloat<f64>>::new()
+    ///     .with_seen_filter(BloomFilter::with_rate(1000, 0.01));
+    /// hp.init_local(&"node1", 1f64.into());
+    ///
+    /// assert!(hp.maybe_seen_local(&"node1"));
+    /// ```
+    pub fn maybe_seen_local(&self, key: &K) -> bool {
+        match &self.seen {
+            Some(filter) => filter.contains(key),
+            None => self.local_keys.contains(key),
+        }
+    }
+
+    /// Limits the local and global key sets to `limit` keys each, see 
+    /// `set_key_retention`
+    pub fn with_key_retention(mut self, limit: usize) -> Self {
+        self.set_key_retention(limit);
+        self
+    }
+
+    /// Limits the local and global key sets to `limit` keys each. Once a 
+    /// set is full, retaining another key evicts the key that was least 
+    /// recently initialized, updated or reset. Keys retained before the 
+    /// limit was set count as older than any later update, in no 
+    /// particular order, and are evicted right away if there are more 
+    /// than `limit` of them.
+    ///
+    /// Evicted keys only drop out of enumeration, e.g. `local_keys` and 
+    /// `local_ranks`. Their mass stays in the sketches, so their 
+    /// estimates and everyone's normalized values are unchanged. In 
+    /// strict mode an evicted peer counts as unknown, and an update to 
+    /// it adds the newcomer default on top of its remaining mass.
+    ///
+    /// ```
+    /// use decentrust::probabilistic::LightHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp = LightHonestPeer::<&str, OrderedFloat<f64>>::new().with_key_retention(1);
+    /// hp.init_local(&"node1", 1f64.into());
+    /// hp.init_local(&"node2", 1f64.into());
+    ///
+    /// assert_eq!(hp.local_keys(), vec!["node2"]);
+    /// assert_eq!(hp.get_raw_local(&"node1"), Some(OrderedFloat::from(1.0)));
+    /// ```
+    pub fn set_key_retention(&mut self, limit: usize) {
+        if self.key_retention.is_none() {
+            self.local_keys.iter().for_each(|key| self.local_recency.touch(key));
+            self.global_keys.iter().for_each(|key| self.global_recency.touch(key));
+        }
+
+        self.key_retention = Some(limit);
+        evict_oldest(&mut self.local_keys, &mut self.local_recency, limit);
+        evict_oldest(&mut self.global_keys, &mut self.global_recency, limit);
+    }
+
+    /// Removes the limit on the key sets, see `set_key_retention`. Keys 
+    /// evicted so far are not restored.
+    pub fn clear_key_retention(&mut self) {
+        self.key_retention = None;
+        self.local_recency.clear();
+        self.global_recency.clear();
+    }
+
+    /// Returns the most keys retained in each key set, if limited
+    pub fn key_retention(&self) -> Option<usize> {
+        self.key_retention
+    }
+
+    /// Moves the raw local estimate of `from` onto `to`, e.g. when a 
+    /// peer rotates its identity key, by decrementing `from`'s cells by 
+    /// its estimate and incrementing `to`'s by the same amount. `from` 
+    /// is dropped from the local key set and the sketch is normalized. 
+    /// Cells `from` shares with other peers lose the moved amount too, 
+    /// so colliding peers may end up underestimated, see `reset_local`. 
+    /// Observation counts stay with `from`. Does nothing if `from` is 
+    /// not in the local key set, as its estimate is then only collision 
+    /// mass, matching `PreciseHonestPeer::transfer_local`.
+    ///
+    /// ```
+    /// use decentrust::probabilistic::LightHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+    /// hp.init_local(&"old", 3f64.into());
+    /// hp.init_local(&"new", 1f64.into());
+    ///
+    /// hp.transfer_local(&"old", &"new");
+    ///
+    /// assert_eq!(hp.get_raw_local(&"new"), Some(OrderedFloat::from(4.0)));
+    /// assert_eq!(hp.local_keys(), vec!["new"]);
+    /// ```
+    pub fn transfer_local(&mut self, from: &K, to: &K) {
+        if from == to || !self.local_keys.contains(from) {
+            return;
+        }
+
+        let moved = self.local_trust.estimate(from);
+        self.local_trust.decrement(from, moved);
+        self.local_trust.increment(to, moved);
+        self.local_keys.remove(from);
+        self.local_recency.remove(from);
+        self.mark_seen(to);
+        self.retain_local_key(to);
+        self.normalize_local();
+    }
+
+    /// Moves the raw global estimate of `from` onto `to`, see 
+    /// `transfer_local`. Does nothing if `from` is not in the global key 
+    /// set.
+    pub fn transfer_global(&mut self, from: &K, to: &K) {
+        if from == to || !self.global_keys.contains(from) {
+            return;
+        }
+
+        let moved = self.global_trust.estimate(from);
+        self.global_trust.decrement(from, moved);
+        self.global_trust.increment(to, moved);
+        self.global_keys.remove(from);
+        self.global_recency.remove(from);
+        self.retain_global_key(to);
+        self.normalize_global();
+    }
+
+    /// Decrements the raw local estimate of every peer in the local key 
+    /// set but those in `except` by `delta`, flooring at the configured 
+    /// floor, then normalizes once. Peers in `except` that share cells 
+    /// with penalized peers lose some of their estimate too.
+    ///
+    /// ```
+    /// use decentrust::probabilistic::LightHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+    /// hp.init_local(&"node1", 5f64.into());
+    /// hp.init_local(&"node2", 5f64.into());
+    ///
+    /// hp.penalize_all_local(2f64.into(), &["node2"]);
+    ///
+    /// assert_eq!(hp.get_raw_local(&"node1"), Some(OrderedFloat::from(3.0)));
+    /// assert_eq!(hp.get_raw_local(&"node2"), Some(OrderedFloat::from(5.0)));
+    /// ```
+    pub fn penalize_all_local(&mut self, delta: V, except: &[K]) {
+        self.local_keys.iter()
+            .filter(|key| !except.contains(key))
+            .for_each(|key| floored_decrement(&mut self.local_trust, key, delta, self.floor));
+        self.normalize_local();
+    }
+
+    /// Folds a full trust map reported by `reporter` into global trust, 
+    /// incrementing each reported peer by its value weighted by the 
+    /// reporter's normalized local estimate and normalizing once at the 
+    /// end, see `PreciseHonestPeer::ingest_report`. Reports from 
+    /// reporters missing from the local key set are ignored, since their 
+    /// estimate would only hold what colliding peers put in their cells. 
+    /// In strict mode unknown peers in the report are skipped.
+    pub fn ingest_report(&mut self, reporter: &K, report: &HashMap<K, V>) {
+        if !self.local_keys.contains(reporter) {
+            return
+        }
+
+        let reporter_trust = self.normalized_local_trust.estimate(reporter);
+        for (key, trust) in report.iter() {
+            if self.strict && !self.global_keys.contains(key) {
+                continue
+            }
+
+            let weighted = self.weight_strategy.weight(*trust, reporter_trust);
+            if self.retain_global_key(key) {
+                self.global_trust.increment(key, self.newcomer_default);
+            }
+            self.global_observations.increment(key, 1);
+            self.global_trust.increment(key, weighted);
+        }
+
+        self.normalize_global();
+    }
+
+    /// Retains a peer's key in the local key set, evicting the least 
+    /// recently updated key if the set is full. Returns whether the 
+    /// key was missing from the set.
+    fn retain_local_key(&mut self, key: &K) -> bool {
+        let inserted = self.local_keys.insert(key.clone());
+        if let Some(limit) = self.key_retention {
+            self.local_recency.touch(key);
+            evict_oldest(&mut self.local_keys, &mut self.local_recency, limit);
+        }
+
+        inserted
+    }
+
+    /// Retains a peer's key in the global key set, see `retain_local_key`
+    fn retain_global_key(&mut self, key: &K) -> bool {
+        let inserted = self.global_keys.insert(key.clone());
+        if let Some(limit) = self.key_retention {
+            self.global_recency.touch(key);
+            evict_oldest(&mut self.global_keys, &mut self.global_recency, limit);
+        }
+
+        inserted
+    }
+
+    /// Initializes the global estimate of a peer to `value`. A new key 
+    /// is incremented so that the cells it shares with other peers are 
+    /// left alone, while a key already in the global key set has its 
+    /// estimate replaced, as `reset_global` does, so that replaying an 
+    /// init leaves the value it set and the latest init wins.
+    fn set_global_init(&mut self, key: &K, value: V) {
+        if !self.retain_global_key(key) {
+            let current = self.global_trust.estimate(key);
+            self.global_trust.decrement(key, current);
+        }
+        self.global_trust.increment(key, value);
+    }
+
+    /// Records a peer in the seen filter, if one is set
+    fn mark_seen(&mut self, key: &K) {
+        if let Some(filter) = self.seen.as_mut() {
+            filter.insert(key);
+        }
+    }
+
+    /// Sets the raw trust peers first seen through an update start 
+    /// from, see `set_newcomer_default`
+    pub fn with_newcomer_default(mut self, newcomer_default: V) -> Self {
+        self.newcomer_default = newcomer_default;
+        self
+    }
+
+    /// Sets the raw trust a peer missing from the retained key set 
+    /// starts from when `update_local` or `update_global` is called for 
+    /// it, so that the update's delta is applied on top of it. Defaults 
+    /// to `V::default()`. See `PreciseHonestPeer::set_newcomer_default`.
+    pub fn set_newcomer_default(&mut self, newcomer_default: V) {
+        self.newcomer_default = newcomer_default;
+    }
+
+    /// Returns the raw trust peers first seen through an update start from
+    pub fn newcomer_default(&self) -> V {
+        self.newcomer_default
+    }
+
+    /// Enables or disables strict mode, see `set_strict`
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Enables or disables strict mode. While enabled, `update_local` 
+    /// and `update_global` ignore peers missing from the local and 
+    /// global key sets respectively, so that a peer must be initialized 
+    /// before it is updated.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Returns whether strict mode is enabled
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Updates a peer's local trust like `update_local`, but returns an 
+    /// `UnknownPeerError` instead of ignoring the update when strict mode 
+    /// is enabled and the peer was never initialized. Outside of strict 
+    /// mode this never fails.
+    pub fn try_update_local(
+        &mut self, 
+        key: &K, 
+        trust_delta: V, 
+        update: Update
+    ) -> Result<(), UnknownPeerError<K>> {
+        if self.strict && !self.local_keys.contains(key) {
+            return Err(UnknownPeerError { key: key.clone() });
+        }
+
+        self.update_local(key, trust_delta, update);
+        Ok(())
+    }
+
+    /// Updates a peer's global trust like `update_global`, but returns an 
+    /// `UnknownPeerError` instead of ignoring the update when strict mode 
+    /// is enabled and the peer was never initialized globally. Outside 
+    /// of strict mode this never fails.
+    pub fn try_update_global(
+        &mut self, 
+        sender: &K,
+        key: &K, 
+        trust_delta: V, 
+        update: Update
+    ) -> Result<(), UnknownPeerError<K>> {
+        if self.strict && !self.global_keys.contains(key) {
+            return Err(UnknownPeerError { key: key.clone() });
         }
+
+        self.update_global(sender, key, trust_delta, update);
+        Ok(())
+    }
+
+    /// Sets how raw trust is normalized and renormalizes both sketches 
+    /// with the new mode. Every later normalization uses it as well.
+    pub fn set_normalization_mode(&mut self, mode: NormalizationMode) {
+        self.normalization_mode = mode;
+        self.normalize_local();
+        self.normalize_global();
+    }
+
+    /// Returns how raw trust is normalized
+    pub fn normalization_mode(&self) -> NormalizationMode {
+        self.normalization_mode
+    }
+
+    /// Sets the sum each row of the normalized sketches is scaled to in 
+    /// `NormalizationMode::Sum` and renormalizes both sketches, see 
+    /// `PreciseHonestPeer::set_normalization_target` and 
+    /// `CountMinSketch::normalize_estimates_scaled`
+    pub fn set_normalization_target(&mut self, target: V) {
+        self.normalization_target = Some(target);
+        self.normalize_local();
+        self.normalize_global();
+    }
+
+    /// Returns the sum normalized rows are scaled to, `None` if they 
+    /// sum to 1
+    pub fn normalization_target(&self) -> Option<V> {
+        self.normalization_target
+    }
+
+    /// Rounds every cell of the normalized sketches to `decimals` 
+    /// decimal places whenever they are recomputed, and rounds them 
+    /// right away, see `PreciseHonestPeer::set_normalized_precision`. 
+    /// Estimates are taken from rounded cells, so they are rounded too.
+    pub fn set_normalized_precision(&mut self, decimals: u32) 
+    where
+        V: NumCast
+    {
+        self.rounding = Some((decimals, decimal_rounding(decimals)));
+        self.normalize_local();
+        self.normalize_global();
+    }
+
+    /// Returns the number of decimal places normalized cells are 
+    /// rounded to, `None` if they are not rounded
+    pub fn normalized_precision(&self) -> Option<u32> {
+        self.rounding.as_ref().map(|(decimals, _)| *decimals)
+    }
+
+    /// Replaces the normalized local sketch with the percentile rank of 
+    /// each retained key's estimate among all retained keys, see 
+    /// `NormalizationMode::Rank`. Ranks are recorded by incrementing an 
+    /// empty sketch, so colliding keys may overestimate their rank. 
+    /// Unless the mode is set to `Rank`, the next update normalizes by 
+    /// sum again.
+    pub fn rank_normalize_local(&mut self) {
+        let entries: Vec<(K, V)> = self.local_keys.iter()
+            .map(|key| (key.clone(), self.local_trust.estimate(key)))
+            .collect();
+        rank_into(&mut self.normalized_local_trust, entries);
+        round_cells(&mut self.normalized_local_trust, &self.rounding);
+    }
+
+    /// Replaces the normalized global sketch with the percentile rank of 
+    /// each retained key's estimate among all retained keys, see 
+    /// `rank_normalize_local`
+    pub fn rank_normalize_global(&mut self) {
+        let entries: Vec<(K, V)> = self.global_keys.iter()
+            .map(|key| (key.clone(), self.global_trust.estimate(key)))
+            .collect();
+        rank_into(&mut self.normalized_global_trust, entries);
+        round_cells(&mut self.normalized_global_trust, &self.rounding);
     }
 
     /// Iterates over provided ids, and returns an iterator over 
@@ -208,12 +808,7 @@ where
     where 
         B: BucketizeSingle<V> + 'a
     {
-       node_ids.map(move |k| {
-            let estimate = self.local_trust.estimate(&k);
-            let bucketed = bucketizer.bucketize(&estimate);
-            (k, bucketed)
-
-        })
+        self.bucketize_sketch(&self.local_trust, node_ids, bucketizer)
     }
 
     /// Iterates over provided ids, and returns an iterator over 
@@ -268,12 +863,7 @@ where
     where 
         B: BucketizeSingle<V> + 'a
     {
-        node_ids.map(move |k| {
-            let estimate = self.normalized_local_trust.estimate(&k);
-            let bucketed = bucketizer.bucketize(&estimate);
-            (k, bucketed)
-
-        })
+        self.bucketize_sketch(&self.normalized_local_trust, node_ids, bucketizer)
     }
 
     /// Iterates over provided ids, and returns an iterator over 
@@ -341,12 +931,7 @@ where
     where 
         B: BucketizeSingle<V> + 'a
     {
-        node_ids.map(move |k| {
-            let estimate = self.global_trust.estimate(&k);
-            let bucketed = bucketizer.bucketize(&estimate);
-            (k, bucketed)
-
-        })
+        self.bucketize_sketch(&self.global_trust, node_ids, bucketizer)
     }
 
     /// Iterates over provided ids, and returns an iterator over 
@@ -413,83 +998,604 @@ where
         node_ids: impl Iterator<Item = K> + 'a, 
         bucketizer: B
     ) -> impl Iterator<Item = (K, usize)> + '_
+    where 
+        B: BucketizeSingle<V> + 'a
+    {
+        self.bucketize_sketch(&self.normalized_global_trust, node_ids, bucketizer)
+    }
+
+    /// Pairs each provided id with the bucketized estimate for that id 
+    /// in a given sketch
+    fn bucketize_sketch<'a, B>(
+        &'a self, 
+        sketch: &'a CountMinSketch<V>, 
+        node_ids: impl Iterator<Item = K> + 'a, 
+        bucketizer: B
+    ) -> impl Iterator<Item = (K, usize)> + 'a
     where 
         B: BucketizeSingle<V> + 'a
     {
         node_ids.map(move |k| {
-            let estimate = self.normalized_global_trust.estimate(&k);
-            let bucketed = bucketizer.bucketize(&estimate);
+            let estimate = sketch.estimate(&k);
+            let bucketed = self.bucket_tracker.observe(bucketizer.bucketize(&estimate));
             (k, bucketed)
-
         })
     }
 
     pub fn get_width(&self) -> usize {
-        self.local_trust.get_width()
+        self.local_trust.width()
     }
 
     pub fn get_depth(&self) -> usize {
-        self.local_trust.get_depth()
+        self.local_trust.depth()
     }
-}
 
-impl<K, V> HonestPeer for LightHonestPeer<K, V> 
-where 
-    K: Eq + Hash + Clone + Debug + ToString,
-    V: AddAssign 
-        + DivAssign 
-        + SubAssign 
-        + Add<Output = V> 
-        + Mul<Output = V> 
-        + Div<Output = V> 
-        + Sub<Output = V> 
-        + Copy 
-        + Default 
-        + Bounded 
-        + Ord 
-        + Hash
-        + Debug
-{
-    type Map = CountMinSketch<V>;
-    type Key = K;
-    type Value = V;
+    /// Returns the number of provided ids that fall into each bucket 
+    /// of the raw local trust estimates, keyed by bucket index. Buckets 
+    /// no id falls into are absent from the map.
+    pub fn local_histogram<B>(
+        &self, 
+        node_ids: impl Iterator<Item = K>, 
+        bucketizer: B
+    ) -> HashMap<usize, usize>
+    where
+        B: BucketizeSingle<V>
+    {
+        bucket_histogram(self.bucketize_local(node_ids, bucketizer))
+    }
 
-    /// Initalizes a local trust value for a newly discovered peer
-    fn init_local(&mut self, key: &Self::Key, init_value: Self::Value) {
-        self.local_trust.increment(key, init_value);
-        self.normalize_local();
+    /// Returns the number of provided ids that fall into each bucket 
+    /// of the normalized local trust estimates, keyed by bucket index
+    pub fn normalized_local_histogram<B>(
+        &self, 
+        node_ids: impl Iterator<Item = K>, 
+        bucketizer: B
+    ) -> HashMap<usize, usize>
+    where
+        B: BucketizeSingle<V>
+    {
+        bucket_histogram(self.bucketize_normalized_local(node_ids, bucketizer))
     }
 
-    /// Updates a local trust value for a given peer
-    fn update_local(
-        &mut self, 
-        key: &Self::Key, 
-        trust_delta: Self::Value, 
-        update: Update
-    ) {
-        match update {
-            Update::Increment => self.local_trust.increment(key, trust_delta),
-            Update::Decrement => self.local_trust.decrement(key, trust_delta), 
-        }
-        self.normalize_local();
+    /// Returns the number of provided ids that fall into each bucket 
+    /// of the raw global trust estimates, keyed by bucket index
+    pub fn global_histogram<B>(
+        &self, 
+        node_ids: impl Iterator<Item = K>, 
+        bucketizer: B
+    ) -> HashMap<usize, usize>
+    where
+        B: BucketizeSingle<V>
+    {
+        bucket_histogram(self.bucketize_global(node_ids, bucketizer))
     }
 
-    /// returns the raw (unnormalized) estimate for a given peer
-    fn get_raw_local(&self, key: &Self::Key) -> Option<Self::Value> {
-        Some(self.local_trust.estimate(key))
+    /// Returns the number of provided ids that fall into each bucket 
+    /// of the normalized global trust estimates, keyed by bucket index
+    pub fn normalized_global_histogram<B>(
+        &self, 
+        node_ids: impl Iterator<Item = K>, 
+        bucketizer: B
+    ) -> HashMap<usize, usize>
+    where
+        B: BucketizeSingle<V>
+    {
+        bucket_histogram(self.bucketize_normalized_global(node_ids, bucketizer))
     }
 
-    /// returns the normalized estimate for a given peer
-    fn get_normalized_local(&self, key: &Self::Key) -> Option<Self::Value> {
-        Some(self.normalized_local_trust.estimate(key))
+    /// Returns the number of buckets produced by the `bucketize_*` 
+    /// methods so far, every bucket index they have yielded is within 
+    /// `0..bucket_count()`. Buckets are only counted as the returned 
+    /// iterators are consumed.
+    pub fn bucket_count(&self) -> usize {
+        self.bucket_tracker.bucket_count()
     }
 
-    /// initializes a global trust value for a newly discovered peer
-    fn init_global(&mut self, sender: &Self::Key, key: &Self::Key, init_value: Self::Value) {
-        let sender_trust = self.normalized_local_trust.estimate(sender);
-        let weighted_init = init_value * sender_trust;
-        self.global_trust.increment(key, weighted_init);
-        self.normalize_global();
+    /// Returns the highest bucket index produced by the `bucketize_*` 
+    /// methods so far, or `None` if none have been produced yet
+    pub fn max_bucket_seen(&self) -> Option<usize> {
+        self.bucket_tracker.max_bucket_seen()
+    }
+
+    /// Returns the (estimated) number of `update_local` calls made for 
+    /// a given peer, regardless of the size of each delta. Like trust 
+    /// estimates, counts may be overestimated but never underestimated.
+    ///
+    /// ```
+    /// use decentrust::probabilistic::LightHonestPeer;
+    /// use decentrust::honest_peer::{HonestPeer, Update};
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp = LightHonestPeer::<String, OrderedFloat<f64>>::new();
+    ///
+    /// hp.update_local(&"node1".to_string(), 100f64.into(), Update::Increment);
+    /// hp.update_local(&"node2".to_string(), 1f64.into(), Update::Increment);
+    /// hp.update_local(&"node2".to_string(), 1f64.into(), Update::Increment);
+    ///
+    /// assert_eq!(hp.observation_count_local(&"node1".to_string()), 1);
+    /// assert_eq!(hp.observation_count_local(&"node2".to_string()), 2);
+    /// ```
+    pub fn observation_count_local(&self, key: &K) -> u64 {
+        self.local_observations.estimate(key)
+    }
+
+    /// Returns the (estimated) number of global reports received for a 
+    /// given peer, i.e. one per `update_global` call and one per report 
+    /// passed to `update_global_multi`
+    pub fn observation_count_global(&self, key: &K) -> u64 {
+        self.global_observations.estimate(key)
+    }
+
+    /// Materializes the raw local trust into a dense map, holding the 
+    /// current estimate for every retained local key. Estimates of 
+    /// colliding keys may exceed the values actually recorded for 
+    /// them, see `suspect_collisions`.
+    ///
+    /// ```
+    /// use decentrust::probabilistic::LightHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp = LightHonestPeer::<String, OrderedFloat<f64>>::new();
+    /// hp.init_local(&"node1".to_string(), 2f64.into());
+    ///
+    /// let dense = hp.reconstruct_local();
+    /// assert_eq!(dense.len(), 1);
+    /// assert!(dense[&"node1".to_string()] >= OrderedFloat::from(2.0));
+    /// ```
+    pub fn reconstruct_local(&self) -> HashMap<K, V> {
+        self.local_keys.iter()
+            .map(|key| (key.clone(), self.local_trust.estimate(key)))
+            .collect()
+    }
+
+    /// Materializes the raw global trust into a dense map, holding the 
+    /// current estimate for every retained global key
+    pub fn reconstruct_global(&self) -> HashMap<K, V> {
+        self.global_keys.iter()
+            .map(|key| (key.clone(), self.global_trust.estimate(key)))
+            .collect()
+    }
+
+    /// Returns how many pairs of distinct keys among `keys` share a cell 
+    /// in at least one row of the sketches, e.g. over `local_keys()` as 
+    /// a single health metric to track over time. A pair only inflates 
+    /// each other's estimates if it shares a cell in every row, which 
+    /// is far rarer, but a rising count is an early sign that the 
+    /// sketches are too narrow for the number of peers tracked. Keys 
+    /// given more than once are counted once.
+    ///
+    /// ```
+    /// use decentrust::probabilistic::LightHonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let hp = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+    ///
+    /// assert!(hp.estimated_collision_count(&["node1", "node2"]) <= 1);
+    /// assert_eq!(hp.estimated_collision_count(&["node1"]), 0);
+    /// ```
+    pub fn estimated_collision_count(&self, keys: &[K]) -> usize {
+        let unique: Vec<&K> = keys.iter().collect::<HashSet<&K>>().into_iter().collect();
+        let columns: Vec<Vec<usize>> = unique.iter()
+            .map(|key| self.local_trust.hash_functions(*key))
+            .collect();
+
+        let mut colliding: HashSet<(usize, usize)> = HashSet::new();
+        for row in 0..self.local_trust.depth() {
+            let mut cells: HashMap<usize, Vec<usize>> = HashMap::new();
+            columns.iter().enumerate().for_each(|(i, hashed)| {
+                cells.entry(hashed[row]).or_default().push(i);
+            });
+            for sharing in cells.values() {
+                for (n, a) in sharing.iter().enumerate() {
+                    sharing[n + 1..].iter().for_each(|b| { colliding.insert((*a, *b)); });
+                }
+            }
+        }
+
+        colliding.len()
+    }
+
+    /// Returns the number of keys in the local key set per column of 
+    /// the local sketch, i.e. how many peers share each cell of a row 
+    /// on average. Estimates degrade as it approaches 1.
+    ///
+    /// ```
+    /// use decentrust::probabilistic::LightHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use decentrust::cms::DEFAULT_WIDTH;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+    /// hp.init_local(&"node1", 1f64.into());
+    /// hp.init_local(&"node2", 1f64.into());
+    ///
+    /// assert_eq!(hp.load_factor(), 2.0 / DEFAULT_WIDTH as f64);
+    /// ```
+    pub fn load_factor(&self) -> f64 {
+        self.local_keys.len() as f64 / self.local_trust.width() as f64
+    }
+
+    /// Returns the keys among `keys` whose raw local estimate is likely 
+    /// inflated by collisions, i.e. whose hashed cells differ by more 
+    /// than `tolerance`, see `CountMinSketch::cell_spread`. Callers can 
+    /// fall back to a precise check for these peers.
+    pub fn suspect_collisions(&self, keys: &[K], tolerance: V) -> Vec<K> {
+        keys.iter()
+            .filter(|key| self.local_trust.cell_spread(*key) > tolerance)
+            .cloned()
+            .collect()
+    }
+
+    /// Folds the trust tracked by `other` into this instance, adding 
+    /// its raw local and global sketches and observation counts into 
+    /// this instance's, taking the union of the retained keys, and 
+    /// re-normalizing. Both instances must be cloned from a common 
+    /// instance so their sketches share dimensions and hash seeds, 
+    /// otherwise a `MergeError` is returned and nothing is modified.
+    ///
+    /// ```
+    /// use decentrust::probabilistic::LightHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let base = LightHonestPeer::<String, OrderedFloat<f64>>::new();
+    /// let mut a = base.clone();
+    /// let mut b = base.clone();
+    ///
+    /// a.init_local(&"node1".to_string(), 2f64.into());
+    /// b.init_local(&"node1".to_string(), 1f64.into());
+    /// b.init_local(&"node2".to_string(), 1f64.into());
+    ///
+    /// a.merge(&b).unwrap();
+    ///
+    /// assert_eq!(a.get_raw_local(&"node1".to_string()), Some(OrderedFloat::from(3.0)));
+    /// assert_eq!(a.local_keys().len(), 2);
+    /// assert!(a.merge(&LightHonestPeer::new()).is_err());
+    /// ```
+    pub fn merge(&mut self, other: &LightHonestPeer<K, V>) -> Result<(), MergeError> {
+        self.local_trust.check_mergeable(&other.local_trust)?;
+        self.global_trust.check_mergeable(&other.global_trust)?;
+        self.local_observations.check_mergeable(&other.local_observations)?;
+        self.global_observations.check_mergeable(&other.global_observations)?;
+
+        self.local_trust.merge(&other.local_trust)?;
+        self.global_trust.merge(&other.global_trust)?;
+        self.local_observations.merge(&other.local_observations)?;
+        self.global_observations.merge(&other.global_observations)?;
+        other.local_keys.iter().for_each(|key| { self.retain_local_key(key); });
+        other.global_keys.iter().for_each(|key| { self.retain_global_key(key); });
+        self.normalize_local();
+        self.normalize_global();
+
+        Ok(())
+    }
+
+    /// Returns how confident the raw local estimate for a given peer 
+    /// is, from 0 to 1, see `CountMinSketch::estimate_confidence`
+    pub fn local_confidence(&self, key: &K) -> f64 
+    where
+        V: ToPrimitive
+    {
+        self.local_trust.estimate_confidence(key)
+    }
+
+    /// Returns how confident the raw global estimate for a given peer 
+    /// is, from 0 to 1, see `CountMinSketch::estimate_confidence`
+    pub fn global_confidence(&self, key: &K) -> f64 
+    where
+        V: ToPrimitive
+    {
+        self.global_trust.estimate_confidence(key)
+    }
+
+    /// Returns the `q` quantile (nearest-rank) of the normalized local 
+    /// trust estimates over the retained local key set, e.g. `0.5` for 
+    /// the median or `0.9` for the p90. Returns `None` if no peers 
+    /// have been seen or `q` is outside of `[0, 1]`.
+    ///
+    /// ```
+    /// use decentrust::probabilistic::LightHonestPeer;
+    /// use decentrust::honest_peer::HonestPeer;
+    /// use ordered_float::OrderedFloat;
+    ///
+    /// let mut hp = LightHonestPeer::<String, OrderedFloat<f64>>::new();
+    ///
+    /// assert_eq!(hp.local_quantile(0.5), None);
+    ///
+    /// hp.init_local(&"node1".to_string(), 1f64.into());
+    /// hp.init_local(&"node2".to_string(), 3f64.into());
+    ///
+    /// assert!(hp.local_quantile(1.0) >= Some(OrderedFloat::from(0.75)));
+    /// ```
+    pub fn local_quantile(&self, q: f64) -> Option<V> {
+        nearest_rank_quantile(
+            self.normalized_local_trust.estimate_all(self.local_keys.iter()), 
+            q
+        )
+    }
+
+    /// Returns the `q` quantile (nearest-rank) of the normalized global 
+    /// trust estimates over the retained global key set. Returns `None` 
+    /// if no peers have been seen or `q` is outside of `[0, 1]`.
+    pub fn global_quantile(&self, q: f64) -> Option<V> {
+        nearest_rank_quantile(
+            self.normalized_global_trust.estimate_all(self.global_keys.iter()), 
+            q
+        )
+    }
+}
+
+/// Configures and builds a `LightHonestPeer`, as a labeled alternative 
+/// to `new_from_bounds`. The sketches are sized from `error_bound`, 
+/// `probability` and `max_entries`, which are validated together as in 
+/// `CountMinSketch::try_new_from_bounds`. If none of them is set the 
+/// sketches get the `CountMinSketch::default()` dimensions; if only 
+/// some are set, `build` reports the first missing one as 
+/// `CmsParamError::MissingParameter`. The hash seed is random unless set with `seed`.
+///
+/// ```
+/// use decentrust::probabilistic::LightHonestPeerBuilder;
+/// use decentrust::honest_peer::{HonestPeer, Update};
+/// use ordered_float::OrderedFloat;
+///
+/// let mut hp = LightHonestPeerBuilder::<&str, OrderedFloat<f64>>::new()
+///     .error_bound(50.0)
+///     .probability(0.0001)
+///     .max_entries(3000.0)
+///     .floor(1f64.into())
+///     .build()
+///     .unwrap();
+///
+/// hp.init_local(&"node1", 2f64.into());
+/// hp.update_local(&"node1", 5f64.into(), Update::Decrement);
+///
+/// assert_eq!(hp.get_raw_local(&"node1"), Some(OrderedFloat::from(1.0)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LightHonestPeerBuilder<K, V> {
+    error_bound: Option<f64>,
+    probability: Option<f64>,
+    max_entries: Option<f64>,
+    bounds: Option<(V, V)>,
+    seed: Option<u64>,
+    strict: bool,
+    newcomer_default: V,
+    floor: V,
+    normalization_target: Option<V>,
+    key_type: PhantomData<K>,
+}
+
+impl<K, V> Default for LightHonestPeerBuilder<K, V> 
+where
+    V: Default
+{
+    fn default() -> Self {
+        LightHonestPeerBuilder {
+            error_bound: None,
+            probability: None,
+            max_entries: None,
+            bounds: None,
+            seed: None,
+            strict: false,
+            newcomer_default: V::default(),
+            floor: V::default(),
+            normalization_target: None,
+            key_type: PhantomData,
+        }
+    }
+}
+
+impl<K, V> LightHonestPeerBuilder<K, V> 
+where 
+    K: Eq + Hash + Clone + Debug + ToString,
+    V: AddAssign 
+    + DivAssign 
+    + SubAssign 
+    + Add<Output = V> 
+    + Mul<Output = V> 
+    + Div<Output = V> 
+    + Sub<Output = V> 
+    + Copy 
+    + Default 
+    + Bounded
+    + One
+    + Ord 
+    + Hash
+    + Debug
+{
+    /// Creates a builder with every setting at its default
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the most a single estimate may overestimate by, see 
+    /// `CountMinSketch::try_new_from_bounds`
+    pub fn error_bound(mut self, error_bound: f64) -> Self {
+        self.error_bound = Some(error_bound);
+        self
+    }
+
+    /// Sets the probability that an estimate exceeds the error bound
+    pub fn probability(mut self, probability: f64) -> Self {
+        self.probability = Some(probability);
+        self
+    }
+
+    /// Sets the total trust expected to be recorded in each sketch
+    pub fn max_entries(mut self, max_entries: f64) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Sets the min and max values of the sketches, the full range of 
+    /// `V` unless set
+    pub fn bounds(mut self, min: V, max: V) -> Self {
+        self.bounds = Some((min, max));
+        self
+    }
+
+    /// Sets the hash seed shared by every sketch, so that instances 
+    /// built apart with the same seed and dimensions can be merged
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Enables or disables strict mode, see `LightHonestPeer::set_strict`
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets the raw trust peers first seen through an update start 
+    /// from, see `LightHonestPeer::set_newcomer_default`
+    pub fn newcomer_default(mut self, newcomer_default: V) -> Self {
+        self.newcomer_default = newcomer_default;
+        self
+    }
+
+    /// Sets the value decrements stop at, see `LightHonestPeer::set_floor`
+    pub fn floor(mut self, floor: V) -> Self {
+        self.floor = floor;
+        self
+    }
+
+    /// Sets the sum normalized rows are scaled to, see 
+    /// `LightHonestPeer::set_normalization_target`. Integer stores need 
+    /// one for normalized values to mean anything.
+    ///
+    /// ```
+    /// use decentrust::probabilistic::LightHonestPeerBuilder;
+    /// use decentrust::honest_peer::HonestPeer;
+    ///
+    /// let mut hp = LightHonestPeerBuilder::<&str, u32>::new()
+    ///     .error_bound(10.0)
+    ///     .probability(0.001)
+    ///     .max_entries(100_000.0)
+    ///     .normalization_target(10_000)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// hp.init_local(&"node1", 1);
+    /// hp.init_local(&"node2", 3);
+    ///
+    /// assert_eq!(hp.get_normalized_local(&"node2"), Some(7_500));
+    /// ```
+    pub fn normalization_target(mut self, target: V) -> Self {
+        self.normalization_target = Some(target);
+        self
+    }
+
+    /// Builds a `LightHonestPeer` with no peers in it, or returns a 
+    /// `CmsParamError` if the sizing parameters are invalid
+    pub fn build(self) -> Result<LightHonestPeer<K, V>, CmsParamError> {
+        let (min, max) = self.bounds.unwrap_or((V::min_value(), V::max_value()));
+        let (width, depth) = match (self.error_bound, self.probability, self.max_entries) {
+            (None, None, None) => (DEFAULT_WIDTH, DEFAULT_DEPTH),
+            (error_bound, probability, max_entries) => {
+                CountMinSketch::<V>::sized_dimensions(
+                    error_bound.ok_or(CmsParamError::MissingParameter("error_bound"))?, 
+                    probability.ok_or(CmsParamError::MissingParameter("probability"))?, 
+                    max_entries.ok_or(CmsParamError::MissingParameter("max_entries"))?
+                )?
+            }
+        };
+        let sketch = match self.seed {
+            Some(seed) => CountMinSketch::new_with_seed(width, depth, min, max, seed),
+            None => CountMinSketch::new(width, depth, min, max),
+        };
+
+        let mut light = LightHonestPeer::from_sketch(sketch);
+        light.strict = self.strict;
+        light.newcomer_default = self.newcomer_default;
+        light.floor = self.floor;
+        light.normalization_target = self.normalization_target;
+
+        Ok(light)
+    }
+}
+
+impl<K, V> HonestPeer for LightHonestPeer<K, V> 
+where 
+    K: Eq + Hash + Clone + Debug + ToString,
+    V: AddAssign 
+        + DivAssign 
+        + SubAssign 
+        + Add<Output = V> 
+        + Mul<Output = V> 
+        + Div<Output = V> 
+        + Sub<Output = V> 
+        + Copy 
+        + Default 
+        + Bounded
+        + One
+        + Ord 
+        + Hash
+        + Debug
+{
+    type Map = CountMinSketch<V>;
+    type Key = K;
+    type Value = V;
+
+    /// Initalizes a local trust value for a newly discovered peer
+    fn init_local(&mut self, key: &Self::Key, init_value: Self::Value) {
+        self.mark_seen(key);
+        self.retain_local_key(key);
+        self.local_trust.increment(key, init_value);
+        self.normalize_local();
+    }
+
+    /// Updates a local trust value for a given peer
+    fn update_local(
+        &mut self, 
+        key: &Self::Key, 
+        trust_delta: Self::Value, 
+        update: Update
+    ) {
+        if self.strict && !self.local_keys.contains(key) {
+            return
+        }
+
+        self.mark_seen(key);
+        if self.retain_local_key(key) {
+            self.local_trust.increment(key, self.newcomer_default);
+        }
+        self.local_observations.increment(key, 1);
+        match update {
+            Update::Increment => self.local_trust.increment(key, trust_delta),
+            Update::Decrement => floored_decrement(&mut self.local_trust, key, trust_delta, self.floor),
+        }
+        self.normalize_local();
+    }
+
+    /// returns the raw (unnormalized) estimate for a given peer
+    fn get_raw_local(&self, key: &Self::Key) -> Option<Self::Value> {
+        Some(self.local_trust.estimate(key))
+    }
+
+    /// returns the normalized estimate for a given peer
+    fn get_normalized_local(&self, key: &Self::Key) -> Option<Self::Value> {
+        Some(self.normalized_local_trust.estimate(key))
+    }
+
+    /// initializes a global trust value for a newly discovered peer. 
+    /// Peers already in the global key set have their estimate replaced 
+    /// rather than incremented, so that replaying a bootstrap message 
+    /// does not add its value again and, as with `PreciseHonestPeer`, 
+    /// the latest init of a peer wins whichever sender made it. Inits 
+    /// from senders outside the local key set are ignored, as their 
+    /// estimate is only collision mass. Keys evicted by 
+    /// `set_key_retention` count as new.
+    fn init_global(&mut self, sender: &Self::Key, key: &Self::Key, init_value: Self::Value) {
+        if !self.local_keys.contains(sender) {
+            return
+        }
+
+        let sender_trust = self.normalized_local_trust.estimate(sender);
+        let weighted_init = self.weight_strategy.weight(init_value, sender_trust);
+        self.set_global_init(key, weighted_init);
+        self.normalize_global();
     }
 
     /// updates a global trust value for a given peer
@@ -500,12 +1606,103 @@ where
         trust_delta: Self::Value, 
         update: Update
     ) {
+        if self.strict && !self.global_keys.contains(key) {
+            return
+        }
+
         let sender_trust = self.normalized_local_trust.estimate(sender);
-        let weighted_delta = trust_delta * sender_trust;
+        let weighted_delta = self.weight_strategy.weight(trust_delta, sender_trust);
+        if self.retain_global_key(key) {
+            self.global_trust.increment(key, self.newcomer_default);
+        }
+        self.global_observations.increment(key, 1);
         match update {
             Update::Increment => self.global_trust.increment(key, weighted_delta),
-            Update::Decrement => self.global_trust.decrement(key, weighted_delta)
+            Update::Decrement => floored_decrement(&mut self.global_trust, key, weighted_delta, self.floor)
+        }
+        self.normalize_global();
+    }
+
+    /// updates a global trust value for a given peer from several 
+    /// senders' reports, weighting each by its sender's normalized local 
+    /// trust and normalizing once
+    fn update_global_multi(
+        &mut self, 
+        reports: &[(Self::Key, Self::Value)], 
+        key: &Self::Key, 
+        update: Update
+    ) {
+        if self.strict && !self.global_keys.contains(key) {
+            return
+        }
+
+        let weighted_delta = reports.iter().fold(V::default(), |acc, (sender, trust_delta)| {
+            let sender_trust = self.normalized_local_trust.estimate(sender);
+            acc + self.weight_strategy.weight(*trust_delta, sender_trust)
+        });
+        if self.retain_global_key(key) {
+            self.global_trust.increment(key, self.newcomer_default);
+        }
+        self.global_observations.increment(key, reports.len() as u64);
+        match update {
+            Update::Increment => self.global_trust.increment(key, weighted_delta),
+            Update::Decrement => floored_decrement(&mut self.global_trust, key, weighted_delta, self.floor)
+        }
+        self.normalize_global();
+    }
+
+    /// initializes local trust values for many newly discovered peers 
+    /// and normalizes once
+    fn init_local_batch(&mut self, peers: impl IntoIterator<Item = (Self::Key, Self::Value)>) {
+        peers.into_iter().for_each(|(key, init_value)| {
+            self.mark_seen(&key);
+            self.local_trust.increment(&key, init_value);
+            self.retain_local_key(&key);
+        });
+        self.normalize_local();
+    }
+
+    /// initializes global trust values for many newly discovered peers 
+    /// reported by a single sender and normalizes once. Peers already in 
+    /// the global key set have their estimate replaced, and a sender 
+    /// outside the local key set is ignored, as with `init_global`.
+    fn init_global_batch(
+        &mut self, 
+        sender: &Self::Key, 
+        peers: impl IntoIterator<Item = (Self::Key, Self::Value)>
+    ) {
+        if !self.local_keys.contains(sender) {
+            return
         }
+
+        let sender_trust = self.normalized_local_trust.estimate(sender);
+        peers.into_iter().for_each(|(key, init_value)| {
+            let weighted_init = self.weight_strategy.weight(init_value, sender_trust);
+            self.set_global_init(&key, weighted_init);
+        });
+        self.normalize_global();
+    }
+
+    /// sets the raw local estimate for a given peer to `value`, by 
+    /// decrementing its current estimate and incrementing by `value`. 
+    /// Cells shared with other peers keep their contribution, so the 
+    /// result is exact only in the absence of collisions.
+    fn reset_local(&mut self, key: &Self::Key, value: Self::Value) {
+        let current = self.local_trust.estimate(key);
+        self.mark_seen(key);
+        self.retain_local_key(key);
+        self.local_trust.decrement(key, current);
+        self.local_trust.increment(key, value);
+        self.normalize_local();
+    }
+
+    /// sets the raw global estimate for a given peer to `value`, 
+    /// without weighting it by any sender, see `reset_local`
+    fn reset_global(&mut self, key: &Self::Key, value: Self::Value) {
+        let current = self.global_trust.estimate(key);
+        self.retain_global_key(key);
+        self.global_trust.decrement(key, current);
+        self.global_trust.increment(key, value);
         self.normalize_global();
     }
 
@@ -542,14 +1739,106 @@ where
         self.normalized_global_trust.clone()
     }
 
-    /// normalizes the local trust matrix
+    /// normalizes the local trust matrix, the raw and normalized 
+    /// sketches must share a hasher for the cells to line up
     fn normalize_local(&mut self) {
-        self.normalized_local_trust.matrix = self.local_trust.normalize_estimates();
+        if self.normalization_mode == NormalizationMode::Rank {
+            return self.rank_normalize_local();
+        }
+
+        debug_assert!(self.local_trust.shares_hasher(&self.normalized_local_trust));
+        let normalized = match self.normalization_target {
+            Some(target) => self.local_trust.normalize_estimates_scaled(target),
+            None => self.local_trust.normalize_estimates(),
+        };
+        self.normalized_local_trust.set_matrix(normalized);
+        round_cells(&mut self.normalized_local_trust, &self.rounding);
     }
 
-    /// normalizes the global trust matrix
+    /// normalizes the global trust matrix, the raw and normalized 
+    /// sketches must share a hasher for the cells to line up
     fn normalize_global(&mut self) {
-        self.normalized_global_trust.matrix = self.global_trust.normalize_estimates();
+        if self.normalization_mode == NormalizationMode::Rank {
+            return self.rank_normalize_global();
+        }
+
+        debug_assert!(self.global_trust.shares_hasher(&self.normalized_global_trust));
+        let normalized = match self.normalization_target {
+            Some(target) => self.global_trust.normalize_estimates_scaled(target),
+            None => self.global_trust.normalize_estimates(),
+        };
+        self.normalized_global_trust.set_matrix(normalized);
+        round_cells(&mut self.normalized_global_trust, &self.rounding);
+    }
+
+    /// returns the keys of every peer that has been initialized or 
+    /// updated in the local trust sketch
+    fn local_keys(&self) -> Vec<Self::Key> {
+        self.local_keys.iter().cloned().collect()
+    }
+
+    /// returns the keys of every peer that has been initialized or 
+    /// updated in the global trust sketch
+    fn global_keys(&self) -> Vec<Self::Key> {
+        self.global_keys.iter().cloned().collect()
+    }
+
+    /// returns the (min, max) bounds the underlying `CountMinSketch` 
+    /// instances were configured with
+    fn value_bounds(&self) -> (Self::Value, Self::Value) {
+        (self.local_trust.get_min(), self.local_trust.get_max())
+    }
+
+    /// returns `false`, estimates may overestimate the recorded values 
+    /// due to collisions in the underlying `CountMinSketch` instances
+    fn is_exact(&self) -> bool {
+        false
+    }
+
+    /// returns the sum normalized rows are scaled to, `None` if they 
+    /// sum to 1
+    fn normalization_target(&self) -> Option<Self::Value> {
+        self.normalization_target
+    }
+
+    /// Yields every given key with its bucketized estimate, unknown 
+    /// peers included, like the inherent `bucketize_local`
+    fn bucketize_local_keys<'a, B>(
+        &'a self, 
+        keys: impl Iterator<Item = Self::Key> + 'a, 
+        bucketizer: B
+    ) -> impl Iterator<Item = (Self::Key, usize)> + 'a
+    where 
+        B: BucketizeSingle<Self::Value> + 'a
+    {
+        self.bucketize_sketch(&self.local_trust, keys, bucketizer)
+    }
+
+    /// Scans every cell of the raw and normalized sketches rather than 
+    /// the estimates, since taking the minimum over a key's cells can 
+    /// hide a NaN that only some of them hold
+    fn has_invalid_values(&self) -> bool 
+    where
+        Self::Value: ToPrimitive
+    {
+        self.local_trust.has_invalid_values()
+            || self.global_trust.has_invalid_values()
+            || self.normalized_local_trust.has_invalid_values()
+            || self.normalized_global_trust.has_invalid_values()
+    }
+
+    /// Adds the load factor and the collisions among the local keys to 
+    /// the shared summary
+    fn summary(&self) -> TrustSummary<Self::Key, Self::Value> 
+    where
+        Self::Key: Ord,
+        Self::Value: ToPrimitive
+    {
+        TrustSummary {
+            load_factor: Some(self.load_factor()),
+            estimated_collisions: Some(self.estimated_collision_count(&self.local_keys())),
+            ..local_summary(self)
+        }
     }
 
     /// returns the number of non-default entries in the raw local 
@@ -576,3 +1865,206 @@ where
         self.normalized_global_trust.get_estimate_length()
     }
 }
+
+/// Returns how far the light estimate of a peer's raw local trust is 
+/// from its exact value, `light - precise`, for checking that a 
+/// `LightHonestPeer` stays within its configured error bound across a 
+/// workload applied to both backends. A peer unknown to the precise 
+/// instance counts as having a raw value of `V::default()`. Without 
+/// decrements a `CountMinSketch` never underestimates, so the error is 
+/// never negative; floored decrements on shared cells can make it so, 
+/// which `V` must be signed to represent.
+///
+/// ```
+/// use decentrust::precise::PreciseHonestPeer;
+/// use decentrust::probabilistic::{LightHonestPeer, estimate_error};
+/// use decentrust::honest_peer::HonestPeer;
+///
+/// let mut precise: PreciseHonestPeer<&str, i64> = PreciseHonestPeer::new();
+/// let mut light: LightHonestPeer<&str, i64> = LightHonestPeer::new_from_bounds(
+///     10.0, 0.001, 1000.0, 0, i64::MAX
+/// );
+/// precise.init_local(&"node1", 5);
+/// light.init_local(&"node1", 5);
+///
+/// assert!(estimate_error(&precise, &light, &"node1") >= 0);
+/// ```
+pub fn estimate_error<K, V>(
+    precise: &PreciseHonestPeer<K, V>, 
+    light: &LightHonestPeer<K, V>, 
+    key: &K
+) -> V 
+where
+    K: Eq + Hash + Clone + Debug + ToString,
+    V: AddAssign 
+    + DivAssign 
+    + SubAssign 
+    + Add<Output = V> 
+    + Mul<Output = V> 
+    + Div<Output = V> 
+    + Sub<Output = V> 
+    + Copy 
+    + Default 
+    + Bounded
+    + One
+    + Ord 
+    + Hash
+    + Debug
+{
+    let exact = precise.get_raw_local(key).unwrap_or_default();
+    light.local_trust.estimate(key) - exact
+}
+
+/// Compares the local key sets of a precise and a light instance fed 
+/// the same workload, returning the keys only the precise instance 
+/// knows and the keys only the light instance retains, in that order. 
+/// Both are empty while the backends agree; differences point at keys 
+/// the light instance evicted under `set_key_retention`, peers removed 
+/// from only one side, or updates that reached only one backend.
+///
+/// ```
+/// use decentrust::precise::PreciseHonestPeer;
+/// use decentrust::probabilistic::{LightHonestPeer, key_set_diff};
+/// use decentrust::honest_peer::HonestPeer;
+/// use ordered_float::OrderedFloat;
+///
+/// let mut precise: PreciseHonestPeer<&str, OrderedFloat<f64>> = PreciseHonestPeer::new();
+/// let mut light = LightHonestPeer::<&str, OrderedFloat<f64>>::new();
+/// precise.init_local(&"node1", 1f64.into());
+/// light.init_local(&"node1", 1f64.into());
+///
+/// assert_eq!(key_set_diff(&precise, &light), (vec![], vec![]));
+/// ```
+pub fn key_set_diff<K, V>(
+    precise: &PreciseHonestPeer<K, V>, 
+    light: &LightHonestPeer<K, V>
+) -> (Vec<K>, Vec<K>) 
+where
+    K: Eq + Hash + Clone + Debug + ToString,
+    V: AddAssign 
+    + DivAssign 
+    + SubAssign 
+    + Add<Output = V> 
+    + Mul<Output = V> 
+    + Div<Output = V> 
+    + Sub<Output = V> 
+    + Copy 
+    + Default 
+    + Bounded
+    + One
+    + Ord 
+    + Hash
+    + Debug
+{
+    let precise_keys: HashSet<K> = precise.local_keys().into_iter().collect();
+    let precise_only = precise_keys.iter()
+        .filter(|key| !light.local_keys.contains(*key))
+        .cloned()
+        .collect();
+    let light_only = light.local_keys.iter()
+        .filter(|key| !precise_keys.contains(*key))
+        .cloned()
+        .collect();
+
+    (precise_only, light_only)
+}
+
+/// Rounds every cell of a normalized sketch, if a rounding is set
+fn round_cells<V>(sketch: &mut CountMinSketch<V>, rounding: &Option<(u32, Rounding<V>)>) 
+where
+    V: AddAssign 
+    + DivAssign 
+    + SubAssign 
+    + Add<Output = V> 
+    + Copy 
+    + Default 
+    + Bounded 
+    + Ord 
+    + Hash
+    + Debug
+{
+    let Some((_, round)) = rounding else { return };
+    let rounded = sketch.matrix().iter()
+        .map(|row| row.iter().map(|cell| round(*cell)).collect())
+        .collect();
+    sketch.set_matrix(rounded);
+}
+
+/// Decrements the estimate for `key` by `delta`, taking only as much as 
+/// lies above `floor`
+fn floored_decrement<K, V>(sketch: &mut CountMinSketch<V>, key: &K, delta: V, floor: V) 
+where
+    K: Hash + ToString,
+    V: AddAssign 
+    + DivAssign 
+    + SubAssign 
+    + Add<Output = V> 
+    + Sub<Output = V> 
+    + Copy 
+    + Default 
+    + Bounded 
+    + Ord 
+    + Hash
+    + Debug
+{
+    let current = sketch.estimate(key);
+    let taken = current - floored_sub(current, delta, floor);
+    sketch.decrement(key, taken);
+}
+
+/// Overwrites a normalized sketch with the percentile ranks of the given 
+/// entries, recorded into its cells from scratch
+fn rank_into<K, V>(normalized: &mut CountMinSketch<V>, entries: Vec<(K, V)>) 
+where
+    K: Hash + ToString,
+    V: AddAssign 
+    + DivAssign 
+    + SubAssign 
+    + Add<Output = V> 
+    + Div<Output = V> 
+    + Copy 
+    + Default 
+    + Bounded
+    + One
+    + Ord 
+    + Hash
+    + Debug
+{
+    let empty = vec![vec![V::default(); normalized.width()]; normalized.depth()];
+    normalized.set_matrix(empty);
+    percentile_ranks(entries).into_iter().for_each(|(key, rank)| {
+        normalized.increment(&key, rank);
+    });
+}
+
+/// Creates an empty observation count sketch with the dimensions and 
+/// seed of `sketch`
+fn observation_sketch<V>(sketch: &CountMinSketch<V>) -> CountMinSketch<u64>
+where
+    V: AddAssign 
+    + DivAssign 
+    + SubAssign 
+    + Add<Output = V> 
+    + Copy 
+    + Default 
+    + Bounded 
+    + Ord 
+    + Hash
+    + Debug
+{
+    CountMinSketch::new_with_seed(sketch.width(), sketch.depth(), 0, u64::MAX, sketch.seed())
+}
+
+/// Evicts the least recently updated keys from a retained key set until 
+/// it holds no more than `limit` keys
+fn evict_oldest<K>(keys: &mut HashSet<K>, recency: &mut RecencyIndex<K>, limit: usize) 
+where
+    K: Eq + Hash + Clone
+{
+    while recency.len() > limit {
+        match recency.pop_oldest() {
+            Some(key) => { keys.remove(&key); },
+            None => break,
+        }
+    }
+}
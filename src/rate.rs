@@ -0,0 +1,76 @@
+use std::time::{Duration, Instant};
+
+/// How many reports a single sender may make, as a token bucket: each
+/// report takes a token, a sender starts with `burst` tokens, and tokens
+/// are refilled at `per_second` up to `burst`. A sender can make a burst
+/// of reports at once, but cannot sustain more than `per_second` reports
+/// a second.
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use decentrust::rate::{RateLimit, TokenBucket};
+///
+/// let limit = RateLimit::new(2, 1.0);
+/// let start = Instant::now();
+/// let mut bucket = TokenBucket::new(limit, start);
+///
+/// assert!(bucket.try_take(start));
+/// assert!(bucket.try_take(start));
+/// assert!(!bucket.try_take(start));
+/// assert!(bucket.try_take(start + Duration::from_secs(1)));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// The most reports a sender can make at once
+    pub burst: u32,
+    /// The number of reports a sender regains each second
+    pub per_second: f64,
+}
+
+impl RateLimit {
+    /// Creates a limit allowing bursts of `burst` reports, refilled at
+    /// `per_second` reports a second. Negative or NaN rates never refill.
+    pub fn new(burst: u32, per_second: f64) -> Self {
+        RateLimit { burst, per_second }
+    }
+}
+
+/// The tokens a single sender has left under a `RateLimit`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket, refilling from `now`
+    pub fn new(limit: RateLimit, now: Instant) -> Self {
+        TokenBucket { limit, tokens: limit.burst as f64, refilled_at: now }
+    }
+
+    /// Refills the tokens earned since the last call, then takes a token
+    /// if one is left. Returns whether a token was taken, i.e. whether
+    /// the report is within the limit.
+    pub fn try_take(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens < 1.0 {
+            return false;
+        }
+
+        self.tokens -= 1.0;
+        true
+    }
+
+    /// Returns the number of whole tokens left as of the last refill
+    pub fn tokens(&self) -> u32 {
+        self.tokens as u32
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed: Duration = now.saturating_duration_since(self.refilled_at);
+        let earned = elapsed.as_secs_f64() * self.limit.per_second.max(0.0);
+        self.tokens = (self.tokens + earned).min(self.limit.burst as f64);
+        self.refilled_at = self.refilled_at.max(now);
+    }
+}
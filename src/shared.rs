@@ -0,0 +1,135 @@
+use std::sync::Arc;
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::honest_peer::{HonestPeer, Update};
+
+/// A cloneable, thread safe handle to an `HonestPeer` implementation 
+/// for use from many async tasks at once. Every clone refers to the 
+/// same underlying instance, and each method acquires a
+/// `tokio::sync::RwLock` internally, so callers do not have to manage 
+/// synchronization themselves. Requires the `async` feature.
+///
+/// ```
+/// use decentrust::shared::SharedHonestPeer;
+/// use decentrust::precise::PreciseHonestPeer;
+/// use decentrust::honest_peer::Update;
+/// use ordered_float::OrderedFloat;
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let hp: SharedHonestPeer<PreciseHonestPeer<String, OrderedFloat<f64>>> = {
+///     SharedHonestPeer::new(PreciseHonestPeer::new())
+/// };
+///
+/// let handle = hp.clone();
+/// handle.init_local(&"node1".to_string(), 5f64.into()).await;
+/// hp.update_local(&"node1".to_string(), 3f64.into(), Update::Increment).await;
+///
+/// assert_eq!(hp.get_raw_local(&"node1".to_string()).await, Some(OrderedFloat::from(8.0)));
+/// # });
+/// ```
+pub struct SharedHonestPeer<H: HonestPeer> {
+    inner: Arc<RwLock<H>>
+}
+
+impl<H: HonestPeer> Clone for SharedHonestPeer<H> {
+    fn clone(&self) -> Self {
+        SharedHonestPeer { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<H: HonestPeer> SharedHonestPeer<H> {
+    /// Wraps an `HonestPeer` instance so it can be shared between tasks
+    pub fn new(peer: H) -> Self {
+        SharedHonestPeer { inner: Arc::new(RwLock::new(peer)) }
+    }
+
+    /// Acquires a read lock on the underlying instance, for reading 
+    /// several values from a consistent state
+    pub async fn read(&self) -> RwLockReadGuard<'_, H> {
+        self.inner.read().await
+    }
+
+    /// Acquires a write lock on the underlying instance, for applying 
+    /// several updates atomically
+    pub async fn write(&self) -> RwLockWriteGuard<'_, H> {
+        self.inner.write().await
+    }
+
+    pub async fn init_local(&self, key: &H::Key, init_value: H::Value) {
+        self.inner.write().await.init_local(key, init_value)
+    }
+
+    pub async fn update_local(&self, key: &H::Key, trust_delta: H::Value, update: Update) {
+        self.inner.write().await.update_local(key, trust_delta, update)
+    }
+
+    pub async fn get_raw_local(&self, key: &H::Key) -> Option<H::Value> {
+        self.inner.read().await.get_raw_local(key)
+    }
+
+    pub async fn get_normalized_local(&self, key: &H::Key) -> Option<H::Value> {
+        self.inner.read().await.get_normalized_local(key)
+    }
+
+    pub async fn init_global(&self, sender: &H::Key, key: &H::Key, init_value: H::Value) {
+        self.inner.write().await.init_global(sender, key, init_value)
+    }
+
+    pub async fn update_global(
+        &self, 
+        sender: &H::Key, 
+        key: &H::Key, 
+        trust_delta: H::Value, 
+        update: Update
+    ) {
+        self.inner.write().await.update_global(sender, key, trust_delta, update)
+    }
+
+    pub async fn update_global_multi(
+        &self, 
+        reports: &[(H::Key, H::Value)], 
+        key: &H::Key, 
+        update: Update
+    ) {
+        self.inner.write().await.update_global_multi(reports, key, update)
+    }
+
+    pub async fn reset_local(&self, key: &H::Key, value: H::Value) {
+        self.inner.write().await.reset_local(key, value)
+    }
+
+    pub async fn reset_global(&self, key: &H::Key, value: H::Value) {
+        self.inner.write().await.reset_global(key, value)
+    }
+
+    pub async fn get_raw_global(&self, key: &H::Key) -> Option<H::Value> {
+        self.inner.read().await.get_raw_global(key)
+    }
+
+    pub async fn get_normalized_global(&self, key: &H::Key) -> Option<H::Value> {
+        self.inner.read().await.get_normalized_global(key)
+    }
+
+    pub async fn get_raw_local_map(&self) -> H::Map {
+        self.inner.read().await.get_raw_local_map()
+    }
+
+    pub async fn get_normalized_local_map(&self) -> H::Map {
+        self.inner.read().await.get_normalized_local_map()
+    }
+
+    pub async fn get_raw_global_map(&self) -> H::Map {
+        self.inner.read().await.get_raw_global_map()
+    }
+
+    pub async fn get_normalized_global_map(&self) -> H::Map {
+        self.inner.read().await.get_normalized_global_map()
+    }
+
+    pub async fn local_keys(&self) -> Vec<H::Key> {
+        self.inner.read().await.local_keys()
+    }
+
+    pub async fn global_keys(&self) -> Vec<H::Key> {
+        self.inner.read().await.global_keys()
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+use arc_swap::ArcSwap;
+use crate::honest_peer::HonestPeer;
+
+/// A cloneable, thread safe handle to an `HonestPeer` implementation
+/// for read-heavy workloads, where trust is queried constantly but only
+/// updated now and then. Readers load the current version without
+/// taking a lock, and always see a complete, consistent version.
+/// Writers clone the current version, apply their changes to the copy
+/// and swap it in, so every update costs a full clone of the instance.
+/// Prefer `SharedHonestPeer` when updates are frequent. Requires the
+/// `snapshot` feature.
+///
+/// ```
+/// use decentrust::snapshot::SnapshotHonestPeer;
+/// use decentrust::precise::PreciseHonestPeer;
+/// use decentrust::honest_peer::HonestPeer;
+/// use ordered_float::OrderedFloat;
+///
+/// let hp: SnapshotHonestPeer<PreciseHonestPeer<&str, OrderedFloat<f64>>> = {
+///     SnapshotHonestPeer::new(PreciseHonestPeer::new())
+/// };
+///
+/// let handle = hp.clone();
+/// handle.update(|peer| {
+///     peer.init_local(&"node1", 1f64.into());
+///     peer.init_global(&"node1", &"node2", 4f64.into());
+/// });
+///
+/// assert_eq!(hp.get_normalized_global(&"node2"), Some(OrderedFloat::from(1.0)));
+/// ```
+pub struct SnapshotHonestPeer<H: HonestPeer> {
+    inner: Arc<ArcSwap<H>>
+}
+
+impl<H: HonestPeer> Clone for SnapshotHonestPeer<H> {
+    fn clone(&self) -> Self {
+        SnapshotHonestPeer { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<H: HonestPeer + Clone> SnapshotHonestPeer<H> {
+    /// Wraps an `HonestPeer` instance so it can be read from many
+    /// threads without locking
+    pub fn new(peer: H) -> Self {
+        SnapshotHonestPeer { inner: Arc::new(ArcSwap::from_pointee(peer)) }
+    }
+
+    /// Returns the current version, for reading several values from
+    /// the same state. Updates made after this call are not visible
+    /// through the returned snapshot.
+    pub fn snapshot(&self) -> Arc<H> {
+        self.inner.load_full()
+    }
+
+    /// Applies `f` to a copy of the current version and swaps the copy
+    /// in, so that readers see either none or all of its changes. If
+    /// another writer swaps in a version first, `f` is applied again to
+    /// a copy of that version, so it may run more than once.
+    pub fn update<F>(&self, mut f: F)
+    where
+        F: FnMut(&mut H)
+    {
+        self.inner.rcu(|current| {
+            let mut next = H::clone(current);
+            f(&mut next);
+            next
+        });
+    }
+
+    /// Replaces the current version with `peer`
+    pub fn store(&self, peer: H) {
+        self.inner.store(Arc::new(peer));
+    }
+
+    pub fn get_raw_local(&self, key: &H::Key) -> Option<H::Value> {
+        self.inner.load().get_raw_local(key)
+    }
+
+    pub fn get_normalized_local(&self, key: &H::Key) -> Option<H::Value> {
+        self.inner.load().get_normalized_local(key)
+    }
+
+    pub fn get_raw_global(&self, key: &H::Key) -> Option<H::Value> {
+        self.inner.load().get_raw_global(key)
+    }
+
+    pub fn get_normalized_global(&self, key: &H::Key) -> Option<H::Value> {
+        self.inner.load().get_normalized_global(key)
+    }
+
+    pub fn local_keys(&self) -> Vec<H::Key> {
+        self.inner.load().local_keys()
+    }
+
+    pub fn global_keys(&self) -> Vec<H::Key> {
+        self.inner.load().global_keys()
+    }
+}
@@ -0,0 +1,190 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{AddAssign, DivAssign, SubAssign, MulAssign, Add, Mul, Div, Sub};
+use num_traits::{Bounded, One, ToPrimitive};
+
+/// An `f64` trust value implementing every bound required by 
+/// `PreciseHonestPeer` and `LightHonestPeer`, so plain floating point 
+/// trust can be tracked without wrapping values in `OrderedFloat`.
+///
+/// NaN is given a single canonical representation and orders above 
+/// every other value, including positive infinity, and `-0.0` is 
+/// stored as `0.0`. This keeps `Eq`, `Ord` and `Hash` consistent with 
+/// one another, e.g. the NaN produced by normalizing an empty total 
+/// equals every other NaN.
+///
+/// ```
+/// use decentrust::precise::PreciseHonestPeer;
+/// use decentrust::honest_peer::{HonestPeer, Update};
+/// use decentrust::trust::Trust;
+///
+/// let mut hp: PreciseHonestPeer<String, Trust> = PreciseHonestPeer::new();
+///
+/// hp.init_local(&"node1".to_string(), 3.0.into());
+/// hp.init_local(&"node2".to_string(), 1.0.into());
+///
+/// assert_eq!(hp.get_normalized_local(&"node1".to_string()), Some(Trust::new(0.75)));
+/// assert!(Trust::new(f64::NAN) > Trust::new(f64::INFINITY));
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct Trust(f64);
+
+impl Trust {
+    /// Creates a new `Trust` value, canonicalizing NaN and `-0.0`
+    pub fn new(value: f64) -> Self {
+        if value.is_nan() {
+            Trust(f64::NAN)
+        } else if value == 0.0 {
+            Trust(0.0)
+        } else {
+            Trust(value)
+        }
+    }
+
+    /// Returns the underlying `f64`
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+
+    /// Returns `true` if the value is NaN
+    pub fn is_nan(&self) -> bool {
+        self.0.is_nan()
+    }
+}
+
+impl From<f64> for Trust {
+    fn from(value: f64) -> Self {
+        Trust::new(value)
+    }
+}
+
+impl From<Trust> for f64 {
+    fn from(trust: Trust) -> Self {
+        trust.0
+    }
+}
+
+impl PartialEq for Trust {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Trust {}
+
+impl PartialOrd for Trust {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Trust {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Hash for Trust {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state)
+    }
+}
+
+impl fmt::Debug for Trust {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for Trust {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl Bounded for Trust {
+    fn min_value() -> Self {
+        Trust(f64::MIN)
+    }
+
+    fn max_value() -> Self {
+        Trust(f64::MAX)
+    }
+}
+
+impl One for Trust {
+    fn one() -> Self {
+        Trust(1.0)
+    }
+}
+
+impl ToPrimitive for Trust {
+    fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.0.to_u64()
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(self.0)
+    }
+}
+
+impl Add for Trust {
+    type Output = Trust;
+
+    fn add(self, rhs: Trust) -> Trust {
+        Trust::new(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Trust {
+    type Output = Trust;
+
+    fn sub(self, rhs: Trust) -> Trust {
+        Trust::new(self.0 - rhs.0)
+    }
+}
+
+impl Mul for Trust {
+    type Output = Trust;
+
+    fn mul(self, rhs: Trust) -> Trust {
+        Trust::new(self.0 * rhs.0)
+    }
+}
+
+impl Div for Trust {
+    type Output = Trust;
+
+    fn div(self, rhs: Trust) -> Trust {
+        Trust::new(self.0 / rhs.0)
+    }
+}
+
+impl AddAssign for Trust {
+    fn add_assign(&mut self, rhs: Trust) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Trust {
+    fn sub_assign(&mut self, rhs: Trust) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Trust {
+    fn mul_assign(&mut self, rhs: Trust) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Trust {
+    fn div_assign(&mut self, rhs: Trust) {
+        *self = *self / rhs;
+    }
+}
@@ -0,0 +1,338 @@
+use core::ops::{AddAssign, Div, Mul, Sub, SubAssign};
+use num_traits::{Bounded, ToPrimitive};
+#[cfg(feature = "std")]
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use core::ops::Add;
+#[cfg(feature = "std")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use num_traits::{NumCast, One};
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+
+/// Returns `true` if `a` and `b` differ by strictly less than 
+/// `epsilon`. Use this rather than `==` when comparing trust values 
+/// produced by floating point arithmetic, e.g. normalized trust.
+///
+/// ```
+/// use decentrust::util::trust_approx_eq;
+/// use ordered_float::OrderedFloat;
+///
+/// let a = OrderedFloat::from(0.1 + 0.2);
+/// let b = OrderedFloat::from(0.3);
+///
+/// assert!(a != b);
+/// assert!(trust_approx_eq(a, b, OrderedFloat::from(1e-9)));
+/// ```
+pub fn trust_approx_eq<V>(a: V, b: V, epsilon: V) -> bool 
+where
+    V: Sub<Output = V> + PartialOrd + Copy
+{
+    let diff = if a > b { a - b } else { b - a };
+    diff < epsilon
+}
+
+/// Subtracts `delta` from `current`, clamping the result at `floor`. 
+/// Values already at or below `floor` are left as they are rather than 
+/// raised to it, and since `current - floor` is only taken when it is 
+/// positive, unsigned values never underflow.
+#[cfg(feature = "std")]
+pub(crate) fn floored_sub<V>(current: V, delta: V, floor: V) -> V 
+where
+    V: Sub<Output = V> + PartialOrd + Copy
+{
+    if current <= floor {
+        return current;
+    }
+
+    if delta > current - floor {
+        floor
+    } else {
+        current - delta
+    }
+}
+
+/// Adds `delta` to `current`, saturating at `V::max_value()` when a 
+/// positive delta would overflow and at `V::min_value()` when a 
+/// negative one would. The headroom is computed before adding, so 
+/// integer values never wrap or panic.
+pub(crate) fn saturating_add<V>(current: V, delta: V) -> V 
+where
+    V: AddAssign + SubAssign + PartialOrd + Copy + Default + Bounded
+{
+    if delta > V::default() {
+        let mut headroom = V::max_value();
+        headroom -= delta;
+        if current > headroom {
+            return V::max_value();
+        }
+    } else if delta < V::default() {
+        let mut legroom = V::min_value();
+        legroom -= delta;
+        if current < legroom {
+            return V::min_value();
+        }
+    }
+
+    let mut sum = current;
+    sum += delta;
+    sum
+}
+
+/// Subtracts `delta` from `current`, the mirror of `saturating_add`: 
+/// saturating at `V::min_value()` when a positive delta would 
+/// underflow and at `V::max_value()` when a negative one would 
+/// overflow.
+pub(crate) fn saturating_sub<V>(current: V, delta: V) -> V 
+where
+    V: AddAssign + SubAssign + PartialOrd + Copy + Default + Bounded
+{
+    if delta > V::default() {
+        let mut legroom = V::min_value();
+        legroom += delta;
+        if current < legroom {
+            return V::min_value();
+        }
+    } else if delta < V::default() {
+        let mut headroom = V::max_value();
+        headroom += delta;
+        if current > headroom {
+            return V::max_value();
+        }
+    }
+
+    let mut difference = current;
+    difference -= delta;
+    difference
+}
+
+/// Returns `value * target / total`, the share of `target` that `value` 
+/// is of `total`. Where `value * target` would overflow `V`, `total` is 
+/// divided by `target` first instead, so integer values give up some 
+/// precision rather than wrapping or panicking, and the share 
+/// saturates at `target` if that leaves nothing to divide by. Floats 
+/// always take the exact path.
+pub(crate) fn scaled_share<V>(value: V, total: V, target: V) -> V 
+where
+    V: Mul<Output = V> + Div<Output = V> + PartialOrd + Copy + Default + Bounded
+{
+    if target <= V::default() || value <= V::max_value() / target {
+        return value * target / total;
+    }
+
+    let divisor = total / target;
+    if divisor == V::default() {
+        target
+    } else {
+        value / divisor
+    }
+}
+
+/// Rounds a normalized value, see `decimal_rounding`
+#[cfg(feature = "std")]
+pub(crate) type Rounding<V> = Arc<dyn Fn(V) -> V + Send + Sync>;
+
+/// Returns a function rounding values to `decimals` decimal places 
+/// through their `f64` conversion. Values that cannot be converted 
+/// either way are left as they are, as are integers, which have no 
+/// fractional digits to round. Beyond `f64::DIGITS` decimal places 
+/// there is no precision left to round away, and the scaling factor 
+/// would eventually overflow to infinity and turn every value into 
+/// NaN, so values are left as they are.
+#[cfg(feature = "std")]
+pub(crate) fn decimal_rounding<V>(decimals: u32) -> Rounding<V> 
+where
+    V: ToPrimitive + NumCast + Copy
+{
+    if decimals > f64::DIGITS {
+        return Arc::new(|value: V| value);
+    }
+
+    let factor = 10f64.powi(decimals as i32);
+    Arc::new(move |value: V| {
+        value.to_f64()
+            .and_then(|raw| <V as NumCast>::from((raw * factor).round() / factor))
+            .unwrap_or(value)
+    })
+}
+
+/// Returns whether a value is NaN or infinite once converted to `f64`. 
+/// Values that cannot be converted are treated as invalid.
+pub(crate) fn is_invalid<V: ToPrimitive>(value: &V) -> bool {
+    value.to_f64().map_or(true, |value| !value.is_finite())
+}
+
+/// Replaces each value with its percentile rank among all values, i.e. 
+/// the fraction of values less than or equal to it. Ties share a rank 
+/// and the largest value ranks 1. As with sum normalization, integer 
+/// values truncate every rank but the top to 0.
+#[cfg(feature = "std")]
+pub(crate) fn percentile_ranks<K, V>(entries: Vec<(K, V)>) -> Vec<(K, V)> 
+where
+    V: Add<Output = V> + Div<Output = V> + PartialOrd + Copy + Default + One
+{
+    let one = V::one();
+    let mut sorted: Vec<V> = entries.iter().map(|(_, v)| *v).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+
+    // counts[i] holds i + 1 expressed in V
+    let counts: Vec<V> = sorted.iter()
+        .scan(V::default(), |count, _| {
+            *count = *count + one;
+            Some(*count)
+        })
+        .collect();
+    let total = counts.last().copied().unwrap_or_default();
+
+    entries.into_iter()
+        .map(|(k, v)| {
+            let at_or_below = sorted.partition_point(|x| *x <= v);
+            let rank = match at_or_below {
+                0 => V::default(),
+                n => counts[n - 1] / total,
+            };
+            (k, rank)
+        })
+        .collect()
+}
+
+/// Returns the nearest-rank `q` quantile of the provided values, 
+/// i.e. the smallest value such that at least `q` of all values are 
+/// less than or equal to it. Returns `None` if there are no values or 
+/// `q` is outside of `[0, 1]`.
+#[cfg(feature = "std")]
+pub(crate) fn nearest_rank_quantile<V>(
+    values: impl Iterator<Item = V>, 
+    q: f64
+) -> Option<V>
+where
+    V: PartialOrd + Copy
+{
+    if !(0.0..=1.0).contains(&q) {
+        return None;
+    }
+
+    let mut sorted: Vec<V> = values.collect();
+    if sorted.is_empty() {
+        return None;
+    }
+
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+    let rank = f64::ceil(q * sorted.len() as f64) as usize;
+
+    Some(sorted[rank.saturating_sub(1).min(sorted.len() - 1)])
+}
+
+/// Tracks the highest bucket index produced by the `bucketize_*` 
+/// methods of an `HonestPeer` implementation. The `buckets` crate 
+/// does not report how many buckets a bucketizer produces, so the 
+/// tracker records it as bucket indices are yielded. Every index seen 
+/// so far is within `0..bucket_count()`.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub(crate) struct BucketTracker {
+    count: AtomicUsize
+}
+
+#[cfg(feature = "std")]
+impl BucketTracker {
+    /// Records a bucket index and returns it unchanged
+    pub(crate) fn observe(&self, bucket: usize) -> usize {
+        self.count.fetch_max(bucket.saturating_add(1), Ordering::Relaxed);
+        bucket
+    }
+
+    /// Returns the highest bucket index seen so far, or `None` if no 
+    /// buckets have been produced yet
+    pub(crate) fn max_bucket_seen(&self) -> Option<usize> {
+        self.count.load(Ordering::Relaxed).checked_sub(1)
+    }
+
+    /// Returns the number of buckets seen so far, i.e. one more than 
+    /// the highest bucket index
+    pub(crate) fn bucket_count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clone for BucketTracker {
+    fn clone(&self) -> Self {
+        BucketTracker {
+            count: AtomicUsize::new(self.bucket_count())
+        }
+    }
+}
+
+/// Orders keys by when they were last touched, so that the least 
+/// recently touched key can be evicted first
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub(crate) struct RecencyIndex<K> {
+    ticks: HashMap<K, u64>,
+    order: BTreeMap<u64, K>,
+    next_tick: u64,
+}
+
+#[cfg(feature = "std")]
+impl<K> Default for RecencyIndex<K> {
+    fn default() -> Self {
+        RecencyIndex { ticks: HashMap::new(), order: BTreeMap::new(), next_tick: 0 }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Eq + Hash + Clone> RecencyIndex<K> {
+    /// Marks a key as the most recently touched one
+    pub(crate) fn touch(&mut self, key: &K) {
+        if let Some(tick) = self.ticks.insert(key.clone(), self.next_tick) {
+            self.order.remove(&tick);
+        }
+        self.order.insert(self.next_tick, key.clone());
+        self.next_tick += 1;
+    }
+
+    /// Removes and returns the least recently touched key
+    pub(crate) fn pop_oldest(&mut self) -> Option<K> {
+        let (_, key) = self.order.pop_first()?;
+        self.ticks.remove(&key);
+        Some(key)
+    }
+
+    /// Forgets a single key
+    pub(crate) fn remove(&mut self, key: &K) {
+        if let Some(tick) = self.ticks.remove(key) {
+            self.order.remove(&tick);
+        }
+    }
+
+    /// Returns the number of keys in the index
+    pub(crate) fn len(&self) -> usize {
+        self.ticks.len()
+    }
+
+    /// Forgets every key
+    pub(crate) fn clear(&mut self) {
+        self.ticks.clear();
+        self.order.clear();
+    }
+}
+
+/// Counts how many keys fall into each bucket index of a bucketized 
+/// trust iterator
+#[cfg(feature = "std")]
+pub(crate) fn bucket_histogram<K>(
+    bucketed: impl Iterator<Item = (K, usize)>
+) -> HashMap<usize, usize> {
+    let mut histogram = HashMap::new();
+    bucketed.for_each(|(_, bucket)| {
+        *histogram.entry(bucket).or_insert(0) += 1;
+    });
+
+    histogram
+}
@@ -0,0 +1,56 @@
+use std::ops::Mul;
+
+/// Decides how much a reported trust delta counts towards a peer's
+/// global trust, given the normalized local trust of the sender who
+/// reported it. Both `PreciseHonestPeer` and `LightHonestPeer` apply
+/// their strategy to every `init_global*` and `update_global*` call.
+///
+/// Strategies are shared behind an `Arc` and must be `Send + Sync` so
+/// that trust stores stay usable across threads.
+///
+/// ```
+/// use decentrust::weight::WeightStrategy;
+/// use decentrust::precise::PreciseHonestPeer;
+/// use decentrust::honest_peer::HonestPeer;
+/// use ordered_float::OrderedFloat;
+///
+/// // Reports only count if the sender holds at least half of the trust
+/// struct Gated;
+///
+/// impl WeightStrategy<OrderedFloat<f64>> for Gated {
+///     fn weight(
+///         &self,
+///         delta: OrderedFloat<f64>,
+///         sender_trust: OrderedFloat<f64>
+///     ) -> OrderedFloat<f64> {
+///         if sender_trust >= OrderedFloat::from(0.5) { delta } else { 0f64.into() }
+///     }
+/// }
+///
+/// let mut hp: PreciseHonestPeer<&str, OrderedFloat<f64>> = {
+///     PreciseHonestPeer::new().with_weight_strategy(Gated)
+/// };
+/// hp.init_local(&"node1", 3f64.into());
+/// hp.init_local(&"node2", 1f64.into());
+///
+/// hp.init_global(&"node1", &"node3", 4f64.into());
+/// hp.init_global(&"node2", &"node4", 4f64.into());
+///
+/// assert_eq!(hp.get_raw_global(&"node3"), Some(OrderedFloat::from(4.0)));
+/// assert_eq!(hp.get_raw_global(&"node4"), Some(OrderedFloat::from(0.0)));
+/// ```
+pub trait WeightStrategy<V>: Send + Sync {
+    /// Returns the weighted delta to apply to the reported peer
+    fn weight(&self, delta: V, sender_trust: V) -> V;
+}
+
+/// The default strategy, weighting each delta by multiplying it with
+/// the sender's normalized local trust
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Multiplicative;
+
+impl<V: Mul<Output = V>> WeightStrategy<V> for Multiplicative {
+    fn weight(&self, delta: V, sender_trust: V) -> V {
+        delta * sender_trust
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::{AddAssign, DivAssign, SubAssign, Add, Mul, Div, Sub};
+use num_traits::Bounded;
+use std::marker::PhantomData;
+use crate::cms::CountMinSketch;
+use crate::honest_peer::Update;
+use std::fmt::Debug;
+
+/// A sliding-window variant of `LightHonestPeer`. Rather than a single
+/// raw sketch for local and global trust, it keeps a ring of `N`
+/// `CountMinSketch` sub-windows. Updates are always recorded in the
+/// newest window, and estimates sum over all windows currently in the
+/// ring. Calling `advance_window` pushes a fresh window and drops the
+/// oldest one, so behaviour recorded more than `N` windows ago no
+/// longer contributes to trust, without any explicit decay factors.
+///
+/// All windows and the normalized sketches are cloned from a single
+/// seeded `CountMinSketch`, so every sketch hashes items identically.
+///
+/// ```
+/// use decentrust::windowed::WindowedLightHonestPeer;
+/// use decentrust::honest_peer::Update;
+/// use ordered_float::OrderedFloat;
+///
+/// let mut hp = WindowedLightHonestPeer::<String, OrderedFloat<f64>>::new(2);
+///
+/// hp.update_local(&"node1".to_string(), 5f64.into(), Update::Increment);
+/// hp.advance_window();
+/// hp.update_local(&"node1".to_string(), 3f64.into(), Update::Increment);
+///
+/// assert_eq!(hp.get_raw_local(&"node1".to_string()), Some(OrderedFloat::from(8.0)));
+///
+/// hp.advance_window();
+///
+/// assert_eq!(hp.get_raw_local(&"node1".to_string()), Some(OrderedFloat::from(3.0)));
+/// ```
+pub struct WindowedLightHonestPeer<K, V>
+where
+    K: Eq + Hash + Clone + Debug + ToString,
+    V: AddAssign
+    + DivAssign
+    + SubAssign
+    + Add<Output = V>
+    + Mul<Output = V>
+    + Div<Output = V>
+    + Sub<Output = V>
+    + Copy
+    + Default
+    + Bounded
+    + Ord
+    + Hash
+    + Debug
+{
+    template: CountMinSketch<V>,
+    local_windows: VecDeque<CountMinSketch<V>>,
+    global_windows: VecDeque<CountMinSketch<V>>,
+    normalized_local_trust: CountMinSketch<V>,
+    normalized_global_trust: CountMinSketch<V>,
+    local_keys: HashSet<K>,
+    global_keys: HashSet<K>,
+    pub id_type: Option<PhantomData<K>>
+}
+
+impl<K, V> WindowedLightHonestPeer<K, V>
+where
+    K: Eq + Hash + Clone + Debug + ToString,
+    V: AddAssign
+    + DivAssign
+    + SubAssign
+    + Add<Output = V>
+    + Mul<Output = V>
+    + Div<Output = V>
+    + Sub<Output = V>
+    + Copy
+    + Default
+    + Bounded
+    + Ord
+    + Hash
+    + Debug
+{
+    /// Creates a new `WindowedLightHonestPeer` with `windows` default
+    /// sized sub-windows and no peers in it. At least one window is
+    /// always kept.
+    pub fn new(windows: usize) -> Self {
+        WindowedLightHonestPeer::from_sketch(windows, CountMinSketch::<V>::default())
+    }
+
+    /// Creates a new `WindowedLightHonestPeer` with `windows`
+    /// sub-windows, each sized from a given `CountMinSketch` error
+    /// bound, an overestimation probability, and the maximum expected
+    /// number of entries. Panics if the parameters are invalid, see
+    /// `CountMinSketch::try_new_from_bounds`.
+    ///
+    /// ```
+    /// use decentrust::windowed::WindowedLightHonestPeer;
+    /// use ordered_float::OrderedFloat;
+    /// use num_traits::Bounded;
+    ///
+    /// let hp = WindowedLightHonestPeer::<String, OrderedFloat<f64>>::new_from_bounds(
+    ///     4,
+    ///     50f64,
+    ///     0.0001f64,
+    ///     3000f64,
+    ///     OrderedFloat::<f64>::min_value(),
+    ///     OrderedFloat::<f64>::max_value()
+    /// );
+    ///
+    /// assert_eq!(hp.window_count(), 4);
+    /// ```
+    pub fn new_from_bounds(
+        windows: usize,
+        error_bound: f64,
+        probability: f64,
+        max_entries: f64,
+        min: V,
+        max: V
+    ) -> Self {
+        let sketch = CountMinSketch::new_from_bounds(
+            error_bound,
+            probability,
+            max_entries,
+            min,
+            max
+        );
+
+        WindowedLightHonestPeer::from_sketch(windows, sketch)
+    }
+
+    fn from_sketch(windows: usize, sketch: CountMinSketch<V>) -> Self {
+        let ring: VecDeque<CountMinSketch<V>> = {
+            (0..windows.max(1)).map(|_| sketch.clone()).collect()
+        };
+
+        WindowedLightHonestPeer {
+            template: sketch.clone(),
+            local_windows: ring.clone(),
+            global_windows: ring,
+            normalized_local_trust: sketch.clone(),
+            normalized_global_trust: sketch,
+            local_keys: HashSet::new(),
+            global_keys: HashSet::new(),
+            id_type: None
+        }
+    }
+
+    /// Returns the number of sub-windows kept in the ring
+    pub fn window_count(&self) -> usize {
+        self.local_windows.len()
+    }
+
+    /// Rotates the ring, pushing a fresh, empty window which receives
+    /// all subsequent updates and dropping the oldest window along
+    /// with everything recorded in it. Both normalized sketches are
+    /// recomputed afterwards.
+    pub fn advance_window(&mut self) {
+        self.local_windows.pop_front();
+        self.local_windows.push_back(self.template.clone());
+        self.global_windows.pop_front();
+        self.global_windows.push_back(self.template.clone());
+        self.normalize_local();
+        self.normalize_global();
+    }
+
+    /// Initalizes a local trust value for a newly discovered peer in
+    /// the current window
+    pub fn init_local(&mut self, key: &K, init_value: V) {
+        self.update_local(key, init_value, Update::Increment);
+    }
+
+    /// Updates a local trust value for a given peer. Increments are
+    /// recorded in the current window, decrements consume trust from
+    /// the newest windows first.
+    pub fn update_local(&mut self, key: &K, trust_delta: V, update: Update) {
+        self.local_keys.insert(key.clone());
+        apply_update(&mut self.local_windows, key, trust_delta, update);
+        self.normalize_local();
+    }
+
+    /// Returns the raw (unnormalized) estimate for a given peer, summed
+    /// over all windows in the ring
+    pub fn get_raw_local(&self, key: &K) -> Option<V> {
+        Some(sum_estimates(&self.local_windows, key))
+    }
+
+    /// Returns the normalized estimate for a given peer
+    pub fn get_normalized_local(&self, key: &K) -> Option<V> {
+        Some(self.normalized_local_trust.estimate(key))
+    }
+
+    /// Initializes a global trust value for a newly discovered peer in
+    /// the current window, weighted by the sender's normalized local
+    /// trust
+    pub fn init_global(&mut self, sender: &K, key: &K, init_value: V) {
+        self.update_global(sender, key, init_value, Update::Increment);
+    }
+
+    /// Updates a global trust value for a given peer, weighted by the
+    /// sender's normalized local trust
+    pub fn update_global(
+        &mut self,
+        sender: &K,
+        key: &K,
+        trust_delta: V,
+        update: Update
+    ) {
+        let sender_trust = self.normalized_local_trust.estimate(sender);
+        let weighted_delta = trust_delta * sender_trust;
+        self.global_keys.insert(key.clone());
+        apply_update(&mut self.global_windows, key, weighted_delta, update);
+        self.normalize_global();
+    }
+
+    /// Returns the raw (unnormalized) global estimate for a given peer,
+    /// summed over all windows in the ring
+    pub fn get_raw_global(&self, key: &K) -> Option<V> {
+        Some(sum_estimates(&self.global_windows, key))
+    }
+
+    /// Returns the normalized global estimate for a given peer
+    pub fn get_normalized_global(&self, key: &K) -> Option<V> {
+        Some(self.normalized_global_trust.estimate(key))
+    }
+
+    /// Returns the keys of every peer that has been initialized or
+    /// updated in the local trust windows.
+    pub fn local_keys(&self) -> Vec<K> {
+        self.local_keys.iter().cloned().collect()
+    }
+
+    /// Returns the keys of every peer that has been initialized or
+    /// updated in the global trust windows.
+    pub fn global_keys(&self) -> Vec<K> {
+        self.global_keys.iter().cloned().collect()
+    }
+
+    /// normalizes the cell-wise sum of all local windows into the
+    /// normalized local sketch
+    fn normalize_local(&mut self) {
+        let summed = sum_windows(&self.template, &self.local_windows);
+        self.normalized_local_trust.set_matrix(summed.normalize_estimates());
+    }
+
+    /// normalizes the cell-wise sum of all global windows into the
+    /// normalized global sketch
+    fn normalize_global(&mut self) {
+        let summed = sum_windows(&self.template, &self.global_windows);
+        self.normalized_global_trust.set_matrix(summed.normalize_estimates());
+    }
+}
+
+/// Increments the newest window, or decrements the windows from
+/// newest to oldest until the delta has been consumed.
+fn apply_update<K, V>(
+    windows: &mut VecDeque<CountMinSketch<V>>,
+    key: &K,
+    trust_delta: V,
+    update: Update
+)
+where
+    K: Hash + ToString,
+    V: AddAssign
+    + SubAssign
+    + DivAssign
+    + Add<Output = V>
+    + Copy
+    + Default
+    + Bounded
+    + Ord
+    + Hash
+    + Debug
+{
+    match update {
+        Update::Increment => {
+            if let Some(current) = windows.back_mut() {
+                current.increment(key, trust_delta);
+            }
+        },
+        Update::Decrement => {
+            let mut remaining = trust_delta;
+            for window in windows.iter_mut().rev() {
+                if remaining == V::default() {
+                    break;
+                }
+
+                let taken = std::cmp::min(window.estimate(key), remaining);
+                window.decrement(key, taken);
+                remaining -= taken;
+            }
+        }
+    }
+}
+
+/// Sums the estimates for `key` in every window
+fn sum_estimates<K, V>(windows: &VecDeque<CountMinSketch<V>>, key: &K) -> V
+where
+    K: Hash + ToString,
+    V: AddAssign
+    + SubAssign
+    + DivAssign
+    + Add<Output = V>
+    + Copy
+    + Default
+    + Bounded
+    + Ord
+    + Hash
+    + Debug
+{
+    windows.iter().fold(V::default(), |acc, window| acc + window.estimate(key))
+}
+
+/// Builds a sketch holding the cell-wise sum of every window
+fn sum_windows<V>(
+    template: &CountMinSketch<V>,
+    windows: &VecDeque<CountMinSketch<V>>
+) -> CountMinSketch<V>
+where
+    V: AddAssign
+    + SubAssign
+    + DivAssign
+    + Add<Output = V>
+    + Copy
+    + Default
+    + Bounded
+    + Ord
+    + Hash
+    + Debug
+{
+    let mut matrix = template.matrix().clone();
+    windows.iter().for_each(|window| {
+        matrix.iter_mut()
+            .zip(window.matrix().iter())
+            .for_each(|(row, window_row)| {
+                row.iter_mut().zip(window_row.iter()).for_each(|(v, w)| {
+                    *v += *w;
+                });
+            });
+    });
+
+    let mut summed = template.clone();
+    summed.set_matrix(matrix);
+    summed
+}